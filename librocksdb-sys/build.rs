@@ -34,6 +34,8 @@ fn rocksdb_include_dir() -> String {
 fn bindgen_rocksdb() {
     let bindings = bindgen::Builder::default()
         .header(rocksdb_include_dir() + "/rocksdb/c.h")
+        .header("ext/rust_rocksdb.h")
+        .clang_arg(format!("-I{}", rocksdb_include_dir()))
         .derive_debug(false)
         .blocklist_type("max_align_t") // https://github.com/rust-lang-nursery/rust-bindgen/issues/550
         .ctypes_prefix("libc")
@@ -311,6 +313,41 @@ fn build_rocksdb() {
     config.compile("librocksdb.a");
 }
 
+/// Builds the C++ extensions in `ext/` which expose functionality that the
+/// upstream C API (`rocksdb/c.h`) does not cover.
+fn build_ext() {
+    let target = env::var("TARGET").unwrap();
+
+    let mut config = cc::Build::new();
+    config.include(rocksdb_include_dir());
+    config.include("ext/");
+    config.define("NDEBUG", Some("1"));
+
+    if target.contains("msvc") {
+        if cfg!(feature = "mt_static") {
+            config.static_crt(true);
+        }
+        config.flag("-EHsc");
+        config.flag("-std:c++17");
+    } else {
+        config.flag(cxx_standard());
+        config.flag("-Wno-unused-parameter");
+    }
+
+    let mut ext_sources = fs::read_dir("ext")
+        .expect("unable to read the `ext` directory")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "cc"))
+        .collect::<Vec<_>>();
+    ext_sources.sort();
+    for file in ext_sources {
+        config.file(file);
+    }
+
+    config.cpp(true);
+    config.compile("librocksdb_ext.a");
+}
+
 fn build_snappy() {
     let target = env::var("TARGET").unwrap();
     let endianness = env::var("CARGO_CFG_TARGET_ENDIAN").unwrap();
@@ -417,6 +454,11 @@ fn main() {
     bindgen_rocksdb();
     let target = env::var("TARGET").unwrap();
 
+    // The extensions have to be linked before rocksdb itself, so they are
+    // built first regardless of where rocksdb comes from.
+    println!("cargo:rerun-if-changed=ext/");
+    build_ext();
+
     if !try_to_find_and_link_lib("ROCKSDB") {
         // rocksdb only works with the prebuilt rocksdb system lib on freebsd.
         // we don't need to rebuild rocksdb
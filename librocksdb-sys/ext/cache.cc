@@ -0,0 +1,196 @@
+#include <atomic>
+#include <cstddef>
+#include <cstring>
+#include <functional>
+#include <memory>
+
+#include "rocksdb/advanced_cache.h"
+#include "rocksdb/cache.h"
+#include "rocksdb/memory_allocator.h"
+#include "rocksdb/secondary_cache.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::Cache;
+using ROCKSDB_NAMESPACE::CacheEntryRole;
+using ROCKSDB_NAMESPACE::CacheTier;
+using ROCKSDB_NAMESPACE::CompressedSecondaryCacheOptions;
+using ROCKSDB_NAMESPACE::CompressionType;
+using ROCKSDB_NAMESPACE::HyperClockCacheOptions;
+using ROCKSDB_NAMESPACE::MemoryAllocator;
+using ROCKSDB_NAMESPACE::SecondaryCache;
+using ROCKSDB_NAMESPACE::SecondaryCacheWrapper;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+
+namespace {
+
+// Set while the secondary cache stores an entry, the blocks it decompresses
+// on a lookup are promoted to the primary cache and charged there.
+thread_local bool tl_storing = false;
+
+class StoringScope {
+ public:
+  StoringScope() : prev_(tl_storing) { tl_storing = true; }
+  ~StoringScope() { tl_storing = prev_; }
+
+ private:
+  bool prev_;
+};
+
+// Keeps track of the bytes the secondary cache allocates to store its
+// entries, as it doesn't report its usage. The memory comes from the
+// allocator of the options, or from `new` as done by RocksDB without one.
+class CountingAllocator : public MemoryAllocator {
+ public:
+  explicit CountingAllocator(std::shared_ptr<MemoryAllocator> target)
+      : target_(std::move(target)) {}
+
+  const char* Name() const override { return "RustCountingAllocator"; }
+
+  void* Allocate(size_t size) override {
+    char* block =
+        target_ != nullptr
+            ? static_cast<char*>(target_->Allocate(kHeaderSize + size))
+            : new char[kHeaderSize + size];
+    size_t counted = tl_storing ? size : 0;
+    memcpy(block, &counted, sizeof(counted));
+    usage_.fetch_add(counted, std::memory_order_relaxed);
+    return block + kHeaderSize;
+  }
+
+  void Deallocate(void* p) override {
+    char* block = static_cast<char*>(p) - kHeaderSize;
+    size_t counted;
+    memcpy(&counted, block, sizeof(counted));
+    usage_.fetch_sub(counted, std::memory_order_relaxed);
+    if (target_ != nullptr) {
+      target_->Deallocate(block);
+    } else {
+      delete[] block;
+    }
+  }
+
+  size_t usage() const { return usage_.load(std::memory_order_relaxed); }
+
+ private:
+  // keeps the returned blocks aligned
+  static constexpr size_t kHeaderSize = alignof(std::max_align_t);
+
+  std::shared_ptr<MemoryAllocator> target_;
+  std::atomic<size_t> usage_{0};
+};
+
+// Marks the allocations made while storing entries for CountingAllocator.
+class CountingSecondaryCache : public SecondaryCacheWrapper {
+ public:
+  using SecondaryCacheWrapper::SecondaryCacheWrapper;
+
+  const char* Name() const override { return "RustCountingSecondaryCache"; }
+
+  Status Insert(const Slice& key, Cache::ObjectPtr obj,
+                const Cache::CacheItemHelper* helper,
+                bool force_insert) override {
+    StoringScope scope;
+    return target()->Insert(key, obj, helper, force_insert);
+  }
+
+  Status InsertSaved(const Slice& key, const Slice& saved,
+                     CompressionType type, CacheTier source) override {
+    StoringScope scope;
+    return target()->InsertSaved(key, saved, type, source);
+  }
+};
+
+}  // namespace
+
+struct rust_rocksdb_compressed_secondary_cache_t {
+  std::shared_ptr<SecondaryCache> rep;
+  std::shared_ptr<CountingAllocator> allocator;
+};
+
+extern "C" {
+
+rust_rocksdb_compressed_secondary_cache_t*
+rust_rocksdb_compressed_secondary_cache_create(
+    size_t capacity, int num_shard_bits, unsigned char strict_capacity_limit,
+    int compression_type, uint32_t compress_format_version) {
+  CompressedSecondaryCacheOptions opts;
+  auto allocator = std::make_shared<CountingAllocator>(opts.memory_allocator);
+  opts.capacity = capacity;
+  opts.num_shard_bits = num_shard_bits;
+  opts.strict_capacity_limit = strict_capacity_limit != 0;
+  opts.compression_type = static_cast<CompressionType>(compression_type);
+  opts.compress_format_version = compress_format_version;
+  opts.memory_allocator = allocator;
+  return new rust_rocksdb_compressed_secondary_cache_t{
+      std::make_shared<CountingSecondaryCache>(
+          opts.MakeSharedSecondaryCache()),
+      std::move(allocator)};
+}
+
+void rust_rocksdb_compressed_secondary_cache_destroy(
+    rust_rocksdb_compressed_secondary_cache_t* cache) {
+  delete cache;
+}
+
+size_t rust_rocksdb_compressed_secondary_cache_get_capacity(
+    rust_rocksdb_compressed_secondary_cache_t* cache) {
+  size_t capacity = 0;
+  cache->rep->GetCapacity(capacity).PermitUncheckedError();
+  return capacity;
+}
+
+void rust_rocksdb_compressed_secondary_cache_set_capacity(
+    rust_rocksdb_compressed_secondary_cache_t* cache, size_t capacity) {
+  cache->rep->SetCapacity(capacity).PermitUncheckedError();
+}
+
+size_t rust_rocksdb_compressed_secondary_cache_get_usage(
+    rust_rocksdb_compressed_secondary_cache_t* cache) {
+  return cache->allocator->usage();
+}
+
+void rust_rocksdb_lru_cache_options_set_secondary_cache(
+    rocksdb_lru_cache_options_t* opt,
+    rust_rocksdb_compressed_secondary_cache_t* secondary) {
+  opt->rep.secondary_cache = secondary->rep;
+}
+
+rocksdb_cache_t* rust_rocksdb_cache_create_hyper_clock_with_secondary(
+    size_t capacity, size_t estimated_entry_charge,
+    rust_rocksdb_compressed_secondary_cache_t* secondary) {
+  HyperClockCacheOptions opts(capacity, estimated_entry_charge);
+  opts.secondary_cache = secondary->rep;
+  return new rocksdb_cache_t{opts.MakeSharedCache()};
+}
+
+void rust_rocksdb_cache_get_entry_stats(rocksdb_cache_t* cache,
+                                        size_t* charges, size_t* counts,
+                                        size_t num_roles) {
+  static_assert(static_cast<size_t>(CacheEntryRole::kNumCacheEntryRoles) ==
+                    RUST_ROCKSDB_NUM_CACHE_ENTRY_ROLES,
+                "update CacheEntryRole of the Rust crate with the new roles");
+  for (size_t i = 0; i < num_roles; i++) {
+    charges[i] = 0;
+    counts[i] = 0;
+  }
+  cache->rep->ApplyToAllEntries(
+      [&](const Slice& /*key*/, Cache::ObjectPtr /*obj*/, size_t charge,
+          const Cache::CacheItemHelper* helper) {
+        CacheEntryRole role =
+            helper != nullptr ? helper->role : CacheEntryRole::kMisc;
+        size_t i = static_cast<size_t>(role);
+        if (i < num_roles) {
+          charges[i] += charge;
+          counts[i]++;
+        }
+      },
+      Cache::ApplyToAllEntriesOptions());
+}
+
+unsigned char rust_rocksdb_cache_has_strict_capacity_limit(
+    rocksdb_cache_t* cache) {
+  return cache->rep->HasStrictCapacityLimit();
+}
+
+}  // end extern "C"
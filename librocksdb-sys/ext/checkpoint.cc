@@ -0,0 +1,110 @@
+#include "rocksdb/metadata.h"
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::ColumnFamilyHandle;
+using ROCKSDB_NAMESPACE::ColumnFamilyOptions;
+using ROCKSDB_NAMESPACE::ExportImportFilesMetaData;
+using ROCKSDB_NAMESPACE::ImportColumnFamilyOptions;
+using ROCKSDB_NAMESPACE::LiveFileMetaData;
+using rust_rocksdb::SaveError;
+
+struct rust_rocksdb_export_import_files_metadata_t {
+  ExportImportFilesMetaData* rep;
+};
+
+struct rust_rocksdb_import_column_family_options_t {
+  ImportColumnFamilyOptions rep;
+};
+
+extern "C" {
+
+rust_rocksdb_export_import_files_metadata_t*
+rust_rocksdb_checkpoint_export_column_family(
+    rocksdb_checkpoint_t* checkpoint, rocksdb_column_family_handle_t* cf,
+    const char* export_dir, char** errptr) {
+  ExportImportFilesMetaData* metadata = nullptr;
+  if (SaveError(errptr, checkpoint->rep->ExportColumnFamily(
+                            cf->rep, std::string(export_dir), &metadata))) {
+    return nullptr;
+  }
+  auto* result = new rust_rocksdb_export_import_files_metadata_t;
+  result->rep = metadata;
+  return result;
+}
+
+void rust_rocksdb_export_import_files_metadata_destroy(
+    rust_rocksdb_export_import_files_metadata_t* metadata) {
+  delete metadata->rep;
+  delete metadata;
+}
+
+const char* rust_rocksdb_export_import_files_metadata_get_db_comparator_name(
+    const rust_rocksdb_export_import_files_metadata_t* metadata, size_t* len) {
+  *len = metadata->rep->db_comparator_name.size();
+  return metadata->rep->db_comparator_name.data();
+}
+
+size_t rust_rocksdb_export_import_files_metadata_get_file_count(
+    const rust_rocksdb_export_import_files_metadata_t* metadata) {
+  return metadata->rep->files.size();
+}
+
+void rust_rocksdb_export_import_files_metadata_get_file(
+    const rust_rocksdb_export_import_files_metadata_t* metadata, size_t index,
+    rust_rocksdb_live_file_info_t* info) {
+  const LiveFileMetaData& file = metadata->rep->files[index];
+  info->column_family_name = file.column_family_name.data();
+  info->column_family_name_len = file.column_family_name.size();
+  info->relative_filename = file.relative_filename.data();
+  info->relative_filename_len = file.relative_filename.size();
+  info->directory = file.directory.data();
+  info->directory_len = file.directory.size();
+  info->size = file.size;
+  info->level = file.level;
+  info->smallest_key = file.smallestkey.data();
+  info->smallest_key_len = file.smallestkey.size();
+  info->largest_key = file.largestkey.data();
+  info->largest_key_len = file.largestkey.size();
+  info->smallest_seqno = file.smallest_seqno;
+  info->largest_seqno = file.largest_seqno;
+  info->num_entries = file.num_entries;
+  info->num_deletions = file.num_deletions;
+  info->temperature = static_cast<int>(file.temperature);
+}
+
+rust_rocksdb_import_column_family_options_t*
+rust_rocksdb_import_column_family_options_create() {
+  return new rust_rocksdb_import_column_family_options_t;
+}
+
+void rust_rocksdb_import_column_family_options_destroy(
+    rust_rocksdb_import_column_family_options_t* opt) {
+  delete opt;
+}
+
+void rust_rocksdb_import_column_family_options_set_move_files(
+    rust_rocksdb_import_column_family_options_t* opt, unsigned char v) {
+  opt->rep.move_files = v;
+}
+
+rocksdb_column_family_handle_t* rust_rocksdb_create_column_family_with_import(
+    rocksdb_t* db, const rocksdb_options_t* column_family_options,
+    const char* column_family_name,
+    const rust_rocksdb_import_column_family_options_t* import_options,
+    const rust_rocksdb_export_import_files_metadata_t* metadata,
+    char** errptr) {
+  ColumnFamilyHandle* handle = nullptr;
+  if (SaveError(errptr, db->rep->CreateColumnFamilyWithImport(
+                            ColumnFamilyOptions(column_family_options->rep),
+                            std::string(column_family_name),
+                            import_options->rep, *metadata->rep, &handle))) {
+    return nullptr;
+  }
+  auto* result = new rocksdb_column_family_handle_t;
+  result->rep = handle;
+  result->immortal = false;
+  return result;
+}
+
+}  // end extern "C"
@@ -0,0 +1,62 @@
+#include <string>
+#include <vector>
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::CompactionOptions;
+using ROCKSDB_NAMESPACE::CompressionType;
+using rust_rocksdb::ColumnFamilyOrDefault;
+using rust_rocksdb::SaveError;
+
+struct rust_rocksdb_compactionoptions_t {
+  CompactionOptions rep;
+};
+
+extern "C" {
+
+rust_rocksdb_compactionoptions_t* rust_rocksdb_compactionoptions_create() {
+  return new rust_rocksdb_compactionoptions_t;
+}
+
+void rust_rocksdb_compactionoptions_destroy(
+    rust_rocksdb_compactionoptions_t* opt) {
+  delete opt;
+}
+
+void rust_rocksdb_compactionoptions_set_output_file_size_limit(
+    rust_rocksdb_compactionoptions_t* opt, uint64_t limit) {
+  opt->rep.output_file_size_limit = limit;
+}
+
+void rust_rocksdb_compactionoptions_set_compression(
+    rust_rocksdb_compactionoptions_t* opt, int compression) {
+  opt->rep.compression = static_cast<CompressionType>(compression);
+}
+
+void rust_rocksdb_compactionoptions_set_max_subcompactions(
+    rust_rocksdb_compactionoptions_t* opt, uint32_t max_subcompactions) {
+  opt->rep.max_subcompactions = max_subcompactions;
+}
+
+void rust_rocksdb_compact_files_cf(
+    rocksdb_t* db, const rust_rocksdb_compactionoptions_t* options,
+    rocksdb_column_family_handle_t* cf, size_t num_input_files,
+    const char* const* input_file_names, int output_level, void* state,
+    void (*output_file)(void*, const char* name, size_t namelen),
+    char** errptr) {
+  std::vector<std::string> input_files(input_file_names,
+                                       input_file_names + num_input_files);
+  std::vector<std::string> output_files;
+  if (SaveError(errptr,
+                db->rep->CompactFiles(options->rep,
+                                      ColumnFamilyOrDefault(db, cf),
+                                      input_files, output_level,
+                                      /*output_path_id=*/-1, &output_files))) {
+    return;
+  }
+  for (const auto& name : output_files) {
+    (*output_file)(state, name.data(), name.size());
+  }
+}
+
+}  // end extern "C"
@@ -0,0 +1,74 @@
+#include <algorithm>
+#include <exception>
+#include <new>
+#include <vector>
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::GetMergeOperandsOptions;
+using ROCKSDB_NAMESPACE::PinnableSlice;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+using rust_rocksdb::ColumnFamilyOrDefault;
+using rust_rocksdb::SaveError;
+
+namespace {
+
+// Number of operands room is first made for, RocksDB reports the actual
+// count when there are more.
+constexpr int kInitialMergeOperands = 16;
+
+}  // namespace
+
+extern "C" {
+
+void rust_rocksdb_get_merge_operands_cf(
+    rocksdb_t* db, const rocksdb_readoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t keylen,
+    int expected_max_operands, void* state,
+    void (*operand)(void*, const char* value, size_t vallen),
+    int* num_operands, char** errptr) {
+  *num_operands = 0;
+  if (expected_max_operands <= 0) {
+    SaveError(errptr, Status::InvalidArgument(
+                          "expected_max_operands must be positive"));
+    return;
+  }
+  try {
+    // RocksDB fills up to `expected_max_number_of_operands` slices, so the
+    // buffer only grows past its initial size to the actual count
+    int capacity = std::min(expected_max_operands, kInitialMergeOperands);
+    while (true) {
+      std::vector<PinnableSlice> values(capacity);
+      GetMergeOperandsOptions merge_operands_options;
+      merge_operands_options.expected_max_number_of_operands = capacity;
+      Status s = db->rep->GetMergeOperands(
+          options->rep, ColumnFamilyOrDefault(db, cf), Slice(key, keylen),
+          values.data(), &merge_operands_options, num_operands);
+      if (s.IsIncomplete() && *num_operands > capacity &&
+          *num_operands <= expected_max_operands) {
+        capacity = *num_operands;
+        continue;
+      }
+      if (s.IsNotFound()) {
+        *num_operands = 0;
+        return;
+      }
+      if (SaveError(errptr, s)) {
+        return;
+      }
+      for (int i = 0; i < *num_operands; i++) {
+        (*operand)(state, values[i].data(), values[i].size());
+      }
+      return;
+    }
+  } catch (const std::bad_alloc&) {
+    *num_operands = 0;
+    SaveError(errptr, Status::MemoryLimit());
+  } catch (const std::exception& e) {
+    *num_operands = 0;
+    SaveError(errptr, Status::Aborted(e.what()));
+  }
+}
+
+}  // end extern "C"
@@ -0,0 +1,119 @@
+#include <memory>
+#include <string>
+
+#include "rocksdb/env_encryption.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::BlockAccessCipherStream;
+using ROCKSDB_NAMESPACE::EncryptionProvider;
+using ROCKSDB_NAMESPACE::EnvOptions;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+
+namespace {
+
+class RustCipherStream : public BlockAccessCipherStream {
+ public:
+  RustCipherStream(void* state,
+                   const rust_rocksdb_encryption_provider_callbacks_t& cbs)
+      : state_(state), callbacks_(cbs) {}
+
+  ~RustCipherStream() override { callbacks_.cipher_stream_destroy(state_); }
+
+  // Encrypt and Decrypt handle arbitrary ranges, the block based helpers
+  // below are never used.
+  size_t BlockSize() override { return 1; }
+
+  Status Encrypt(uint64_t file_offset, char* data, size_t size) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.cipher_stream_encrypt(state_, file_offset, data, size, &err);
+    return err.status;
+  }
+
+  Status Decrypt(uint64_t file_offset, char* data, size_t size) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.cipher_stream_decrypt(state_, file_offset, data, size, &err);
+    return err.status;
+  }
+
+ protected:
+  void AllocateScratch(std::string& /*scratch*/) override {}
+
+  Status EncryptBlock(uint64_t /*block_index*/, char* /*data*/,
+                      char* /*scratch*/) override {
+    return Status::NotSupported();
+  }
+
+  Status DecryptBlock(uint64_t /*block_index*/, char* /*data*/,
+                      char* /*scratch*/) override {
+    return Status::NotSupported();
+  }
+
+ private:
+  void* state_;
+  rust_rocksdb_encryption_provider_callbacks_t callbacks_;
+};
+
+class RustEncryptionProvider : public EncryptionProvider {
+ public:
+  RustEncryptionProvider(
+      void* state, void (*destructor)(void*),
+      const rust_rocksdb_encryption_provider_callbacks_t& callbacks)
+      : state_(state), destructor_(destructor), callbacks_(callbacks) {}
+
+  ~RustEncryptionProvider() override { destructor_(state_); }
+
+  const char* Name() const override { return "RustEncryptionProvider"; }
+
+  size_t GetPrefixLength() const override {
+    return callbacks_.prefix_length(state_);
+  }
+
+  Status CreateNewPrefix(const std::string& fname, char* prefix,
+                         size_t prefix_length) const override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.create_new_prefix(state_, fname.data(), fname.size(), prefix,
+                                 prefix_length, &err);
+    return err.status;
+  }
+
+  Status AddCipher(const std::string& /*descriptor*/, const char* /*cipher*/,
+                   size_t /*len*/, bool /*for_write*/) override {
+    return Status::NotSupported("ciphers are managed by the Rust provider");
+  }
+
+  Status CreateCipherStream(
+      const std::string& fname, const EnvOptions& /*options*/, Slice& prefix,
+      std::unique_ptr<BlockAccessCipherStream>* result) override {
+    rust_rocksdb_fs_error_t err;
+    void* stream =
+        callbacks_.create_cipher_stream(state_, fname.data(), fname.size(),
+                                        prefix.data(), prefix.size(), &err);
+    if (err.status.ok()) {
+      result->reset(new RustCipherStream(stream, callbacks_));
+    }
+    return err.status;
+  }
+
+ private:
+  void* state_;
+  void (*destructor_)(void*);
+  rust_rocksdb_encryption_provider_callbacks_t callbacks_;
+};
+
+}  // namespace
+
+extern "C" {
+
+rocksdb_env_t* rust_rocksdb_create_encrypted_env(
+    rocksdb_env_t* base, void* state, void (*destructor)(void*),
+    const rust_rocksdb_encryption_provider_callbacks_t* callbacks) {
+  auto provider =
+      std::make_shared<RustEncryptionProvider>(state, destructor, *callbacks);
+  auto* result = new rocksdb_env_t;
+  result->rep = ROCKSDB_NAMESPACE::NewEncryptedEnv(base->rep, provider);
+  result->is_default = false;
+  return result;
+}
+
+}  // end extern "C"
@@ -0,0 +1,237 @@
+#include <memory>
+#include <string>
+#include <vector>
+
+#include "rocksdb/listener.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::BackgroundErrorReason;
+using ROCKSDB_NAMESPACE::BackgroundErrorRecoveryInfo;
+using ROCKSDB_NAMESPACE::CompactionJobInfo;
+using ROCKSDB_NAMESPACE::DB;
+using ROCKSDB_NAMESPACE::EventListener;
+using ROCKSDB_NAMESPACE::FlushJobInfo;
+using ROCKSDB_NAMESPACE::Status;
+using ROCKSDB_NAMESPACE::TableFileCreationInfo;
+using ROCKSDB_NAMESPACE::TableFileDeletionInfo;
+using ROCKSDB_NAMESPACE::WriteStallCondition;
+using ROCKSDB_NAMESPACE::WriteStallInfo;
+
+namespace {
+
+// Holds the message of a non-OK status for the duration of a callback.
+class StatusMessage {
+ public:
+  explicit StatusMessage(const Status& s) : ok_(s.ok()) {
+    if (!ok_) {
+      message_ = s.ToString();
+    }
+  }
+
+  const char* get() const { return ok_ ? nullptr : message_.c_str(); }
+
+ private:
+  bool ok_;
+  std::string message_;
+};
+
+// Pointer and length arrays over a list of strings.
+struct StringList {
+  explicit StringList(const std::vector<std::string>& strings) {
+    ptrs.reserve(strings.size());
+    lens.reserve(strings.size());
+    for (const auto& s : strings) {
+      ptrs.push_back(s.data());
+      lens.push_back(s.size());
+    }
+  }
+
+  std::vector<const char*> ptrs;
+  std::vector<size_t> lens;
+};
+
+int ConvertWriteStallCondition(WriteStallCondition condition) {
+  switch (condition) {
+    case WriteStallCondition::kDelayed:
+      return 1;
+    case WriteStallCondition::kStopped:
+      return 2;
+    case WriteStallCondition::kNormal:
+      return 0;
+    default:
+      return 3 + static_cast<int>(condition);
+  }
+}
+
+void FillFlushJobInfo(const FlushJobInfo& info,
+                      rust_rocksdb_flush_job_info_t* out) {
+  out->cf_id = info.cf_id;
+  out->cf_name = info.cf_name.data();
+  out->cf_name_len = info.cf_name.size();
+  out->file_path = info.file_path.data();
+  out->file_path_len = info.file_path.size();
+  out->file_number = info.file_number;
+  out->thread_id = info.thread_id;
+  out->job_id = info.job_id;
+  out->triggered_writes_slowdown = info.triggered_writes_slowdown;
+  out->triggered_writes_stop = info.triggered_writes_stop;
+  out->smallest_seqno = info.smallest_seqno;
+  out->largest_seqno = info.largest_seqno;
+  out->flush_reason = static_cast<int>(info.flush_reason);
+}
+
+class RustEventListener : public EventListener {
+ public:
+  RustEventListener(void* state, void (*destructor)(void*),
+                    const rust_rocksdb_eventlistener_callbacks_t& callbacks)
+      : state_(state), destructor_(destructor), callbacks_(callbacks) {}
+
+  ~RustEventListener() override { destructor_(state_); }
+
+  const char* Name() const override { return "RustEventListener"; }
+
+  void OnFlushBegin(DB* /*db*/, const FlushJobInfo& info) override {
+    if (callbacks_.on_flush_begin != nullptr) {
+      rust_rocksdb_flush_job_info_t c_info;
+      FillFlushJobInfo(info, &c_info);
+      callbacks_.on_flush_begin(state_, &c_info);
+    }
+  }
+
+  void OnFlushCompleted(DB* /*db*/, const FlushJobInfo& info) override {
+    if (callbacks_.on_flush_completed != nullptr) {
+      rust_rocksdb_flush_job_info_t c_info;
+      FillFlushJobInfo(info, &c_info);
+      callbacks_.on_flush_completed(state_, &c_info);
+    }
+  }
+
+  void OnCompactionBegin(DB* /*db*/, const CompactionJobInfo& info) override {
+    if (callbacks_.on_compaction_begin != nullptr) {
+      CallCompaction(callbacks_.on_compaction_begin, info);
+    }
+  }
+
+  void OnCompactionCompleted(DB* /*db*/,
+                             const CompactionJobInfo& info) override {
+    if (callbacks_.on_compaction_completed != nullptr) {
+      CallCompaction(callbacks_.on_compaction_completed, info);
+    }
+  }
+
+  void OnTableFileCreated(const TableFileCreationInfo& info) override {
+    if (callbacks_.on_table_file_created == nullptr) {
+      return;
+    }
+    StatusMessage status(info.status);
+    rust_rocksdb_table_file_creation_info_t c_info;
+    c_info.db_name = info.db_name.data();
+    c_info.db_name_len = info.db_name.size();
+    c_info.cf_name = info.cf_name.data();
+    c_info.cf_name_len = info.cf_name.size();
+    c_info.file_path = info.file_path.data();
+    c_info.file_path_len = info.file_path.size();
+    c_info.job_id = info.job_id;
+    c_info.reason = static_cast<int>(info.reason);
+    c_info.file_size = info.file_size;
+    c_info.status = status.get();
+    callbacks_.on_table_file_created(state_, &c_info);
+  }
+
+  void OnTableFileDeleted(const TableFileDeletionInfo& info) override {
+    if (callbacks_.on_table_file_deleted == nullptr) {
+      return;
+    }
+    StatusMessage status(info.status);
+    rust_rocksdb_table_file_deletion_info_t c_info;
+    c_info.db_name = info.db_name.data();
+    c_info.db_name_len = info.db_name.size();
+    c_info.file_path = info.file_path.data();
+    c_info.file_path_len = info.file_path.size();
+    c_info.job_id = info.job_id;
+    c_info.status = status.get();
+    callbacks_.on_table_file_deleted(state_, &c_info);
+  }
+
+  void OnStallConditionsChanged(const WriteStallInfo& info) override {
+    if (callbacks_.on_stall_conditions_changed == nullptr) {
+      return;
+    }
+    rust_rocksdb_write_stall_info_t c_info;
+    c_info.cf_name = info.cf_name.data();
+    c_info.cf_name_len = info.cf_name.size();
+    c_info.cur = ConvertWriteStallCondition(info.condition.cur);
+    c_info.prev = ConvertWriteStallCondition(info.condition.prev);
+    callbacks_.on_stall_conditions_changed(state_, &c_info);
+  }
+
+  void OnBackgroundError(BackgroundErrorReason reason,
+                         Status* bg_error) override {
+    if (callbacks_.on_background_error == nullptr || bg_error == nullptr) {
+      return;
+    }
+    StatusMessage status(*bg_error);
+    callbacks_.on_background_error(state_, static_cast<int>(reason),
+                                   status.get());
+  }
+
+  void OnErrorRecoveryEnd(const BackgroundErrorRecoveryInfo& info) override {
+    if (callbacks_.on_error_recovery_end == nullptr) {
+      return;
+    }
+    StatusMessage old_bg_error(info.old_bg_error);
+    StatusMessage new_bg_error(info.new_bg_error);
+    rust_rocksdb_error_recovery_info_t c_info;
+    c_info.old_bg_error = old_bg_error.get();
+    c_info.new_bg_error = new_bg_error.get();
+    callbacks_.on_error_recovery_end(state_, &c_info);
+  }
+
+ private:
+  void CallCompaction(void (*callback)(void*,
+                                       const rust_rocksdb_compaction_job_info_t*),
+                      const CompactionJobInfo& info) {
+    StatusMessage status(info.status);
+    StringList input_files(info.input_files);
+    StringList output_files(info.output_files);
+    rust_rocksdb_compaction_job_info_t c_info;
+    c_info.cf_id = info.cf_id;
+    c_info.cf_name = info.cf_name.data();
+    c_info.cf_name_len = info.cf_name.size();
+    c_info.status = status.get();
+    c_info.thread_id = info.thread_id;
+    c_info.job_id = info.job_id;
+    c_info.base_input_level = info.base_input_level;
+    c_info.output_level = info.output_level;
+    c_info.num_input_files = input_files.ptrs.size();
+    c_info.input_files = input_files.ptrs.data();
+    c_info.input_files_lens = input_files.lens.data();
+    c_info.num_output_files = output_files.ptrs.size();
+    c_info.output_files = output_files.ptrs.data();
+    c_info.output_files_lens = output_files.lens.data();
+    c_info.compaction_reason = static_cast<int>(info.compaction_reason);
+    c_info.elapsed_micros = info.stats.elapsed_micros;
+    c_info.num_input_records = info.stats.num_input_records;
+    c_info.num_output_records = info.stats.num_output_records;
+    c_info.total_input_bytes = info.stats.total_input_bytes;
+    c_info.total_output_bytes = info.stats.total_output_bytes;
+    callback(state_, &c_info);
+  }
+
+  void* state_;
+  void (*destructor_)(void*);
+  rust_rocksdb_eventlistener_callbacks_t callbacks_;
+};
+
+}  // namespace
+
+extern "C" {
+
+void rust_rocksdb_options_add_eventlistener(
+    rocksdb_options_t* opt, void* state, void (*destructor)(void*),
+    const rust_rocksdb_eventlistener_callbacks_t* callbacks) {
+  opt->rep.listeners.push_back(
+      std::make_shared<RustEventListener>(state, destructor, *callbacks));
+}
+
+}  // end extern "C"
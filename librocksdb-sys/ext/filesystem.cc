@@ -0,0 +1,913 @@
+#include <cstdarg>
+#include <cstdio>
+#include <memory>
+#include <mutex>
+#include <string>
+#include <vector>
+
+#include "rocksdb/file_system.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::FileLock;
+using ROCKSDB_NAMESPACE::FileOptions;
+using ROCKSDB_NAMESPACE::FileSystem;
+using ROCKSDB_NAMESPACE::FSDirectory;
+using ROCKSDB_NAMESPACE::FSRandomAccessFile;
+using ROCKSDB_NAMESPACE::FSSequentialFile;
+using ROCKSDB_NAMESPACE::FSWritableFile;
+using ROCKSDB_NAMESPACE::IODebugContext;
+using ROCKSDB_NAMESPACE::IOOptions;
+using ROCKSDB_NAMESPACE::IOStatus;
+using ROCKSDB_NAMESPACE::kDefaultPageSize;
+using ROCKSDB_NAMESPACE::Logger;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+using ROCKSDB_NAMESPACE::Temperature;
+using rust_rocksdb::CopyString;
+using rust_rocksdb::SaveError;
+
+struct rust_rocksdb_filesystem_t {
+  std::shared_ptr<FileSystem> rep;
+};
+
+struct rust_rocksdb_fs_sequential_file_t {
+  std::shared_ptr<FileSystem> fs;
+  std::unique_ptr<FSSequentialFile> rep;
+};
+
+struct rust_rocksdb_fs_random_access_file_t {
+  std::shared_ptr<FileSystem> fs;
+  std::unique_ptr<FSRandomAccessFile> rep;
+};
+
+struct rust_rocksdb_fs_writable_file_t {
+  std::shared_ptr<FileSystem> fs;
+  std::unique_ptr<FSWritableFile> rep;
+};
+
+struct rust_rocksdb_fs_file_lock_t {
+  std::shared_ptr<FileSystem> fs;
+  FileLock* rep;
+};
+
+namespace {
+
+void PushString(void* out, const char* s, size_t len) {
+  static_cast<std::vector<std::string>*>(out)->emplace_back(s, len);
+}
+
+rust_rocksdb_fs_file_options_t FromFileOptions(const FileOptions& opts) {
+  rust_rocksdb_fs_file_options_t result;
+  result.use_mmap_reads = opts.use_mmap_reads;
+  result.use_mmap_writes = opts.use_mmap_writes;
+  result.use_direct_reads = opts.use_direct_reads;
+  result.use_direct_writes = opts.use_direct_writes;
+  result.allow_fallocate = opts.allow_fallocate;
+  result.fallocate_with_keep_size = opts.fallocate_with_keep_size;
+  result.set_fd_cloexec = opts.set_fd_cloexec;
+  result.strict_bytes_per_sync = opts.strict_bytes_per_sync;
+  result.bytes_per_sync = opts.bytes_per_sync;
+  result.io_priority = static_cast<int>(opts.io_options.rate_limiter_priority);
+  result.temperature = static_cast<int>(opts.temperature);
+  return result;
+}
+
+FileOptions ToFileOptions(const rust_rocksdb_fs_file_options_t& opts) {
+  FileOptions result;
+  result.use_mmap_reads = opts.use_mmap_reads;
+  result.use_mmap_writes = opts.use_mmap_writes;
+  result.use_direct_reads = opts.use_direct_reads;
+  result.use_direct_writes = opts.use_direct_writes;
+  result.allow_fallocate = opts.allow_fallocate;
+  result.fallocate_with_keep_size = opts.fallocate_with_keep_size;
+  result.set_fd_cloexec = opts.set_fd_cloexec;
+  result.strict_bytes_per_sync = opts.strict_bytes_per_sync;
+  result.bytes_per_sync = opts.bytes_per_sync;
+  result.io_options.rate_limiter_priority =
+      static_cast<ROCKSDB_NAMESPACE::Env::IOPriority>(opts.io_priority);
+  result.temperature =
+      static_cast<Temperature>(static_cast<uint8_t>(opts.temperature));
+  return result;
+}
+
+// How a file opened through the callbacks does its I/O.
+struct RustFileInfo {
+  unsigned char use_direct_io = 0;
+  size_t alignment = kDefaultPageSize;
+};
+
+// Writes the info log through a file of the file system that created it.
+class RustFileSystemLogger : public Logger {
+ public:
+  explicit RustFileSystemLogger(std::unique_ptr<FSWritableFile> file)
+      : file_(std::move(file)) {}
+
+  ~RustFileSystemLogger() override {
+    if (!closed_) {
+      CloseHelper().PermitUncheckedError();
+    }
+  }
+
+  using Logger::Logv;
+  void Logv(const char* format, va_list ap) override {
+    char stack_buf[512];
+    std::string heap_buf;
+    va_list ap_copy;
+    va_copy(ap_copy, ap);
+    int len = vsnprintf(stack_buf, sizeof(stack_buf), format, ap);
+    if (len < 0) {
+      va_end(ap_copy);
+      return;
+    }
+    Slice line(stack_buf, len);
+    if (static_cast<size_t>(len) >= sizeof(stack_buf)) {
+      heap_buf.resize(len + 1);
+      vsnprintf(&heap_buf[0], heap_buf.size(), format, ap_copy);
+      heap_buf.resize(len);
+      line = heap_buf;
+    }
+    va_end(ap_copy);
+
+    std::lock_guard<std::mutex> lock(mutex_);
+    if (closed_) {
+      return;
+    }
+    file_->Append(line, IOOptions(), nullptr).PermitUncheckedError();
+    if (line.empty() || line[line.size() - 1] != '\n') {
+      file_->Append("\n", IOOptions(), nullptr).PermitUncheckedError();
+    }
+    file_->Flush(IOOptions(), nullptr).PermitUncheckedError();
+  }
+
+ protected:
+  Status CloseImpl() override { return CloseHelper(); }
+
+ private:
+  Status CloseHelper() {
+    std::lock_guard<std::mutex> lock(mutex_);
+    closed_ = true;
+    return file_->Close(IOOptions(), nullptr);
+  }
+
+  std::unique_ptr<FSWritableFile> file_;
+  std::mutex mutex_;
+  bool closed_ = false;
+};
+
+class RustSequentialFile : public FSSequentialFile {
+ public:
+  RustSequentialFile(void* state,
+                     const rust_rocksdb_filesystem_callbacks_t& callbacks,
+                     const RustFileInfo& info)
+      : state_(state), callbacks_(callbacks), info_(info) {}
+
+  ~RustSequentialFile() override {
+    callbacks_.sequential_file_destroy(state_);
+  }
+
+  IOStatus Read(size_t n, const IOOptions& /*options*/, Slice* result,
+                char* scratch, IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    size_t read = callbacks_.sequential_file_read(state_, scratch, n, &err);
+    *result = err.status.ok() ? Slice(scratch, read) : Slice();
+    return err.status;
+  }
+
+  IOStatus PositionedRead(uint64_t offset, size_t n,
+                          const IOOptions& /*options*/, Slice* result,
+                          char* scratch, IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    size_t read = callbacks_.sequential_file_positioned_read(
+        state_, offset, scratch, n, &err);
+    *result = err.status.ok() ? Slice(scratch, read) : Slice();
+    return err.status;
+  }
+
+  IOStatus Skip(uint64_t n) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.sequential_file_skip(state_, n, &err);
+    return err.status;
+  }
+
+  bool use_direct_io() const override { return info_.use_direct_io; }
+
+  size_t GetRequiredBufferAlignment() const override {
+    return info_.alignment;
+  }
+
+ private:
+  void* state_;
+  rust_rocksdb_filesystem_callbacks_t callbacks_;
+  RustFileInfo info_;
+};
+
+class RustRandomAccessFile : public FSRandomAccessFile {
+ public:
+  RustRandomAccessFile(void* state,
+                       const rust_rocksdb_filesystem_callbacks_t& callbacks,
+                       const RustFileInfo& info)
+      : state_(state), callbacks_(callbacks), info_(info) {}
+
+  ~RustRandomAccessFile() override {
+    callbacks_.random_access_file_destroy(state_);
+  }
+
+  IOStatus Read(uint64_t offset, size_t n, const IOOptions& /*options*/,
+                Slice* result, char* scratch,
+                IODebugContext* /*dbg*/) const override {
+    rust_rocksdb_fs_error_t err;
+    size_t read =
+        callbacks_.random_access_file_read(state_, offset, scratch, n, &err);
+    *result = err.status.ok() ? Slice(scratch, read) : Slice();
+    return err.status;
+  }
+
+  bool use_direct_io() const override { return info_.use_direct_io; }
+
+  size_t GetRequiredBufferAlignment() const override {
+    return info_.alignment;
+  }
+
+ private:
+  void* state_;
+  rust_rocksdb_filesystem_callbacks_t callbacks_;
+  RustFileInfo info_;
+};
+
+class RustWritableFile : public FSWritableFile {
+ public:
+  RustWritableFile(void* state,
+                   const rust_rocksdb_filesystem_callbacks_t& callbacks,
+                   const RustFileInfo& info)
+      : state_(state), callbacks_(callbacks), info_(info) {}
+
+  ~RustWritableFile() override { callbacks_.writable_file_destroy(state_); }
+
+  using FSWritableFile::Append;
+  IOStatus Append(const Slice& data, const IOOptions& /*options*/,
+                  IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.writable_file_append(state_, data.data(), data.size(), &err);
+    return err.status;
+  }
+
+  using FSWritableFile::PositionedAppend;
+  IOStatus PositionedAppend(const Slice& data, uint64_t offset,
+                            const IOOptions& /*options*/,
+                            IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.writable_file_positioned_append(state_, data.data(),
+                                               data.size(), offset, &err);
+    return err.status;
+  }
+
+  IOStatus Truncate(uint64_t size, const IOOptions& /*options*/,
+                    IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.writable_file_truncate(state_, size, &err);
+    return err.status;
+  }
+
+  IOStatus Flush(const IOOptions& /*options*/,
+                 IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.writable_file_flush(state_, &err);
+    return err.status;
+  }
+
+  IOStatus Sync(const IOOptions& /*options*/,
+                IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.writable_file_sync(state_, &err);
+    return err.status;
+  }
+
+  IOStatus Close(const IOOptions& /*options*/,
+                 IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.writable_file_close(state_, &err);
+    return err.status;
+  }
+
+  uint64_t GetFileSize(const IOOptions& /*options*/,
+                       IODebugContext* /*dbg*/) override {
+    return callbacks_.writable_file_get_size(state_);
+  }
+
+  bool use_direct_io() const override { return info_.use_direct_io; }
+
+  size_t GetRequiredBufferAlignment() const override {
+    return info_.alignment;
+  }
+
+ private:
+  void* state_;
+  rust_rocksdb_filesystem_callbacks_t callbacks_;
+  RustFileInfo info_;
+};
+
+class RustDirectory : public FSDirectory {
+ public:
+  RustDirectory(void* state,
+                const rust_rocksdb_filesystem_callbacks_t& callbacks,
+                std::string path)
+      : state_(state), callbacks_(callbacks), path_(std::move(path)) {}
+
+  IOStatus Fsync(const IOOptions& /*options*/,
+                 IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.sync_dir(state_, path_.data(), path_.size(), &err);
+    return err.status;
+  }
+
+  IOStatus Close(const IOOptions& /*options*/,
+                 IODebugContext* /*dbg*/) override {
+    return IOStatus::OK();
+  }
+
+ private:
+  void* state_;
+  rust_rocksdb_filesystem_callbacks_t callbacks_;
+  std::string path_;
+};
+
+class RustFileLock : public FileLock {
+ public:
+  explicit RustFileLock(void* state) : state(state) {}
+
+  void* state;
+};
+
+class RustFileSystem : public FileSystem {
+ public:
+  RustFileSystem(void* state, void (*destructor)(void*),
+                 const rust_rocksdb_filesystem_callbacks_t& callbacks)
+      : state_(state), destructor_(destructor), callbacks_(callbacks) {}
+
+  ~RustFileSystem() override { destructor_(state_); }
+
+  const char* Name() const override { return "RustFileSystem"; }
+
+  IOStatus NewSequentialFile(const std::string& fname,
+                             const FileOptions& file_opts,
+                             std::unique_ptr<FSSequentialFile>* result,
+                             IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    rust_rocksdb_fs_file_options_t opts = FromFileOptions(file_opts);
+    RustFileInfo info;
+    void* file = callbacks_.new_sequential_file(
+        state_, fname.data(), fname.size(), &opts, &info.use_direct_io,
+        &info.alignment, &err);
+    if (err.status.ok()) {
+      result->reset(new RustSequentialFile(file, callbacks_, info));
+    }
+    return err.status;
+  }
+
+  IOStatus NewRandomAccessFile(const std::string& fname,
+                               const FileOptions& file_opts,
+                               std::unique_ptr<FSRandomAccessFile>* result,
+                               IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    rust_rocksdb_fs_file_options_t opts = FromFileOptions(file_opts);
+    RustFileInfo info;
+    void* file = callbacks_.new_random_access_file(
+        state_, fname.data(), fname.size(), &opts, &info.use_direct_io,
+        &info.alignment, &err);
+    if (err.status.ok()) {
+      result->reset(new RustRandomAccessFile(file, callbacks_, info));
+    }
+    return err.status;
+  }
+
+  IOStatus NewWritableFile(const std::string& fname,
+                           const FileOptions& file_opts,
+                           std::unique_ptr<FSWritableFile>* result,
+                           IODebugContext* /*dbg*/) override {
+    return OpenWritableFile(fname, file_opts, false, result);
+  }
+
+  IOStatus ReopenWritableFile(const std::string& fname,
+                              const FileOptions& file_opts,
+                              std::unique_ptr<FSWritableFile>* result,
+                              IODebugContext* /*dbg*/) override {
+    return OpenWritableFile(fname, file_opts, true, result);
+  }
+
+  IOStatus NewDirectory(const std::string& name, const IOOptions& /*io_opts*/,
+                        std::unique_ptr<FSDirectory>* result,
+                        IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.open_dir(state_, name.data(), name.size(), &err);
+    if (err.status.ok()) {
+      result->reset(new RustDirectory(state_, callbacks_, name));
+    }
+    return err.status;
+  }
+
+  IOStatus FileExists(const std::string& fname, const IOOptions& /*options*/,
+                      IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    bool exists =
+        callbacks_.file_exists(state_, fname.data(), fname.size(), &err);
+    if (err.status.ok() && !exists) {
+      return IOStatus::NotFound(fname);
+    }
+    return err.status;
+  }
+
+  IOStatus GetChildren(const std::string& dir, const IOOptions& /*options*/,
+                       std::vector<std::string>* result,
+                       IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    result->clear();
+    callbacks_.get_children(state_, dir.data(), dir.size(), result,
+                            &PushString, &err);
+    return err.status;
+  }
+
+  IOStatus DeleteFile(const std::string& fname, const IOOptions& /*options*/,
+                      IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.delete_file(state_, fname.data(), fname.size(), &err);
+    return err.status;
+  }
+
+  IOStatus CreateDir(const std::string& dirname, const IOOptions& /*options*/,
+                     IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.create_dir(state_, dirname.data(), dirname.size(), false, &err);
+    return err.status;
+  }
+
+  IOStatus CreateDirIfMissing(const std::string& dirname,
+                              const IOOptions& /*options*/,
+                              IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.create_dir(state_, dirname.data(), dirname.size(), true, &err);
+    return err.status;
+  }
+
+  IOStatus DeleteDir(const std::string& dirname, const IOOptions& /*options*/,
+                     IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.delete_dir(state_, dirname.data(), dirname.size(), &err);
+    return err.status;
+  }
+
+  IOStatus GetFileSize(const std::string& fname, const IOOptions& /*options*/,
+                       uint64_t* file_size, IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    *file_size =
+        callbacks_.get_file_size(state_, fname.data(), fname.size(), &err);
+    return err.status;
+  }
+
+  IOStatus GetFileModificationTime(const std::string& fname,
+                                   const IOOptions& /*options*/,
+                                   uint64_t* file_mtime,
+                                   IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    *file_mtime = callbacks_.get_file_modification_time(state_, fname.data(),
+                                                        fname.size(), &err);
+    return err.status;
+  }
+
+  IOStatus RenameFile(const std::string& src, const std::string& target,
+                      const IOOptions& /*options*/,
+                      IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.rename_file(state_, src.data(), src.size(), target.data(),
+                           target.size(), &err);
+    return err.status;
+  }
+
+  IOStatus LinkFile(const std::string& src, const std::string& target,
+                    const IOOptions& /*options*/,
+                    IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    callbacks_.link_file(state_, src.data(), src.size(), target.data(),
+                         target.size(), &err);
+    return err.status;
+  }
+
+  IOStatus LockFile(const std::string& fname, const IOOptions& /*options*/,
+                    FileLock** lock, IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    void* state =
+        callbacks_.lock_file(state_, fname.data(), fname.size(), &err);
+    *lock = err.status.ok() ? new RustFileLock(state) : nullptr;
+    return err.status;
+  }
+
+  IOStatus UnlockFile(FileLock* lock, const IOOptions& /*options*/,
+                      IODebugContext* /*dbg*/) override {
+    auto* rust_lock = static_cast<RustFileLock*>(lock);
+    rust_rocksdb_fs_error_t err;
+    callbacks_.unlock_file(state_, rust_lock->state, &err);
+    delete rust_lock;
+    return err.status;
+  }
+
+  IOStatus GetTestDirectory(const IOOptions& options, std::string* path,
+                            IODebugContext* dbg) override {
+    return FileSystem::Default()->GetTestDirectory(options, path, dbg);
+  }
+
+  IOStatus NewLogger(const std::string& fname, const IOOptions& io_opts,
+                     std::shared_ptr<Logger>* result,
+                     IODebugContext* dbg) override {
+    std::unique_ptr<FSWritableFile> file;
+    IOStatus s = NewWritableFile(fname, FileOptions(io_opts), &file, dbg);
+    if (s.ok()) {
+      *result = std::make_shared<RustFileSystemLogger>(std::move(file));
+    }
+    return s;
+  }
+
+  IOStatus GetAbsolutePath(const std::string& db_path,
+                           const IOOptions& /*options*/,
+                           std::string* output_path,
+                           IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    std::vector<std::string> out;
+    callbacks_.get_absolute_path(state_, db_path.data(), db_path.size(), &out,
+                                 &PushString, &err);
+    if (err.status.ok()) {
+      *output_path = out.empty() ? db_path : out.front();
+    }
+    return err.status;
+  }
+
+  IOStatus IsDirectory(const std::string& path, const IOOptions& /*options*/,
+                       bool* is_dir, IODebugContext* /*dbg*/) override {
+    rust_rocksdb_fs_error_t err;
+    *is_dir = callbacks_.is_directory(state_, path.data(), path.size(), &err);
+    return err.status;
+  }
+
+ private:
+  IOStatus OpenWritableFile(const std::string& fname,
+                            const FileOptions& file_opts, bool reopen,
+                            std::unique_ptr<FSWritableFile>* result) {
+    rust_rocksdb_fs_error_t err;
+    rust_rocksdb_fs_file_options_t opts = FromFileOptions(file_opts);
+    RustFileInfo info;
+    void* file = callbacks_.new_writable_file(
+        state_, fname.data(), fname.size(), &opts, reopen,
+        &info.use_direct_io, &info.alignment, &err);
+    if (err.status.ok()) {
+      result->reset(new RustWritableFile(file, callbacks_, info));
+    }
+    return err.status;
+  }
+
+  void* state_;
+  void (*destructor_)(void*);
+  rust_rocksdb_filesystem_callbacks_t callbacks_;
+};
+
+}  // namespace
+
+extern "C" {
+
+rust_rocksdb_filesystem_t* rust_rocksdb_filesystem_create_default() {
+  return new rust_rocksdb_filesystem_t{FileSystem::Default()};
+}
+
+rust_rocksdb_filesystem_t* rust_rocksdb_env_get_filesystem(
+    rocksdb_env_t* env) {
+  return new rust_rocksdb_filesystem_t{env->rep->GetFileSystem()};
+}
+
+void rust_rocksdb_filesystem_destroy(rust_rocksdb_filesystem_t* fs) {
+  delete fs;
+}
+
+void rust_rocksdb_fs_file_options_init(rust_rocksdb_fs_file_options_t* opts) {
+  *opts = FromFileOptions(FileOptions());
+}
+
+rust_rocksdb_fs_sequential_file_t* rust_rocksdb_filesystem_new_sequential_file(
+    rust_rocksdb_filesystem_t* fs, const char* path,
+    const rust_rocksdb_fs_file_options_t* opts, char** errptr) {
+  std::unique_ptr<FSSequentialFile> file;
+  if (SaveError(errptr, fs->rep->NewSequentialFile(path, ToFileOptions(*opts),
+                                                   &file, nullptr))) {
+    return nullptr;
+  }
+  return new rust_rocksdb_fs_sequential_file_t{fs->rep, std::move(file)};
+}
+
+rust_rocksdb_fs_random_access_file_t*
+rust_rocksdb_filesystem_new_random_access_file(
+    rust_rocksdb_filesystem_t* fs, const char* path,
+    const rust_rocksdb_fs_file_options_t* opts, char** errptr) {
+  std::unique_ptr<FSRandomAccessFile> file;
+  if (SaveError(errptr, fs->rep->NewRandomAccessFile(
+                            path, ToFileOptions(*opts), &file, nullptr))) {
+    return nullptr;
+  }
+  return new rust_rocksdb_fs_random_access_file_t{fs->rep, std::move(file)};
+}
+
+rust_rocksdb_fs_writable_file_t* rust_rocksdb_filesystem_new_writable_file(
+    rust_rocksdb_filesystem_t* fs, const char* path,
+    const rust_rocksdb_fs_file_options_t* opts, unsigned char reopen,
+    char** errptr) {
+  std::unique_ptr<FSWritableFile> file;
+  FileOptions file_opts = ToFileOptions(*opts);
+  IOStatus s =
+      reopen ? fs->rep->ReopenWritableFile(path, file_opts, &file, nullptr)
+             : fs->rep->NewWritableFile(path, file_opts, &file, nullptr);
+  if (SaveError(errptr, s)) {
+    return nullptr;
+  }
+  return new rust_rocksdb_fs_writable_file_t{fs->rep, std::move(file)};
+}
+
+unsigned char rust_rocksdb_filesystem_file_exists(rust_rocksdb_filesystem_t* fs,
+                                                  const char* path,
+                                                  char** errptr) {
+  IOStatus s = fs->rep->FileExists(path, IOOptions(), nullptr);
+  if (s.IsNotFound()) {
+    return 0;
+  }
+  return !SaveError(errptr, s);
+}
+
+void rust_rocksdb_filesystem_get_children(
+    rust_rocksdb_filesystem_t* fs, const char* path, void* state,
+    void (*child)(void* state, const char* name, size_t name_len),
+    char** errptr) {
+  std::vector<std::string> children;
+  if (SaveError(errptr,
+                fs->rep->GetChildren(path, IOOptions(), &children, nullptr))) {
+    return;
+  }
+  for (const auto& name : children) {
+    child(state, name.data(), name.size());
+  }
+}
+
+void rust_rocksdb_filesystem_delete_file(rust_rocksdb_filesystem_t* fs,
+                                         const char* path, char** errptr) {
+  SaveError(errptr, fs->rep->DeleteFile(path, IOOptions(), nullptr));
+}
+
+void rust_rocksdb_filesystem_create_dir(rust_rocksdb_filesystem_t* fs,
+                                        const char* path,
+                                        unsigned char if_missing,
+                                        char** errptr) {
+  IOStatus s = if_missing
+                   ? fs->rep->CreateDirIfMissing(path, IOOptions(), nullptr)
+                   : fs->rep->CreateDir(path, IOOptions(), nullptr);
+  SaveError(errptr, s);
+}
+
+void rust_rocksdb_filesystem_delete_dir(rust_rocksdb_filesystem_t* fs,
+                                        const char* path, char** errptr) {
+  SaveError(errptr, fs->rep->DeleteDir(path, IOOptions(), nullptr));
+}
+
+uint64_t rust_rocksdb_filesystem_get_file_size(rust_rocksdb_filesystem_t* fs,
+                                               const char* path,
+                                               char** errptr) {
+  uint64_t size = 0;
+  SaveError(errptr, fs->rep->GetFileSize(path, IOOptions(), &size, nullptr));
+  return size;
+}
+
+uint64_t rust_rocksdb_filesystem_get_file_modification_time(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr) {
+  uint64_t mtime = 0;
+  SaveError(errptr, fs->rep->GetFileModificationTime(path, IOOptions(),
+                                                     &mtime, nullptr));
+  return mtime;
+}
+
+void rust_rocksdb_filesystem_rename_file(rust_rocksdb_filesystem_t* fs,
+                                         const char* src, const char* target,
+                                         char** errptr) {
+  SaveError(errptr, fs->rep->RenameFile(src, target, IOOptions(), nullptr));
+}
+
+void rust_rocksdb_filesystem_link_file(rust_rocksdb_filesystem_t* fs,
+                                       const char* src, const char* target,
+                                       char** errptr) {
+  SaveError(errptr, fs->rep->LinkFile(src, target, IOOptions(), nullptr));
+}
+
+rust_rocksdb_fs_file_lock_t* rust_rocksdb_filesystem_lock_file(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr) {
+  FileLock* lock = nullptr;
+  if (SaveError(errptr, fs->rep->LockFile(path, IOOptions(), &lock, nullptr))) {
+    return nullptr;
+  }
+  return new rust_rocksdb_fs_file_lock_t{fs->rep, lock};
+}
+
+void rust_rocksdb_fs_file_lock_unlock(rust_rocksdb_fs_file_lock_t* lock,
+                                      char** errptr) {
+  SaveError(errptr, lock->fs->UnlockFile(lock->rep, IOOptions(), nullptr));
+  delete lock;
+}
+
+unsigned char rust_rocksdb_filesystem_is_directory(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr) {
+  bool is_dir = false;
+  SaveError(errptr, fs->rep->IsDirectory(path, IOOptions(), &is_dir, nullptr));
+  return is_dir;
+}
+
+void rust_rocksdb_filesystem_open_dir(rust_rocksdb_filesystem_t* fs,
+                                      const char* path, char** errptr) {
+  std::unique_ptr<FSDirectory> dir;
+  IOStatus s = fs->rep->NewDirectory(path, IOOptions(), &dir, nullptr);
+  if (s.ok()) {
+    s = dir->Close(IOOptions(), nullptr);
+    if (s.IsNotSupported()) {
+      s = IOStatus::OK();
+    }
+  }
+  SaveError(errptr, s);
+}
+
+void rust_rocksdb_filesystem_sync_dir(rust_rocksdb_filesystem_t* fs,
+                                      const char* path, char** errptr) {
+  std::unique_ptr<FSDirectory> dir;
+  IOStatus s = fs->rep->NewDirectory(path, IOOptions(), &dir, nullptr);
+  if (s.ok()) {
+    s = dir->Fsync(IOOptions(), nullptr);
+  }
+  if (s.ok()) {
+    s = dir->Close(IOOptions(), nullptr);
+    if (s.IsNotSupported()) {
+      s = IOStatus::OK();
+    }
+  }
+  SaveError(errptr, s);
+}
+
+char* rust_rocksdb_filesystem_get_absolute_path(rust_rocksdb_filesystem_t* fs,
+                                                const char* path, size_t* len,
+                                                char** errptr) {
+  std::string output;
+  IOStatus s = fs->rep->GetAbsolutePath(path, IOOptions(), &output, nullptr);
+  if (SaveError(errptr, s)) {
+    *len = 0;
+    return nullptr;
+  }
+  *len = output.size();
+  return CopyString(output);
+}
+
+size_t rust_rocksdb_fs_sequential_file_read(
+    rust_rocksdb_fs_sequential_file_t* file, char* buf, size_t n,
+    char** errptr) {
+  Slice result;
+  if (SaveError(errptr,
+                file->rep->Read(n, IOOptions(), &result, buf, nullptr))) {
+    return 0;
+  }
+  if (result.data() != buf) {
+    memmove(buf, result.data(), result.size());
+  }
+  return result.size();
+}
+
+size_t rust_rocksdb_fs_sequential_file_positioned_read(
+    rust_rocksdb_fs_sequential_file_t* file, uint64_t offset, char* buf,
+    size_t n, char** errptr) {
+  Slice result;
+  if (SaveError(errptr, file->rep->PositionedRead(offset, n, IOOptions(),
+                                                  &result, buf, nullptr))) {
+    return 0;
+  }
+  if (result.data() != buf) {
+    memmove(buf, result.data(), result.size());
+  }
+  return result.size();
+}
+
+void rust_rocksdb_fs_sequential_file_skip(
+    rust_rocksdb_fs_sequential_file_t* file, uint64_t n, char** errptr) {
+  SaveError(errptr, file->rep->Skip(n));
+}
+
+void rust_rocksdb_fs_sequential_file_destroy(
+    rust_rocksdb_fs_sequential_file_t* file) {
+  delete file;
+}
+
+unsigned char rust_rocksdb_fs_sequential_file_use_direct_io(
+    rust_rocksdb_fs_sequential_file_t* file, size_t* alignment) {
+  *alignment = file->rep->GetRequiredBufferAlignment();
+  return file->rep->use_direct_io();
+}
+
+size_t rust_rocksdb_fs_random_access_file_read(
+    rust_rocksdb_fs_random_access_file_t* file, uint64_t offset, char* buf,
+    size_t n, char** errptr) {
+  Slice result;
+  if (SaveError(errptr, file->rep->Read(offset, n, IOOptions(), &result, buf,
+                                        nullptr))) {
+    return 0;
+  }
+  if (result.data() != buf) {
+    memmove(buf, result.data(), result.size());
+  }
+  return result.size();
+}
+
+void rust_rocksdb_fs_random_access_file_destroy(
+    rust_rocksdb_fs_random_access_file_t* file) {
+  delete file;
+}
+
+unsigned char rust_rocksdb_fs_random_access_file_use_direct_io(
+    rust_rocksdb_fs_random_access_file_t* file, size_t* alignment) {
+  *alignment = file->rep->GetRequiredBufferAlignment();
+  return file->rep->use_direct_io();
+}
+
+void rust_rocksdb_fs_writable_file_append(rust_rocksdb_fs_writable_file_t* file,
+                                          const char* data, size_t n,
+                                          char** errptr) {
+  SaveError(errptr,
+            file->rep->Append(Slice(data, n), IOOptions(), nullptr));
+}
+
+void rust_rocksdb_fs_writable_file_positioned_append(
+    rust_rocksdb_fs_writable_file_t* file, const char* data, size_t n,
+    uint64_t offset, char** errptr) {
+  SaveError(errptr, file->rep->PositionedAppend(Slice(data, n), offset,
+                                                IOOptions(), nullptr));
+}
+
+void rust_rocksdb_fs_writable_file_truncate(
+    rust_rocksdb_fs_writable_file_t* file, uint64_t size, char** errptr) {
+  SaveError(errptr, file->rep->Truncate(size, IOOptions(), nullptr));
+}
+
+void rust_rocksdb_fs_writable_file_flush(rust_rocksdb_fs_writable_file_t* file,
+                                         char** errptr) {
+  SaveError(errptr, file->rep->Flush(IOOptions(), nullptr));
+}
+
+void rust_rocksdb_fs_writable_file_sync(rust_rocksdb_fs_writable_file_t* file,
+                                        char** errptr) {
+  SaveError(errptr, file->rep->Sync(IOOptions(), nullptr));
+}
+
+void rust_rocksdb_fs_writable_file_close(rust_rocksdb_fs_writable_file_t* file,
+                                         char** errptr) {
+  SaveError(errptr, file->rep->Close(IOOptions(), nullptr));
+}
+
+uint64_t rust_rocksdb_fs_writable_file_get_size(
+    rust_rocksdb_fs_writable_file_t* file) {
+  return file->rep->GetFileSize(IOOptions(), nullptr);
+}
+
+void rust_rocksdb_fs_writable_file_destroy(
+    rust_rocksdb_fs_writable_file_t* file) {
+  delete file;
+}
+
+unsigned char rust_rocksdb_fs_writable_file_use_direct_io(
+    rust_rocksdb_fs_writable_file_t* file, size_t* alignment) {
+  *alignment = file->rep->GetRequiredBufferAlignment();
+  return file->rep->use_direct_io();
+}
+
+void rust_rocksdb_fs_error_set(rust_rocksdb_fs_error_t* err, int code,
+                               const char* msg, size_t msg_len) {
+  Slice message(msg, msg_len);
+  switch (code) {
+    case 1:
+      err->status = IOStatus::PathNotFound(message);
+      break;
+    case 2:
+      err->status = IOStatus::NotSupported(message);
+      break;
+    case 3:
+      err->status = IOStatus::NoSpace(message);
+      break;
+    default:
+      err->status = IOStatus::IOError(message);
+      break;
+  }
+}
+
+rocksdb_env_t* rust_rocksdb_create_env_from_filesystem(
+    void* state, void (*destructor)(void*),
+    const rust_rocksdb_filesystem_callbacks_t* callbacks) {
+  auto fs = std::make_shared<RustFileSystem>(state, destructor, *callbacks);
+  auto* result = new rocksdb_env_t;
+  result->rep = ROCKSDB_NAMESPACE::NewCompositeEnv(fs).release();
+  result->is_default = false;
+  return result;
+}
+
+}  // end extern "C"
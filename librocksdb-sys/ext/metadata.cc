@@ -0,0 +1,110 @@
+#include "rocksdb/metadata.h"
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::BlobMetaData;
+using ROCKSDB_NAMESPACE::ColumnFamilyMetaData;
+using ROCKSDB_NAMESPACE::LevelMetaData;
+using ROCKSDB_NAMESPACE::SstFileMetaData;
+using rust_rocksdb::ColumnFamilyOrDefault;
+
+struct rust_rocksdb_column_family_metadata_t {
+  ColumnFamilyMetaData rep;
+};
+
+extern "C" {
+
+rust_rocksdb_column_family_metadata_t*
+rust_rocksdb_get_column_family_metadata_cf(
+    rocksdb_t* db, rocksdb_column_family_handle_t* cf) {
+  auto* meta = new rust_rocksdb_column_family_metadata_t;
+  db->rep->GetColumnFamilyMetaData(ColumnFamilyOrDefault(db, cf), &meta->rep);
+  return meta;
+}
+
+void rust_rocksdb_column_family_metadata_destroy(
+    rust_rocksdb_column_family_metadata_t* meta) {
+  delete meta;
+}
+
+const char* rust_rocksdb_column_family_metadata_get_name(
+    const rust_rocksdb_column_family_metadata_t* meta) {
+  return meta->rep.name.c_str();
+}
+
+uint64_t rust_rocksdb_column_family_metadata_get_size(
+    const rust_rocksdb_column_family_metadata_t* meta) {
+  return meta->rep.size;
+}
+
+size_t rust_rocksdb_column_family_metadata_get_file_count(
+    const rust_rocksdb_column_family_metadata_t* meta) {
+  return meta->rep.file_count;
+}
+
+size_t rust_rocksdb_column_family_metadata_get_level_count(
+    const rust_rocksdb_column_family_metadata_t* meta) {
+  return meta->rep.levels.size();
+}
+
+void rust_rocksdb_column_family_metadata_get_level(
+    const rust_rocksdb_column_family_metadata_t* meta, size_t level_index,
+    rust_rocksdb_level_metadata_info_t* info) {
+  const LevelMetaData& level = meta->rep.levels[level_index];
+  info->level = level.level;
+  info->size = level.size;
+  info->file_count = level.files.size();
+}
+
+void rust_rocksdb_column_family_metadata_get_sst_file(
+    const rust_rocksdb_column_family_metadata_t* meta, size_t level_index,
+    size_t file_index, rust_rocksdb_sst_file_metadata_info_t* info) {
+  const SstFileMetaData& file = meta->rep.levels[level_index].files[file_index];
+  info->relative_filename = file.relative_filename.data();
+  info->relative_filename_len = file.relative_filename.size();
+  info->directory = file.directory.data();
+  info->directory_len = file.directory.size();
+  info->file_number = file.file_number;
+  info->size = file.size;
+  info->smallest_key = file.smallestkey.data();
+  info->smallest_key_len = file.smallestkey.size();
+  info->largest_key = file.largestkey.data();
+  info->largest_key_len = file.largestkey.size();
+  info->smallest_seqno = file.smallest_seqno;
+  info->largest_seqno = file.largest_seqno;
+  info->num_entries = file.num_entries;
+  info->num_deletions = file.num_deletions;
+  info->being_compacted = file.being_compacted;
+  info->oldest_blob_file_number = file.oldest_blob_file_number;
+  info->oldest_ancester_time = file.oldest_ancester_time;
+  info->file_creation_time = file.file_creation_time;
+  info->temperature = static_cast<int>(file.temperature);
+}
+
+uint64_t rust_rocksdb_column_family_metadata_get_blob_file_size(
+    const rust_rocksdb_column_family_metadata_t* meta) {
+  return meta->rep.blob_file_size;
+}
+
+size_t rust_rocksdb_column_family_metadata_get_blob_file_count(
+    const rust_rocksdb_column_family_metadata_t* meta) {
+  return meta->rep.blob_files.size();
+}
+
+void rust_rocksdb_column_family_metadata_get_blob_file(
+    const rust_rocksdb_column_family_metadata_t* meta, size_t blob_index,
+    rust_rocksdb_blob_metadata_info_t* info) {
+  const BlobMetaData& blob = meta->rep.blob_files[blob_index];
+  info->file_number = blob.blob_file_number;
+  info->file_name = blob.blob_file_name.data();
+  info->file_name_len = blob.blob_file_name.size();
+  info->file_path = blob.blob_file_path.data();
+  info->file_path_len = blob.blob_file_path.size();
+  info->size = blob.blob_file_size;
+  info->total_blob_count = blob.total_blob_count;
+  info->total_blob_bytes = blob.total_blob_bytes;
+  info->garbage_blob_count = blob.garbage_blob_count;
+  info->garbage_blob_bytes = blob.garbage_blob_bytes;
+}
+
+}  // end extern "C"
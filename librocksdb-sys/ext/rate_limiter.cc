@@ -0,0 +1,63 @@
+#include <memory>
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::Env;
+using ROCKSDB_NAMESPACE::RateLimiter;
+
+extern "C" {
+
+rocksdb_ratelimiter_t* rust_rocksdb_ratelimiter_create_with_mode(
+    int64_t rate_bytes_per_sec, int64_t refill_period_us, int32_t fairness,
+    int mode, unsigned char auto_tuned) {
+  auto* limiter = new rocksdb_ratelimiter_t;
+  limiter->rep.reset(ROCKSDB_NAMESPACE::NewGenericRateLimiter(
+      rate_bytes_per_sec, refill_period_us, fairness,
+      static_cast<RateLimiter::Mode>(mode), auto_tuned));
+  return limiter;
+}
+
+void rust_rocksdb_ratelimiter_set_bytes_per_second(
+    rocksdb_ratelimiter_t* limiter, int64_t bytes_per_second) {
+  limiter->rep->SetBytesPerSecond(bytes_per_second);
+}
+
+int64_t rust_rocksdb_ratelimiter_get_bytes_per_second(
+    rocksdb_ratelimiter_t* limiter) {
+  return limiter->rep->GetBytesPerSecond();
+}
+
+int64_t rust_rocksdb_ratelimiter_get_single_burst_bytes(
+    rocksdb_ratelimiter_t* limiter) {
+  return limiter->rep->GetSingleBurstBytes();
+}
+
+int64_t rust_rocksdb_ratelimiter_get_total_bytes_through(
+    rocksdb_ratelimiter_t* limiter, int priority) {
+  return limiter->rep->GetTotalBytesThrough(
+      static_cast<Env::IOPriority>(priority));
+}
+
+int64_t rust_rocksdb_ratelimiter_get_total_requests(
+    rocksdb_ratelimiter_t* limiter, int priority) {
+  return limiter->rep->GetTotalRequests(
+      static_cast<Env::IOPriority>(priority));
+}
+
+void rust_rocksdb_ratelimiter_request(rocksdb_ratelimiter_t* limiter,
+                                      int64_t bytes, int priority) {
+  limiter->rep->Request(bytes, static_cast<Env::IOPriority>(priority),
+                        nullptr);
+}
+
+void rust_rocksdb_readoptions_set_rate_limiter_priority(
+    rocksdb_readoptions_t* opt, int priority) {
+  opt->rep.rate_limiter_priority = static_cast<Env::IOPriority>(priority);
+}
+
+void rust_rocksdb_writeoptions_set_rate_limiter_priority(
+    rocksdb_writeoptions_t* opt, int priority) {
+  opt->rep.rate_limiter_priority = static_cast<Env::IOPriority>(priority);
+}
+
+}  // end extern "C"
@@ -0,0 +1,1202 @@
+/*
+ * C declarations for the extensions in this directory.
+ *
+ * The upstream C API (`rocksdb/c.h`) does not expose everything the Rust
+ * wrapper needs. The functions declared here fill those gaps; they follow the
+ * conventions of `rocksdb/c.h` (opaque handles, `char** errptr` for errors,
+ * caller-freed `char*` results) and are prefixed with `rust_rocksdb_`.
+ */
+
+#ifndef RUST_ROCKSDB_EXT_H
+#define RUST_ROCKSDB_EXT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#include "rocksdb/c.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Version */
+
+/*
+ * Fails unless the linked library is of the RocksDB version the extensions
+ * were built for, as they mirror private definitions of its C API.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_check_linked_version(
+    char** errptr);
+
+/* Wide columns */
+
+typedef struct rust_rocksdb_wide_columns_t rust_rocksdb_wide_columns_t;
+
+/* `cf` may be NULL, in which case the default column family is used. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_put_entity_cf(
+    rocksdb_t* db, const rocksdb_writeoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t keylen,
+    size_t num_columns, const char* const* names, const size_t* names_sizes,
+    const char* const* values, const size_t* values_sizes, char** errptr);
+
+/* Returns NULL if the key was not found or on error. */
+extern ROCKSDB_LIBRARY_API rust_rocksdb_wide_columns_t*
+rust_rocksdb_get_entity_cf(rocksdb_t* db, const rocksdb_readoptions_t* options,
+                           rocksdb_column_family_handle_t* cf, const char* key,
+                           size_t keylen, char** errptr);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_wide_columns_count(const rust_rocksdb_wide_columns_t* columns);
+
+extern ROCKSDB_LIBRARY_API const char* rust_rocksdb_wide_columns_name(
+    const rust_rocksdb_wide_columns_t* columns, size_t index, size_t* len);
+
+extern ROCKSDB_LIBRARY_API const char* rust_rocksdb_wide_columns_value(
+    const rust_rocksdb_wide_columns_t* columns, size_t index, size_t* len);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_wide_columns_destroy(
+    rust_rocksdb_wide_columns_t* columns);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_writebatch_put_entity_cf(
+    rocksdb_writebatch_t* batch, rocksdb_column_family_handle_t* cf,
+    const char* key, size_t keylen, size_t num_columns,
+    const char* const* names, const size_t* names_sizes,
+    const char* const* values, const size_t* values_sizes, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_sstfilewriter_put_entity(
+    rocksdb_sstfilewriter_t* writer, const char* key, size_t keylen,
+    size_t num_columns, const char* const* names, const size_t* names_sizes,
+    const char* const* values, const size_t* values_sizes, char** errptr);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_iter_columns_count(const rocksdb_iterator_t* iter);
+
+extern ROCKSDB_LIBRARY_API const char* rust_rocksdb_iter_column_name(
+    const rocksdb_iterator_t* iter, size_t index, size_t* len);
+
+extern ROCKSDB_LIBRARY_API const char* rust_rocksdb_iter_column_value(
+    const rocksdb_iterator_t* iter, size_t index, size_t* len);
+
+/* Merge operands */
+
+/*
+ * Calls `operand` for the base value (if any) and every merge operand of
+ * `key`, oldest first. When the key has more than `expected_max_operands`
+ * operands, an Incomplete error is stored in `errptr`, `operand` is not
+ * called and `num_operands` still receives the actual count. The buffer
+ * holding the operands is sized to their actual count rather than to
+ * `expected_max_operands`, which must be positive.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_get_merge_operands_cf(
+    rocksdb_t* db, const rocksdb_readoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t keylen,
+    int expected_max_operands, void* state,
+    void (*operand)(void*, const char* value, size_t vallen),
+    int* num_operands, char** errptr);
+
+/* Manual file compaction */
+
+typedef struct rust_rocksdb_compactionoptions_t
+    rust_rocksdb_compactionoptions_t;
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_compactionoptions_t*
+rust_rocksdb_compactionoptions_create(void);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_compactionoptions_destroy(
+    rust_rocksdb_compactionoptions_t* opt);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_compactionoptions_set_output_file_size_limit(
+    rust_rocksdb_compactionoptions_t* opt, uint64_t limit);
+
+/* One of the `rocksdb_*_compression` constants from `rocksdb/c.h`. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_compactionoptions_set_compression(
+    rust_rocksdb_compactionoptions_t* opt, int compression);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_compactionoptions_set_max_subcompactions(
+    rust_rocksdb_compactionoptions_t* opt, uint32_t max_subcompactions);
+
+/* Calls `output_file` with the path of every file the compaction produced. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_compact_files_cf(
+    rocksdb_t* db, const rust_rocksdb_compactionoptions_t* options,
+    rocksdb_column_family_handle_t* cf, size_t num_input_files,
+    const char* const* input_file_names, int output_level, void* state,
+    void (*output_file)(void*, const char* name, size_t namelen),
+    char** errptr);
+
+/* Column family metadata */
+
+typedef struct rust_rocksdb_column_family_metadata_t
+    rust_rocksdb_column_family_metadata_t;
+
+typedef struct rust_rocksdb_level_metadata_info_t {
+  int level;
+  uint64_t size;
+  size_t file_count;
+} rust_rocksdb_level_metadata_info_t;
+
+/* The pointers stay valid until the metadata is destroyed. */
+typedef struct rust_rocksdb_sst_file_metadata_info_t {
+  const char* relative_filename;
+  size_t relative_filename_len;
+  const char* directory;
+  size_t directory_len;
+  uint64_t file_number;
+  uint64_t size;
+  const char* smallest_key;
+  size_t smallest_key_len;
+  const char* largest_key;
+  size_t largest_key_len;
+  uint64_t smallest_seqno;
+  uint64_t largest_seqno;
+  uint64_t num_entries;
+  uint64_t num_deletions;
+  unsigned char being_compacted;
+  uint64_t oldest_blob_file_number;
+  uint64_t oldest_ancester_time;
+  uint64_t file_creation_time;
+  int temperature;
+} rust_rocksdb_sst_file_metadata_info_t;
+
+/* The pointers stay valid until the metadata is destroyed. */
+typedef struct rust_rocksdb_blob_metadata_info_t {
+  uint64_t file_number;
+  const char* file_name;
+  size_t file_name_len;
+  const char* file_path;
+  size_t file_path_len;
+  uint64_t size;
+  uint64_t total_blob_count;
+  uint64_t total_blob_bytes;
+  uint64_t garbage_blob_count;
+  uint64_t garbage_blob_bytes;
+} rust_rocksdb_blob_metadata_info_t;
+
+/* `cf` may be NULL, in which case the default column family is used. */
+extern ROCKSDB_LIBRARY_API rust_rocksdb_column_family_metadata_t*
+rust_rocksdb_get_column_family_metadata_cf(rocksdb_t* db,
+                                           rocksdb_column_family_handle_t* cf);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_column_family_metadata_destroy(
+    rust_rocksdb_column_family_metadata_t* meta);
+
+/* NUL-terminated, valid until the metadata is destroyed. */
+extern ROCKSDB_LIBRARY_API const char*
+rust_rocksdb_column_family_metadata_get_name(
+    const rust_rocksdb_column_family_metadata_t* meta);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_column_family_metadata_get_size(
+    const rust_rocksdb_column_family_metadata_t* meta);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_column_family_metadata_get_file_count(
+    const rust_rocksdb_column_family_metadata_t* meta);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_column_family_metadata_get_level_count(
+    const rust_rocksdb_column_family_metadata_t* meta);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_column_family_metadata_get_level(
+    const rust_rocksdb_column_family_metadata_t* meta, size_t level_index,
+    rust_rocksdb_level_metadata_info_t* info);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_column_family_metadata_get_sst_file(
+    const rust_rocksdb_column_family_metadata_t* meta, size_t level_index,
+    size_t file_index, rust_rocksdb_sst_file_metadata_info_t* info);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_column_family_metadata_get_blob_file_size(
+    const rust_rocksdb_column_family_metadata_t* meta);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_column_family_metadata_get_blob_file_count(
+    const rust_rocksdb_column_family_metadata_t* meta);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_column_family_metadata_get_blob_file(
+    const rust_rocksdb_column_family_metadata_t* meta, size_t blob_index,
+    rust_rocksdb_blob_metadata_info_t* info);
+
+/* Column family export and import */
+
+typedef struct rust_rocksdb_export_import_files_metadata_t
+    rust_rocksdb_export_import_files_metadata_t;
+typedef struct rust_rocksdb_import_column_family_options_t
+    rust_rocksdb_import_column_family_options_t;
+
+/* The pointers stay valid until the owning object is destroyed. */
+typedef struct rust_rocksdb_live_file_info_t {
+  const char* column_family_name;
+  size_t column_family_name_len;
+  const char* relative_filename;
+  size_t relative_filename_len;
+  const char* directory;
+  size_t directory_len;
+  uint64_t size;
+  int level;
+  const char* smallest_key;
+  size_t smallest_key_len;
+  const char* largest_key;
+  size_t largest_key_len;
+  uint64_t smallest_seqno;
+  uint64_t largest_seqno;
+  uint64_t num_entries;
+  uint64_t num_deletions;
+  int temperature;
+} rust_rocksdb_live_file_info_t;
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_export_import_files_metadata_t*
+rust_rocksdb_checkpoint_export_column_family(
+    rocksdb_checkpoint_t* checkpoint, rocksdb_column_family_handle_t* cf,
+    const char* export_dir, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_export_import_files_metadata_destroy(
+    rust_rocksdb_export_import_files_metadata_t* metadata);
+
+extern ROCKSDB_LIBRARY_API const char*
+rust_rocksdb_export_import_files_metadata_get_db_comparator_name(
+    const rust_rocksdb_export_import_files_metadata_t* metadata, size_t* len);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_export_import_files_metadata_get_file_count(
+    const rust_rocksdb_export_import_files_metadata_t* metadata);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_export_import_files_metadata_get_file(
+    const rust_rocksdb_export_import_files_metadata_t* metadata, size_t index,
+    rust_rocksdb_live_file_info_t* info);
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_import_column_family_options_t*
+rust_rocksdb_import_column_family_options_create(void);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_import_column_family_options_destroy(
+    rust_rocksdb_import_column_family_options_t* opt);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_import_column_family_options_set_move_files(
+    rust_rocksdb_import_column_family_options_t* opt, unsigned char v);
+
+extern ROCKSDB_LIBRARY_API rocksdb_column_family_handle_t*
+rust_rocksdb_create_column_family_with_import(
+    rocksdb_t* db, const rocksdb_options_t* column_family_options,
+    const char* column_family_name,
+    const rust_rocksdb_import_column_family_options_t* import_options,
+    const rust_rocksdb_export_import_files_metadata_t* metadata,
+    char** errptr);
+
+/* Event listeners */
+
+/*
+ * Info structs passed to the event listener callbacks. All pointers are only
+ * valid for the duration of the callback. Strings are not NUL-terminated;
+ * `status` fields are NULL when the status is OK and a NUL-terminated message
+ * otherwise. Reasons are the integer values of the corresponding RocksDB enums.
+ */
+
+typedef struct rust_rocksdb_flush_job_info_t {
+  uint32_t cf_id;
+  const char* cf_name;
+  size_t cf_name_len;
+  const char* file_path;
+  size_t file_path_len;
+  uint64_t file_number;
+  uint64_t thread_id;
+  int job_id;
+  unsigned char triggered_writes_slowdown;
+  unsigned char triggered_writes_stop;
+  uint64_t smallest_seqno;
+  uint64_t largest_seqno;
+  int flush_reason;
+} rust_rocksdb_flush_job_info_t;
+
+typedef struct rust_rocksdb_compaction_job_info_t {
+  uint32_t cf_id;
+  const char* cf_name;
+  size_t cf_name_len;
+  const char* status;
+  uint64_t thread_id;
+  int job_id;
+  int base_input_level;
+  int output_level;
+  size_t num_input_files;
+  const char* const* input_files;
+  const size_t* input_files_lens;
+  size_t num_output_files;
+  const char* const* output_files;
+  const size_t* output_files_lens;
+  int compaction_reason;
+  uint64_t elapsed_micros;
+  uint64_t num_input_records;
+  uint64_t num_output_records;
+  uint64_t total_input_bytes;
+  uint64_t total_output_bytes;
+} rust_rocksdb_compaction_job_info_t;
+
+typedef struct rust_rocksdb_table_file_creation_info_t {
+  const char* db_name;
+  size_t db_name_len;
+  const char* cf_name;
+  size_t cf_name_len;
+  const char* file_path;
+  size_t file_path_len;
+  int job_id;
+  int reason;
+  uint64_t file_size;
+  const char* status;
+} rust_rocksdb_table_file_creation_info_t;
+
+typedef struct rust_rocksdb_table_file_deletion_info_t {
+  const char* db_name;
+  size_t db_name_len;
+  const char* file_path;
+  size_t file_path_len;
+  int job_id;
+  const char* status;
+} rust_rocksdb_table_file_deletion_info_t;
+
+/*
+ * Conditions are 0 for normal, 1 for delayed and 2 for stopped writes. Other
+ * conditions are 3 plus their value in RocksDB.
+ */
+typedef struct rust_rocksdb_write_stall_info_t {
+  const char* cf_name;
+  size_t cf_name_len;
+  int cur;
+  int prev;
+} rust_rocksdb_write_stall_info_t;
+
+typedef struct rust_rocksdb_error_recovery_info_t {
+  const char* old_bg_error;
+  const char* new_bg_error;
+} rust_rocksdb_error_recovery_info_t;
+
+/* Any of the callbacks may be NULL. */
+typedef struct rust_rocksdb_eventlistener_callbacks_t {
+  void (*on_flush_begin)(void* state, const rust_rocksdb_flush_job_info_t*);
+  void (*on_flush_completed)(void* state,
+                             const rust_rocksdb_flush_job_info_t*);
+  void (*on_compaction_begin)(void* state,
+                              const rust_rocksdb_compaction_job_info_t*);
+  void (*on_compaction_completed)(void* state,
+                                  const rust_rocksdb_compaction_job_info_t*);
+  void (*on_table_file_created)(void* state,
+                                const rust_rocksdb_table_file_creation_info_t*);
+  void (*on_table_file_deleted)(void* state,
+                                const rust_rocksdb_table_file_deletion_info_t*);
+  void (*on_stall_conditions_changed)(void* state,
+                                      const rust_rocksdb_write_stall_info_t*);
+  void (*on_background_error)(void* state, int reason, const char* error);
+  void (*on_error_recovery_end)(void* state,
+                                const rust_rocksdb_error_recovery_info_t*);
+} rust_rocksdb_eventlistener_callbacks_t;
+
+/*
+ * Registers a listener on the options. The listener is shared by copies of
+ * the options and by the databases opened with them; `destructor` is called
+ * with `state` once the last of them is gone.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_options_add_eventlistener(
+    rocksdb_options_t* opt, void* state, void (*destructor)(void*),
+    const rust_rocksdb_eventlistener_callbacks_t* callbacks);
+
+/* File systems */
+
+/*
+ * Access to the C++ `FileSystem` of an env. File and lock handles keep the
+ * file system alive. Paths are NUL-terminated.
+ */
+
+typedef struct rust_rocksdb_filesystem_t rust_rocksdb_filesystem_t;
+typedef struct rust_rocksdb_fs_sequential_file_t
+    rust_rocksdb_fs_sequential_file_t;
+typedef struct rust_rocksdb_fs_random_access_file_t
+    rust_rocksdb_fs_random_access_file_t;
+typedef struct rust_rocksdb_fs_writable_file_t rust_rocksdb_fs_writable_file_t;
+typedef struct rust_rocksdb_fs_file_lock_t rust_rocksdb_fs_file_lock_t;
+
+/*
+ * The options a file is opened with, the fields of `FileOptions` in
+ * rocksdb/file_system.h that change how a file system opens files.
+ * `io_priority` is the `Env::IOPriority` of the I/O and `temperature` the
+ * `Temperature` of the file.
+ */
+typedef struct rust_rocksdb_fs_file_options_t {
+  unsigned char use_mmap_reads;
+  unsigned char use_mmap_writes;
+  unsigned char use_direct_reads;
+  unsigned char use_direct_writes;
+  unsigned char allow_fallocate;
+  unsigned char fallocate_with_keep_size;
+  unsigned char set_fd_cloexec;
+  unsigned char strict_bytes_per_sync;
+  uint64_t bytes_per_sync;
+  int io_priority;
+  int temperature;
+} rust_rocksdb_fs_file_options_t;
+
+/* Fills `opts` with the defaults of `FileOptions`. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_file_options_init(
+    rust_rocksdb_fs_file_options_t* opts);
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_filesystem_t*
+rust_rocksdb_filesystem_create_default(void);
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_filesystem_t*
+rust_rocksdb_env_get_filesystem(rocksdb_env_t* env);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_destroy(
+    rust_rocksdb_filesystem_t* fs);
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_fs_sequential_file_t*
+rust_rocksdb_filesystem_new_sequential_file(
+    rust_rocksdb_filesystem_t* fs, const char* path,
+    const rust_rocksdb_fs_file_options_t* opts, char** errptr);
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_fs_random_access_file_t*
+rust_rocksdb_filesystem_new_random_access_file(
+    rust_rocksdb_filesystem_t* fs, const char* path,
+    const rust_rocksdb_fs_file_options_t* opts, char** errptr);
+
+/* Truncates the file if it exists, unless `reopen` is set. */
+extern ROCKSDB_LIBRARY_API rust_rocksdb_fs_writable_file_t*
+rust_rocksdb_filesystem_new_writable_file(
+    rust_rocksdb_filesystem_t* fs, const char* path,
+    const rust_rocksdb_fs_file_options_t* opts, unsigned char reopen,
+    char** errptr);
+
+extern ROCKSDB_LIBRARY_API unsigned char rust_rocksdb_filesystem_file_exists(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_get_children(
+    rust_rocksdb_filesystem_t* fs, const char* path, void* state,
+    void (*child)(void* state, const char* name, size_t name_len),
+    char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_delete_file(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_create_dir(
+    rust_rocksdb_filesystem_t* fs, const char* path, unsigned char if_missing,
+    char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_delete_dir(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+extern ROCKSDB_LIBRARY_API uint64_t rust_rocksdb_filesystem_get_file_size(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_filesystem_get_file_modification_time(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_rename_file(
+    rust_rocksdb_filesystem_t* fs, const char* src, const char* target,
+    char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_link_file(
+    rust_rocksdb_filesystem_t* fs, const char* src, const char* target,
+    char** errptr);
+
+extern ROCKSDB_LIBRARY_API rust_rocksdb_fs_file_lock_t*
+rust_rocksdb_filesystem_lock_file(rust_rocksdb_filesystem_t* fs,
+                                  const char* path, char** errptr);
+
+/* Releases the lock and destroys the handle, even on error. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_file_lock_unlock(
+    rust_rocksdb_fs_file_lock_t* lock, char** errptr);
+
+extern ROCKSDB_LIBRARY_API unsigned char rust_rocksdb_filesystem_is_directory(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+/* Opens the directory, as done before syncing it, and closes it. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_open_dir(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+/* Fsyncs the directory itself, persisting the entries in it. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_filesystem_sync_dir(
+    rust_rocksdb_filesystem_t* fs, const char* path, char** errptr);
+
+/* Returns a malloc'ed, not NUL-terminated string. */
+extern ROCKSDB_LIBRARY_API char* rust_rocksdb_filesystem_get_absolute_path(
+    rust_rocksdb_filesystem_t* fs, const char* path, size_t* len,
+    char** errptr);
+
+/* Reads up to `n` bytes; fewer are only returned at the end of the file. */
+extern ROCKSDB_LIBRARY_API size_t rust_rocksdb_fs_sequential_file_read(
+    rust_rocksdb_fs_sequential_file_t* file, char* buf, size_t n,
+    char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_sequential_file_skip(
+    rust_rocksdb_fs_sequential_file_t* file, uint64_t n, char** errptr);
+
+/* Reads at `offset`, only supported by files using direct I/O. */
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_fs_sequential_file_positioned_read(
+    rust_rocksdb_fs_sequential_file_t* file, uint64_t offset, char* buf,
+    size_t n, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_sequential_file_destroy(
+    rust_rocksdb_fs_sequential_file_t* file);
+
+/*
+ * Whether the file bypasses the OS page cache, in which case the buffers,
+ * offsets and sizes of its I/O must be multiples of `alignment`.
+ */
+extern ROCKSDB_LIBRARY_API unsigned char
+rust_rocksdb_fs_sequential_file_use_direct_io(
+    rust_rocksdb_fs_sequential_file_t* file, size_t* alignment);
+
+extern ROCKSDB_LIBRARY_API size_t rust_rocksdb_fs_random_access_file_read(
+    rust_rocksdb_fs_random_access_file_t* file, uint64_t offset, char* buf,
+    size_t n, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_random_access_file_destroy(
+    rust_rocksdb_fs_random_access_file_t* file);
+
+extern ROCKSDB_LIBRARY_API unsigned char
+rust_rocksdb_fs_random_access_file_use_direct_io(
+    rust_rocksdb_fs_random_access_file_t* file, size_t* alignment);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_append(
+    rust_rocksdb_fs_writable_file_t* file, const char* data, size_t n,
+    char** errptr);
+
+/* Writes at `offset`, only supported by files using direct I/O. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_positioned_append(
+    rust_rocksdb_fs_writable_file_t* file, const char* data, size_t n,
+    uint64_t offset, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_truncate(
+    rust_rocksdb_fs_writable_file_t* file, uint64_t size, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_flush(
+    rust_rocksdb_fs_writable_file_t* file, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_sync(
+    rust_rocksdb_fs_writable_file_t* file, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_close(
+    rust_rocksdb_fs_writable_file_t* file, char** errptr);
+
+extern ROCKSDB_LIBRARY_API uint64_t rust_rocksdb_fs_writable_file_get_size(
+    rust_rocksdb_fs_writable_file_t* file);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_writable_file_destroy(
+    rust_rocksdb_fs_writable_file_t* file);
+
+extern ROCKSDB_LIBRARY_API unsigned char
+rust_rocksdb_fs_writable_file_use_direct_io(
+    rust_rocksdb_fs_writable_file_t* file, size_t* alignment);
+
+/*
+ * Envs whose file system is implemented through callbacks.
+ *
+ * Every callback reports failures through `err` with
+ * `rust_rocksdb_fs_error_set`. The `new_*_file` and `lock_file` callbacks
+ * return an opaque file or lock state, passed to the corresponding file
+ * callbacks and released with the matching `destroy` (or `unlock_file`)
+ * callback. String results are reported through `push`. Paths are not
+ * NUL-terminated. The `new_*_file` callbacks also report whether the file
+ * uses direct I/O and the alignment its I/O then requires.
+ */
+
+typedef struct rust_rocksdb_fs_error_t rust_rocksdb_fs_error_t;
+
+/*
+ * `code` is 1 for a missing path, 2 for an unsupported operation, 3 for a
+ * full device and 0 for any other I/O error.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_fs_error_set(
+    rust_rocksdb_fs_error_t* err, int code, const char* msg, size_t msg_len);
+
+typedef void (*rust_rocksdb_fs_push_string_t)(void* out, const char* s,
+                                              size_t len);
+
+typedef struct rust_rocksdb_filesystem_callbacks_t {
+  void* (*new_sequential_file)(void* state, const char* path, size_t path_len,
+                               const rust_rocksdb_fs_file_options_t* opts,
+                               unsigned char* use_direct_io,
+                               size_t* alignment,
+                               rust_rocksdb_fs_error_t* err);
+  void* (*new_random_access_file)(void* state, const char* path,
+                                  size_t path_len,
+                                  const rust_rocksdb_fs_file_options_t* opts,
+                                  unsigned char* use_direct_io,
+                                  size_t* alignment,
+                                  rust_rocksdb_fs_error_t* err);
+  void* (*new_writable_file)(void* state, const char* path, size_t path_len,
+                             const rust_rocksdb_fs_file_options_t* opts,
+                             unsigned char reopen,
+                             unsigned char* use_direct_io, size_t* alignment,
+                             rust_rocksdb_fs_error_t* err);
+  unsigned char (*file_exists)(void* state, const char* path, size_t path_len,
+                               rust_rocksdb_fs_error_t* err);
+  void (*get_children)(void* state, const char* path, size_t path_len,
+                       void* out, rust_rocksdb_fs_push_string_t push,
+                       rust_rocksdb_fs_error_t* err);
+  void (*delete_file)(void* state, const char* path, size_t path_len,
+                      rust_rocksdb_fs_error_t* err);
+  void (*create_dir)(void* state, const char* path, size_t path_len,
+                     unsigned char if_missing, rust_rocksdb_fs_error_t* err);
+  void (*delete_dir)(void* state, const char* path, size_t path_len,
+                     rust_rocksdb_fs_error_t* err);
+  uint64_t (*get_file_size)(void* state, const char* path, size_t path_len,
+                            rust_rocksdb_fs_error_t* err);
+  uint64_t (*get_file_modification_time)(void* state, const char* path,
+                                         size_t path_len,
+                                         rust_rocksdb_fs_error_t* err);
+  void (*rename_file)(void* state, const char* src, size_t src_len,
+                      const char* target, size_t target_len,
+                      rust_rocksdb_fs_error_t* err);
+  void (*link_file)(void* state, const char* src, size_t src_len,
+                    const char* target, size_t target_len,
+                    rust_rocksdb_fs_error_t* err);
+  void* (*lock_file)(void* state, const char* path, size_t path_len,
+                     rust_rocksdb_fs_error_t* err);
+  void (*unlock_file)(void* state, void* lock, rust_rocksdb_fs_error_t* err);
+  unsigned char (*is_directory)(void* state, const char* path,
+                                size_t path_len, rust_rocksdb_fs_error_t* err);
+  void (*open_dir)(void* state, const char* path, size_t path_len,
+                   rust_rocksdb_fs_error_t* err);
+  void (*sync_dir)(void* state, const char* path, size_t path_len,
+                   rust_rocksdb_fs_error_t* err);
+  void (*get_absolute_path)(void* state, const char* path, size_t path_len,
+                            void* out, rust_rocksdb_fs_push_string_t push,
+                            rust_rocksdb_fs_error_t* err);
+
+  size_t (*sequential_file_read)(void* file, char* buf, size_t n,
+                                 rust_rocksdb_fs_error_t* err);
+  size_t (*sequential_file_positioned_read)(void* file, uint64_t offset,
+                                            char* buf, size_t n,
+                                            rust_rocksdb_fs_error_t* err);
+  void (*sequential_file_skip)(void* file, uint64_t n,
+                               rust_rocksdb_fs_error_t* err);
+  void (*sequential_file_destroy)(void* file);
+  size_t (*random_access_file_read)(void* file, uint64_t offset, char* buf,
+                                    size_t n, rust_rocksdb_fs_error_t* err);
+  void (*random_access_file_destroy)(void* file);
+  void (*writable_file_append)(void* file, const char* data, size_t n,
+                               rust_rocksdb_fs_error_t* err);
+  void (*writable_file_positioned_append)(void* file, const char* data,
+                                          size_t n, uint64_t offset,
+                                          rust_rocksdb_fs_error_t* err);
+  void (*writable_file_truncate)(void* file, uint64_t size,
+                                 rust_rocksdb_fs_error_t* err);
+  void (*writable_file_flush)(void* file, rust_rocksdb_fs_error_t* err);
+  void (*writable_file_sync)(void* file, rust_rocksdb_fs_error_t* err);
+  void (*writable_file_close)(void* file, rust_rocksdb_fs_error_t* err);
+  uint64_t (*writable_file_get_size)(void* file);
+  void (*writable_file_destroy)(void* file);
+} rust_rocksdb_filesystem_callbacks_t;
+
+/*
+ * Creates an env that uses the callbacks for all file system operations and
+ * the default env for everything else. `destructor` is called with `state`
+ * once the env and everything created from it are gone.
+ */
+extern ROCKSDB_LIBRARY_API rocksdb_env_t*
+rust_rocksdb_create_env_from_filesystem(
+    void* state, void (*destructor)(void*),
+    const rust_rocksdb_filesystem_callbacks_t* callbacks);
+
+/* Encryption */
+
+/*
+ * Encryption provider implemented through callbacks, see
+ * `EncryptionProvider` in rocksdb/env_encryption.h. Failures are reported
+ * through `err` with `rust_rocksdb_fs_error_set`. `create_cipher_stream`
+ * returns an opaque stream state, passed to the `cipher_stream_*` callbacks
+ * and released with `cipher_stream_destroy`. File names are not
+ * NUL-terminated.
+ */
+typedef struct rust_rocksdb_encryption_provider_callbacks_t {
+  size_t (*prefix_length)(void* state);
+  void (*create_new_prefix)(void* state, const char* fname, size_t fname_len,
+                            char* prefix, size_t prefix_len,
+                            rust_rocksdb_fs_error_t* err);
+  void* (*create_cipher_stream)(void* state, const char* fname,
+                                size_t fname_len, const char* prefix,
+                                size_t prefix_len,
+                                rust_rocksdb_fs_error_t* err);
+  void (*cipher_stream_encrypt)(void* stream, uint64_t file_offset,
+                                char* data, size_t len,
+                                rust_rocksdb_fs_error_t* err);
+  void (*cipher_stream_decrypt)(void* stream, uint64_t file_offset,
+                                char* data, size_t len,
+                                rust_rocksdb_fs_error_t* err);
+  void (*cipher_stream_destroy)(void* stream);
+} rust_rocksdb_encryption_provider_callbacks_t;
+
+/*
+ * Creates an env encrypting all files of `base` with the provider. The
+ * returned env does not own `base`, which must outlive it. `destructor` is
+ * called with `state` once the provider is no longer used.
+ */
+extern ROCKSDB_LIBRARY_API rocksdb_env_t* rust_rocksdb_create_encrypted_env(
+    rocksdb_env_t* base, void* state, void (*destructor)(void*),
+    const rust_rocksdb_encryption_provider_callbacks_t* callbacks);
+
+/* Rate limiters */
+
+/*
+ * `mode` is 0 to limit reads only, 1 for writes only and 2 for all I/O.
+ * Priorities are the values of `Env::IOPriority`: 0 low, 1 mid, 2 high,
+ * 3 user and 4 total, i.e. all priorities in statistics and no rate
+ * limiting in read and write options.
+ */
+extern ROCKSDB_LIBRARY_API rocksdb_ratelimiter_t*
+rust_rocksdb_ratelimiter_create_with_mode(int64_t rate_bytes_per_sec,
+                                          int64_t refill_period_us,
+                                          int32_t fairness, int mode,
+                                          unsigned char auto_tuned);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_ratelimiter_set_bytes_per_second(
+    rocksdb_ratelimiter_t* limiter, int64_t bytes_per_second);
+
+extern ROCKSDB_LIBRARY_API int64_t
+rust_rocksdb_ratelimiter_get_bytes_per_second(rocksdb_ratelimiter_t* limiter);
+
+extern ROCKSDB_LIBRARY_API int64_t
+rust_rocksdb_ratelimiter_get_single_burst_bytes(
+    rocksdb_ratelimiter_t* limiter);
+
+extern ROCKSDB_LIBRARY_API int64_t
+rust_rocksdb_ratelimiter_get_total_bytes_through(
+    rocksdb_ratelimiter_t* limiter, int priority);
+
+extern ROCKSDB_LIBRARY_API int64_t
+rust_rocksdb_ratelimiter_get_total_requests(rocksdb_ratelimiter_t* limiter,
+                                            int priority);
+
+/* Blocks until `bytes`, at most the single burst bytes, are granted. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_ratelimiter_request(
+    rocksdb_ratelimiter_t* limiter, int64_t bytes, int priority);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_readoptions_set_rate_limiter_priority(rocksdb_readoptions_t* opt,
+                                                   int priority);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_writeoptions_set_rate_limiter_priority(
+    rocksdb_writeoptions_t* opt, int priority);
+
+/* SST file managers */
+
+typedef struct rust_rocksdb_sst_file_manager_t rust_rocksdb_sst_file_manager_t;
+
+/* `env` must outlive the manager. */
+extern ROCKSDB_LIBRARY_API rust_rocksdb_sst_file_manager_t*
+rust_rocksdb_sst_file_manager_create(rocksdb_env_t* env, char** errptr);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_sst_file_manager_destroy(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_options_set_sst_file_manager(
+    rocksdb_options_t* opt, rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_sst_file_manager_set_max_allowed_space_usage(
+    rust_rocksdb_sst_file_manager_t* sfm, uint64_t max_allowed_space);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_sst_file_manager_set_compaction_buffer_size(
+    rust_rocksdb_sst_file_manager_t* sfm, uint64_t compaction_buffer_size);
+
+extern ROCKSDB_LIBRARY_API unsigned char
+rust_rocksdb_sst_file_manager_is_max_allowed_space_reached(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API unsigned char
+rust_rocksdb_sst_file_manager_is_max_space_reached_with_compactions(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_sst_file_manager_get_total_size(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_sst_file_manager_get_tracked_files(
+    rust_rocksdb_sst_file_manager_t* sfm, void* state,
+    void (*file)(void* state, const char* path, size_t path_len,
+                 uint64_t size));
+
+extern ROCKSDB_LIBRARY_API int64_t
+rust_rocksdb_sst_file_manager_get_delete_rate_bytes_per_second(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_sst_file_manager_set_delete_rate_bytes_per_second(
+    rust_rocksdb_sst_file_manager_t* sfm, int64_t delete_rate);
+
+extern ROCKSDB_LIBRARY_API double
+rust_rocksdb_sst_file_manager_get_max_trash_db_ratio(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_sst_file_manager_set_max_trash_db_ratio(
+    rust_rocksdb_sst_file_manager_t* sfm, double ratio);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_sst_file_manager_get_total_trash_size(
+    rust_rocksdb_sst_file_manager_t* sfm);
+
+/* Table properties */
+
+/*
+ * Properties of one SST file, valid for the duration of the callback.
+ * Strings are not NUL-terminated. The user collected properties are given
+ * as parallel arrays of names and values.
+ */
+typedef struct rust_rocksdb_table_properties_t {
+  uint64_t data_size;
+  uint64_t index_size;
+  uint64_t filter_size;
+  uint64_t raw_key_size;
+  uint64_t raw_value_size;
+  uint64_t num_data_blocks;
+  uint64_t num_entries;
+  uint64_t num_deletions;
+  uint64_t num_merge_operands;
+  uint64_t num_range_deletions;
+  uint64_t format_version;
+  uint64_t creation_time;
+  uint64_t oldest_key_time;
+  uint64_t file_creation_time;
+  uint64_t column_family_id;
+  const char* column_family_name;
+  size_t column_family_name_len;
+  const char* comparator_name;
+  size_t comparator_name_len;
+  const char* merge_operator_name;
+  size_t merge_operator_name_len;
+  const char* filter_policy_name;
+  size_t filter_policy_name_len;
+  const char* compression_name;
+  size_t compression_name_len;
+  size_t num_user_collected_properties;
+  const char* const* user_collected_names;
+  const size_t* user_collected_names_lens;
+  const char* const* user_collected_values;
+  const size_t* user_collected_values_lens;
+} rust_rocksdb_table_properties_t;
+
+/* Calls `table` with the name and properties of every SST file of `cf`. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_get_properties_of_all_tables_cf(
+    rocksdb_t* db, rocksdb_column_family_handle_t* cf, void* state,
+    void (*table)(void* state, const char* file, size_t file_len,
+                  const rust_rocksdb_table_properties_t* props),
+    char** errptr);
+
+/*
+ * Like `rust_rocksdb_get_properties_of_all_tables_cf`, limited to the files
+ * overlapping one of the `num_ranges` key ranges.
+ */
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_get_properties_of_tables_in_range_cf(
+    rocksdb_t* db, rocksdb_column_family_handle_t* cf, size_t num_ranges,
+    const char* const* start_keys, const size_t* start_key_lens,
+    const char* const* limit_keys, const size_t* limit_key_lens, void* state,
+    void (*table)(void* state, const char* file, size_t file_len,
+                  const rust_rocksdb_table_properties_t* props),
+    char** errptr);
+
+/*
+ * Table properties collector factory implemented through callbacks, see
+ * `TablePropertiesCollectorFactory` in rocksdb/table_properties.h.
+ * `create_collector` returns an opaque collector state, passed to the
+ * `collector_*` callbacks and released with `collector_destroy`.
+ * `entry_type` is the value of `EntryType`, with 9 for `kEntryOther`.
+ * `collector_finish` reports every property with `add`. Keys, values and
+ * names are not NUL-terminated.
+ */
+typedef struct rust_rocksdb_table_properties_collector_callbacks_t {
+  const char* (*name)(void* factory);
+  void* (*create_collector)(void* factory, uint32_t column_family_id,
+                            int level_at_creation);
+  void (*collector_add_user_key)(void* collector, const char* key,
+                                 size_t key_len, const char* value,
+                                 size_t value_len, int entry_type,
+                                 uint64_t seq, uint64_t file_size);
+  void (*collector_finish)(void* collector, void* props,
+                           void (*add)(void* props, const char* name,
+                                       size_t name_len, const char* value,
+                                       size_t value_len));
+  unsigned char (*collector_need_compact)(void* collector);
+  void (*collector_destroy)(void* collector);
+} rust_rocksdb_table_properties_collector_callbacks_t;
+
+/*
+ * Adds a collector factory to the options. `destructor` is called with
+ * `factory` once no options nor database use it anymore.
+ */
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_options_add_table_properties_collector_factory(
+    rocksdb_options_t* opt, void* factory, void (*destructor)(void*),
+    const rust_rocksdb_table_properties_collector_callbacks_t* callbacks);
+
+/* Caches */
+
+typedef struct rust_rocksdb_compressed_secondary_cache_t
+    rust_rocksdb_compressed_secondary_cache_t;
+
+/*
+ * Creates a compressed secondary cache. `compression_type` is the value of
+ * `CompressionType`, as for `rocksdb_options_set_compression`.
+ */
+extern ROCKSDB_LIBRARY_API rust_rocksdb_compressed_secondary_cache_t*
+rust_rocksdb_compressed_secondary_cache_create(
+    size_t capacity, int num_shard_bits, unsigned char strict_capacity_limit,
+    int compression_type, uint32_t compress_format_version);
+
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_compressed_secondary_cache_destroy(
+    rust_rocksdb_compressed_secondary_cache_t* cache);
+
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_compressed_secondary_cache_get_capacity(
+    rust_rocksdb_compressed_secondary_cache_t* cache);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_compressed_secondary_cache_set_capacity(
+    rust_rocksdb_compressed_secondary_cache_t* cache, size_t capacity);
+
+/*
+ * Approximate bytes allocated for the entries of the cache, mostly compressed
+ * blocks. The blocks it decompresses for the primary cache are not counted,
+ * nor is the bookkeeping of the cache.
+ */
+extern ROCKSDB_LIBRARY_API size_t
+rust_rocksdb_compressed_secondary_cache_get_usage(
+    rust_rocksdb_compressed_secondary_cache_t* cache);
+
+/* The options keep a reference to the secondary cache. */
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_lru_cache_options_set_secondary_cache(
+    rocksdb_lru_cache_options_t* opt,
+    rust_rocksdb_compressed_secondary_cache_t* secondary);
+
+extern ROCKSDB_LIBRARY_API rocksdb_cache_t*
+rust_rocksdb_cache_create_hyper_clock_with_secondary(
+    size_t capacity, size_t estimated_entry_charge,
+    rust_rocksdb_compressed_secondary_cache_t* secondary);
+
+/* Number of values of `CacheEntryRole`, checked when building. */
+#define RUST_ROCKSDB_NUM_CACHE_ENTRY_ROLES 14
+
+/*
+ * Fills `charges` and `counts`, indexed by the values of `CacheEntryRole`,
+ * with the total charge and number of the entries of each role. At most
+ * `num_roles` roles are reported.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_cache_get_entry_stats(
+    rocksdb_cache_t* cache, size_t* charges, size_t* counts, size_t num_roles);
+
+extern ROCKSDB_LIBRARY_API unsigned char
+rust_rocksdb_cache_has_strict_capacity_limit(rocksdb_cache_t* cache);
+
+/* Temperatures */
+
+/*
+ * Temperatures are the values of `Temperature`: 0 for unknown, 0x04 hot,
+ * 0x08 warm, 0x0A cool, 0x0C cold and 0x10 ice.
+ */
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_options_set_last_level_temperature(rocksdb_options_t* opt,
+                                                int temperature);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_options_set_preclude_last_level_data_seconds(
+    rocksdb_options_t* opt, uint64_t seconds);
+
+/*
+ * Replaces the thresholds of the FIFO compaction, moving the files older
+ * than `ages[i]` seconds to `temperatures[i]`. Ages must be increasing.
+ */
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_fifo_compaction_options_set_file_temperature_age_thresholds(
+    rocksdb_fifo_compaction_options_t* fifo_opts, const int* temperatures,
+    const uint64_t* ages, size_t num_thresholds);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_fifo_compaction_options_set_allow_trivial_copy(
+    rocksdb_fifo_compaction_options_t* fifo_opts, unsigned char allow);
+
+extern ROCKSDB_LIBRARY_API int rust_rocksdb_livefiles_temperature(
+    const rocksdb_livefiles_t* files, int index);
+
+/* Write batches */
+
+/* Like `rocksdb_writebatch_iterate`, but also reports single deletes. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_writebatch_iterate(
+    rocksdb_writebatch_t* batch, void* state,
+    void (*put)(void*, const char* k, size_t klen, const char* v, size_t vlen),
+    void (*deleted)(void*, const char* k, size_t klen),
+    void (*single_deleted)(void*, const char* k, size_t klen));
+
+/* Like `rocksdb_writebatch_iterate_cf`, but also reports single deletes. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_writebatch_iterate_cf(
+    rocksdb_writebatch_t* batch, void* state,
+    void (*put_cf)(void*, uint32_t cfid, const char* k, size_t klen,
+                   const char* v, size_t vlen),
+    void (*deleted_cf)(void*, uint32_t cfid, const char* k, size_t klen),
+    void (*merge_cf)(void*, uint32_t cfid, const char* k, size_t klen,
+                     const char* v, size_t vlen),
+    void (*single_deleted_cf)(void*, uint32_t cfid, const char* k,
+                              size_t klen));
+
+/*
+ * One operation of a write batch, valid for the duration of the callback.
+ * `type` is 0 for a put, 1 for a merge, 2 for a delete, 3 for a single
+ * delete, 4 for a range deletion, 5 for a wide-column entity and 6 for log
+ * data. A range deletion gives its begin key in `key` and its end key in
+ * `value`, log data gives its blob in `value`, and an entity gives its
+ * columns as parallel arrays of names and values.
+ */
+typedef struct rust_rocksdb_writebatch_op_t {
+  int type;
+  uint32_t column_family_id;
+  const char* key;
+  size_t key_len;
+  const char* value;
+  size_t value_len;
+  size_t num_columns;
+  const char* const* column_names;
+  const size_t* column_names_lens;
+  const char* const* column_values;
+  const size_t* column_values_lens;
+} rust_rocksdb_writebatch_op_t;
+
+/*
+ * Calls `op` for every operation of the batch, in order. Timed puts are
+ * reported as puts, and the markers of two-phase commit are skipped.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_writebatch_iterate_ops(
+    rocksdb_writebatch_t* batch, void* state,
+    void (*op)(void* state, const rust_rocksdb_writebatch_op_t* op),
+    char** errptr);
+
+/* Transactions */
+
+/* `cf` may be NULL, in which case the default column family is used. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transaction_singledelete_cf(
+    rocksdb_transaction_t* txn, rocksdb_column_family_handle_t* cf,
+    const char* key, size_t klen, char** errptr);
+
+/* `cf` may be NULL, in which case the default column family is used. */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transactiondb_singledelete_cf(
+    rocksdb_transactiondb_t* txn_db, const rocksdb_writeoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t klen,
+    char** errptr);
+
+/*
+ * User-defined timestamps of transactions are 64-bit integers, as
+ * `TxnTimestamp`. Only supported by write committed transactions.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transaction_set_commit_timestamp(
+    rocksdb_transaction_t* txn, uint64_t ts, char** errptr);
+
+/* Returns UINT64_MAX if no commit timestamp was set. */
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_transaction_get_commit_timestamp(rocksdb_transaction_t* txn);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_transaction_set_read_timestamp_for_validation(
+    rocksdb_transaction_t* txn, uint64_t ts, char** errptr);
+
+/*
+ * Puts the key, to be written with the commit timestamp, and once the put
+ * succeeded sets the commit timestamp to `ts`, an encoded `TxnTimestamp`
+ * of 8 bytes. Fails if the commit timestamp was already set to another
+ * value. `cf` may be NULL, in which case the default column family is
+ * used.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transaction_put_cf_with_ts(
+    rocksdb_transaction_t* txn, rocksdb_column_family_handle_t* cf,
+    const char* key, size_t klen, const char* ts, size_t tslen,
+    const char* val, size_t vlen, char** errptr);
+
+/*
+ * Like `rocksdb_get_cf_with_ts`. `cf` may be NULL, in which case the
+ * default column family is used.
+ */
+extern ROCKSDB_LIBRARY_API char* rust_rocksdb_transactiondb_get_cf_with_ts(
+    rocksdb_transactiondb_t* txn_db, const rocksdb_readoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t klen,
+    size_t* vlen, char** ts, size_t* tslen, char** errptr);
+
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_transaction_get_id(rocksdb_transaction_t* txn);
+
+/* Time elapsed since the transaction began, in milliseconds. */
+extern ROCKSDB_LIBRARY_API uint64_t
+rust_rocksdb_transaction_get_elapsed_time(rocksdb_transaction_t* txn);
+
+/*
+ * Calls `txn_id` with the ids of the transactions `txn` is waiting for.
+ * `column_family_id` and the key, not NUL-terminated, are those of the lock
+ * it waits on.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transaction_get_waiting_txns(
+    rocksdb_transaction_t* txn, void* state,
+    void (*waiting)(void* state, uint32_t column_family_id, const char* key,
+                    size_t key_len, const uint64_t* txn_ids,
+                    size_t num_txn_ids));
+
+/*
+ * Calls `lock` for every key locked by the point lock manager, with the ids
+ * of the transactions holding it. Keys are not NUL-terminated.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transactiondb_get_lock_status(
+    rocksdb_transactiondb_t* txn_db, void* state,
+    void (*lock)(void* state, uint32_t column_family_id, const char* key,
+                 size_t key_len, const uint64_t* txn_ids, size_t num_txn_ids,
+                 unsigned char exclusive));
+
+/* One transaction of a deadlock cycle, waiting for the next one. */
+typedef struct rust_rocksdb_deadlock_info_t {
+  uint64_t txn_id;
+  uint32_t column_family_id;
+  unsigned char exclusive;
+  const char* waiting_key;
+  size_t waiting_key_len;
+} rust_rocksdb_deadlock_info_t;
+
+/*
+ * Calls `path` for every deadlock kept in the buffer of recent deadlocks,
+ * oldest first. `deadlock_time` is in seconds since the epoch.
+ */
+extern ROCKSDB_LIBRARY_API void rust_rocksdb_transactiondb_get_deadlock_info(
+    rocksdb_transactiondb_t* txn_db, void* state,
+    void (*path)(void* state, const rust_rocksdb_deadlock_info_t* infos,
+                 size_t num_infos, unsigned char limit_exceeded,
+                 int64_t deadlock_time));
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_transactiondb_set_deadlock_info_buffer_size(
+    rocksdb_transactiondb_t* txn_db, uint32_t size);
+
+/*
+ * `policy` is the value of `TxnDBWritePolicy`: 0 for write committed, 1 for
+ * write prepared and 2 for write unprepared.
+ */
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_transactiondb_options_set_write_policy(
+    rocksdb_transactiondb_options_t* opt, int policy);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_transactiondb_options_set_default_write_batch_flush_threshold(
+    rocksdb_transactiondb_options_t* opt, int64_t threshold);
+
+extern ROCKSDB_LIBRARY_API void
+rust_rocksdb_transaction_options_set_write_batch_flush_threshold(
+    rocksdb_transaction_options_t* opt, int64_t threshold);
+
+#ifdef __cplusplus
+} /* end extern "C" */
+#endif
+
+#endif /* RUST_ROCKSDB_EXT_H */
@@ -0,0 +1,147 @@
+// Private helpers shared by the extensions in this directory.
+//
+// The opaque handle types of the C API are defined in `db/c.cc`, which does
+// not publish them. The definitions below mirror the ones from c.cc for the
+// RocksDB version this crate is built against so that the extensions can
+// reach the wrapped C++ objects. Only the leading `rep` members are accessed,
+// but the full layouts are kept so that objects allocated here have the size
+// c.cc expects.
+//
+// A layout change in c.cc would not be detected by the compiler, so the
+// definitions are pinned to the RocksDB version they were checked against:
+// when upgrading, compare them with the new c.cc and bump the version below.
+// The check below only sees the headers the extensions are built with, a
+// system library may be of another version, which
+// `rust_rocksdb_check_linked_version` detects before a database is opened.
+
+#pragma once
+
+#include <cassert>
+#include <cstdlib>
+#include <cstring>
+#include <memory>
+#include <string>
+#include <vector>
+
+#include "rocksdb/cache.h"
+#include "rocksdb/db.h"
+#include "rocksdb/env.h"
+#include "rocksdb/io_status.h"
+#include "rocksdb/iterator.h"
+#include "rocksdb/metadata.h"
+#include "rocksdb/options.h"
+#include "rocksdb/rate_limiter.h"
+#include "rocksdb/slice.h"
+#include "rocksdb/sst_file_writer.h"
+#include "rocksdb/status.h"
+#include "rocksdb/utilities/checkpoint.h"
+#include "rocksdb/utilities/transaction.h"
+#include "rocksdb/utilities/transaction_db.h"
+#include "rocksdb/version.h"
+#include "rocksdb/write_batch.h"
+#include "rust_rocksdb.h"
+
+static_assert(ROCKSDB_MAJOR == 10 && ROCKSDB_MINOR == 4,
+              "the handle definitions of rust_rocksdb_internal.h were checked "
+              "against db/c.cc of RocksDB 10.4, check them again and update "
+              "this version");
+
+struct rocksdb_t {
+  ROCKSDB_NAMESPACE::DB* rep;
+};
+struct rocksdb_column_family_handle_t {
+  ROCKSDB_NAMESPACE::ColumnFamilyHandle* rep;
+  bool immortal;
+};
+struct rocksdb_iterator_t {
+  ROCKSDB_NAMESPACE::Iterator* rep;
+};
+struct rocksdb_writebatch_t {
+  ROCKSDB_NAMESPACE::WriteBatch rep;
+};
+struct rocksdb_env_t {
+  ROCKSDB_NAMESPACE::Env* rep;
+  bool is_default;
+};
+struct rocksdb_options_t {
+  ROCKSDB_NAMESPACE::Options rep;
+};
+struct rocksdb_readoptions_t {
+  ROCKSDB_NAMESPACE::ReadOptions rep;
+  // stack variables to set pointers to in ReadOptions
+  ROCKSDB_NAMESPACE::Slice upper_bound;
+  ROCKSDB_NAMESPACE::Slice lower_bound;
+  ROCKSDB_NAMESPACE::Slice timestamp;
+  ROCKSDB_NAMESPACE::Slice iter_start_ts;
+};
+struct rocksdb_writeoptions_t {
+  ROCKSDB_NAMESPACE::WriteOptions rep;
+};
+struct rocksdb_sstfilewriter_t {
+  ROCKSDB_NAMESPACE::SstFileWriter* rep;
+};
+struct rocksdb_checkpoint_t {
+  ROCKSDB_NAMESPACE::Checkpoint* rep;
+};
+struct rocksdb_transactiondb_t {
+  ROCKSDB_NAMESPACE::TransactionDB* rep;
+};
+struct rocksdb_transaction_t {
+  ROCKSDB_NAMESPACE::Transaction* rep;
+};
+struct rocksdb_transactiondb_options_t {
+  ROCKSDB_NAMESPACE::TransactionDBOptions rep;
+};
+struct rocksdb_transaction_options_t {
+  ROCKSDB_NAMESPACE::TransactionOptions rep;
+};
+struct rocksdb_cache_t {
+  std::shared_ptr<ROCKSDB_NAMESPACE::Cache> rep;
+};
+struct rocksdb_lru_cache_options_t {
+  ROCKSDB_NAMESPACE::LRUCacheOptions rep;
+};
+struct rocksdb_fifo_compaction_options_t {
+  ROCKSDB_NAMESPACE::CompactionOptionsFIFO rep;
+};
+struct rocksdb_livefiles_t {
+  std::vector<ROCKSDB_NAMESPACE::LiveFileMetaData> rep;
+};
+struct rocksdb_ratelimiter_t {
+  std::shared_ptr<ROCKSDB_NAMESPACE::RateLimiter> rep;
+};
+
+// Error slot filled by the callbacks of Rust-implemented file systems and
+// encryption providers through `rust_rocksdb_fs_error_set`.
+struct rust_rocksdb_fs_error_t {
+  ROCKSDB_NAMESPACE::IOStatus status;
+};
+
+namespace rust_rocksdb {
+
+// Stores a non-OK status into `errptr`, the same way `db/c.cc` does.
+inline bool SaveError(char** errptr, const ROCKSDB_NAMESPACE::Status& s) {
+  assert(errptr != nullptr);
+  if (s.ok()) {
+    return false;
+  } else if (*errptr == nullptr) {
+    *errptr = strdup(s.ToString().c_str());
+  } else {
+    free(*errptr);
+    *errptr = strdup(s.ToString().c_str());
+  }
+  return true;
+}
+
+inline char* CopyString(const std::string& str) {
+  char* result = reinterpret_cast<char*>(malloc(sizeof(char) * str.size()));
+  memcpy(result, str.data(), sizeof(char) * str.size());
+  return result;
+}
+
+inline ROCKSDB_NAMESPACE::ColumnFamilyHandle* ColumnFamilyOrDefault(
+    rocksdb_t* db, rocksdb_column_family_handle_t* cf) {
+  return cf != nullptr ? cf->rep : db->rep->DefaultColumnFamily();
+}
+
+}  // namespace rust_rocksdb
@@ -0,0 +1,99 @@
+#include <memory>
+#include <string>
+
+#include "rocksdb/sst_file_manager.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::SstFileManager;
+using ROCKSDB_NAMESPACE::Status;
+using rust_rocksdb::SaveError;
+
+struct rust_rocksdb_sst_file_manager_t {
+  std::shared_ptr<SstFileManager> rep;
+};
+
+extern "C" {
+
+rust_rocksdb_sst_file_manager_t* rust_rocksdb_sst_file_manager_create(
+    rocksdb_env_t* env, char** errptr) {
+  Status s;
+  std::shared_ptr<SstFileManager> sfm(ROCKSDB_NAMESPACE::NewSstFileManager(
+      env->rep, /*info_log=*/nullptr, /*trash_dir=*/"",
+      /*rate_bytes_per_sec=*/0, /*delete_existing_trash=*/true, &s));
+  if (SaveError(errptr, s)) {
+    return nullptr;
+  }
+  return new rust_rocksdb_sst_file_manager_t{std::move(sfm)};
+}
+
+void rust_rocksdb_sst_file_manager_destroy(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  delete sfm;
+}
+
+void rust_rocksdb_options_set_sst_file_manager(
+    rocksdb_options_t* opt, rust_rocksdb_sst_file_manager_t* sfm) {
+  opt->rep.sst_file_manager = sfm->rep;
+}
+
+void rust_rocksdb_sst_file_manager_set_max_allowed_space_usage(
+    rust_rocksdb_sst_file_manager_t* sfm, uint64_t max_allowed_space) {
+  sfm->rep->SetMaxAllowedSpaceUsage(max_allowed_space);
+}
+
+void rust_rocksdb_sst_file_manager_set_compaction_buffer_size(
+    rust_rocksdb_sst_file_manager_t* sfm, uint64_t compaction_buffer_size) {
+  sfm->rep->SetCompactionBufferSize(compaction_buffer_size);
+}
+
+unsigned char rust_rocksdb_sst_file_manager_is_max_allowed_space_reached(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  return sfm->rep->IsMaxAllowedSpaceReached();
+}
+
+unsigned char
+rust_rocksdb_sst_file_manager_is_max_space_reached_with_compactions(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  return sfm->rep->IsMaxAllowedSpaceReachedIncludingCompactions();
+}
+
+uint64_t rust_rocksdb_sst_file_manager_get_total_size(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  return sfm->rep->GetTotalSize();
+}
+
+void rust_rocksdb_sst_file_manager_get_tracked_files(
+    rust_rocksdb_sst_file_manager_t* sfm, void* state,
+    void (*file)(void* state, const char* path, size_t path_len,
+                 uint64_t size)) {
+  for (const auto& entry : sfm->rep->GetTrackedFiles()) {
+    file(state, entry.first.data(), entry.first.size(), entry.second);
+  }
+}
+
+int64_t rust_rocksdb_sst_file_manager_get_delete_rate_bytes_per_second(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  return sfm->rep->GetDeleteRateBytesPerSecond();
+}
+
+void rust_rocksdb_sst_file_manager_set_delete_rate_bytes_per_second(
+    rust_rocksdb_sst_file_manager_t* sfm, int64_t delete_rate) {
+  sfm->rep->SetDeleteRateBytesPerSecond(delete_rate);
+}
+
+double rust_rocksdb_sst_file_manager_get_max_trash_db_ratio(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  return sfm->rep->GetMaxTrashDBRatio();
+}
+
+void rust_rocksdb_sst_file_manager_set_max_trash_db_ratio(
+    rust_rocksdb_sst_file_manager_t* sfm, double ratio) {
+  sfm->rep->SetMaxTrashDBRatio(ratio);
+}
+
+uint64_t rust_rocksdb_sst_file_manager_get_total_trash_size(
+    rust_rocksdb_sst_file_manager_t* sfm) {
+  return sfm->rep->GetTotalTrashSize();
+}
+
+}  // end extern "C"
@@ -0,0 +1,219 @@
+#include <memory>
+#include <string>
+#include <vector>
+
+#include "rocksdb/table_properties.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::EntryType;
+using ROCKSDB_NAMESPACE::Range;
+using ROCKSDB_NAMESPACE::SequenceNumber;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+using ROCKSDB_NAMESPACE::TableProperties;
+using ROCKSDB_NAMESPACE::TablePropertiesCollection;
+using ROCKSDB_NAMESPACE::TablePropertiesCollector;
+using ROCKSDB_NAMESPACE::TablePropertiesCollectorFactory;
+using ROCKSDB_NAMESPACE::UserCollectedProperties;
+using rust_rocksdb::ColumnFamilyOrDefault;
+using rust_rocksdb::SaveError;
+
+namespace {
+
+int ConvertEntryType(EntryType type) {
+  switch (type) {
+    case EntryType::kEntryPut:
+      return 0;
+    case EntryType::kEntryDelete:
+      return 1;
+    case EntryType::kEntrySingleDelete:
+      return 2;
+    case EntryType::kEntryMerge:
+      return 3;
+    case EntryType::kEntryRangeDeletion:
+      return 4;
+    case EntryType::kEntryBlobIndex:
+      return 5;
+    case EntryType::kEntryDeleteWithTimestamp:
+      return 6;
+    case EntryType::kEntryWideColumnEntity:
+      return 7;
+    case EntryType::kEntryTimedPut:
+      return 8;
+    default:
+      return 9;
+  }
+}
+
+class RustTablePropertiesCollector : public TablePropertiesCollector {
+ public:
+  RustTablePropertiesCollector(
+      void* state, std::string name,
+      const rust_rocksdb_table_properties_collector_callbacks_t& callbacks)
+      : state_(state), name_(std::move(name)), callbacks_(callbacks) {}
+
+  ~RustTablePropertiesCollector() override {
+    callbacks_.collector_destroy(state_);
+  }
+
+  Status AddUserKey(const Slice& key, const Slice& value, EntryType type,
+                    SequenceNumber seq, uint64_t file_size) override {
+    callbacks_.collector_add_user_key(state_, key.data(), key.size(),
+                                      value.data(), value.size(),
+                                      ConvertEntryType(type), seq, file_size);
+    return Status::OK();
+  }
+
+  Status Finish(UserCollectedProperties* properties) override {
+    callbacks_.collector_finish(state_, properties, &AddProperty);
+    return Status::OK();
+  }
+
+  UserCollectedProperties GetReadableProperties() const override {
+    return UserCollectedProperties();
+  }
+
+  const char* Name() const override { return name_.c_str(); }
+
+  bool NeedCompact() const override {
+    return callbacks_.collector_need_compact(state_) != 0;
+  }
+
+ private:
+  static void AddProperty(void* props, const char* name, size_t name_len,
+                          const char* value, size_t value_len) {
+    (*static_cast<UserCollectedProperties*>(props))[std::string(
+        name, name_len)] = std::string(value, value_len);
+  }
+
+  void* state_;
+  std::string name_;
+  rust_rocksdb_table_properties_collector_callbacks_t callbacks_;
+};
+
+class RustTablePropertiesCollectorFactory
+    : public TablePropertiesCollectorFactory {
+ public:
+  RustTablePropertiesCollectorFactory(
+      void* state, void (*destructor)(void*),
+      const rust_rocksdb_table_properties_collector_callbacks_t& callbacks)
+      : state_(state), destructor_(destructor), callbacks_(callbacks) {}
+
+  ~RustTablePropertiesCollectorFactory() override { destructor_(state_); }
+
+  TablePropertiesCollector* CreateTablePropertiesCollector(
+      TablePropertiesCollectorFactory::Context context) override {
+    void* collector = callbacks_.create_collector(
+        state_, context.column_family_id, context.level_at_creation);
+    return new RustTablePropertiesCollector(collector, Name(), callbacks_);
+  }
+
+  const char* Name() const override { return callbacks_.name(state_); }
+
+ private:
+  void* state_;
+  void (*destructor_)(void*);
+  rust_rocksdb_table_properties_collector_callbacks_t callbacks_;
+};
+
+void ReportTables(const TablePropertiesCollection& tables, void* state,
+                  void (*table)(void* state, const char* file,
+                                size_t file_len,
+                                const rust_rocksdb_table_properties_t* props)) {
+  for (const auto& entry : tables) {
+    const TableProperties& tp = *entry.second;
+    std::vector<const char*> names;
+    std::vector<size_t> names_lens;
+    std::vector<const char*> values;
+    std::vector<size_t> values_lens;
+    for (const auto& prop : tp.user_collected_properties) {
+      names.push_back(prop.first.data());
+      names_lens.push_back(prop.first.size());
+      values.push_back(prop.second.data());
+      values_lens.push_back(prop.second.size());
+    }
+
+    rust_rocksdb_table_properties_t props;
+    props.data_size = tp.data_size;
+    props.index_size = tp.index_size;
+    props.filter_size = tp.filter_size;
+    props.raw_key_size = tp.raw_key_size;
+    props.raw_value_size = tp.raw_value_size;
+    props.num_data_blocks = tp.num_data_blocks;
+    props.num_entries = tp.num_entries;
+    props.num_deletions = tp.num_deletions;
+    props.num_merge_operands = tp.num_merge_operands;
+    props.num_range_deletions = tp.num_range_deletions;
+    props.format_version = tp.format_version;
+    props.creation_time = tp.creation_time;
+    props.oldest_key_time = tp.oldest_key_time;
+    props.file_creation_time = tp.file_creation_time;
+    props.column_family_id = tp.column_family_id;
+    props.column_family_name = tp.column_family_name.data();
+    props.column_family_name_len = tp.column_family_name.size();
+    props.comparator_name = tp.comparator_name.data();
+    props.comparator_name_len = tp.comparator_name.size();
+    props.merge_operator_name = tp.merge_operator_name.data();
+    props.merge_operator_name_len = tp.merge_operator_name.size();
+    props.filter_policy_name = tp.filter_policy_name.data();
+    props.filter_policy_name_len = tp.filter_policy_name.size();
+    props.compression_name = tp.compression_name.data();
+    props.compression_name_len = tp.compression_name.size();
+    props.num_user_collected_properties = names.size();
+    props.user_collected_names = names.data();
+    props.user_collected_names_lens = names_lens.data();
+    props.user_collected_values = values.data();
+    props.user_collected_values_lens = values_lens.data();
+    table(state, entry.first.data(), entry.first.size(), &props);
+  }
+}
+
+}  // namespace
+
+extern "C" {
+
+void rust_rocksdb_get_properties_of_all_tables_cf(
+    rocksdb_t* db, rocksdb_column_family_handle_t* cf, void* state,
+    void (*table)(void* state, const char* file, size_t file_len,
+                  const rust_rocksdb_table_properties_t* props),
+    char** errptr) {
+  TablePropertiesCollection tables;
+  Status s = db->rep->GetPropertiesOfAllTables(ColumnFamilyOrDefault(db, cf),
+                                               &tables);
+  if (SaveError(errptr, s)) {
+    return;
+  }
+  ReportTables(tables, state, table);
+}
+
+void rust_rocksdb_get_properties_of_tables_in_range_cf(
+    rocksdb_t* db, rocksdb_column_family_handle_t* cf, size_t num_ranges,
+    const char* const* start_keys, const size_t* start_key_lens,
+    const char* const* limit_keys, const size_t* limit_key_lens, void* state,
+    void (*table)(void* state, const char* file, size_t file_len,
+                  const rust_rocksdb_table_properties_t* props),
+    char** errptr) {
+  std::vector<Range> ranges;
+  ranges.reserve(num_ranges);
+  for (size_t i = 0; i < num_ranges; i++) {
+    ranges.emplace_back(Slice(start_keys[i], start_key_lens[i]),
+                        Slice(limit_keys[i], limit_key_lens[i]));
+  }
+  TablePropertiesCollection tables;
+  Status s = db->rep->GetPropertiesOfTablesInRange(
+      ColumnFamilyOrDefault(db, cf), ranges.data(), ranges.size(), &tables);
+  if (SaveError(errptr, s)) {
+    return;
+  }
+  ReportTables(tables, state, table);
+}
+
+void rust_rocksdb_options_add_table_properties_collector_factory(
+    rocksdb_options_t* opt, void* factory, void (*destructor)(void*),
+    const rust_rocksdb_table_properties_collector_callbacks_t* callbacks) {
+  opt->rep.table_properties_collector_factories.push_back(
+      std::make_shared<RustTablePropertiesCollectorFactory>(factory, destructor,
+                                                            *callbacks));
+}
+
+}  // end extern "C"
@@ -0,0 +1,50 @@
+#include <vector>
+
+#include "rocksdb/advanced_options.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::FileTemperatureAge;
+using ROCKSDB_NAMESPACE::Temperature;
+
+namespace {
+
+Temperature ToTemperature(int temperature) {
+  return static_cast<Temperature>(static_cast<uint8_t>(temperature));
+}
+
+}  // namespace
+
+extern "C" {
+
+void rust_rocksdb_options_set_last_level_temperature(rocksdb_options_t* opt,
+                                                     int temperature) {
+  opt->rep.last_level_temperature = ToTemperature(temperature);
+}
+
+void rust_rocksdb_options_set_preclude_last_level_data_seconds(
+    rocksdb_options_t* opt, uint64_t seconds) {
+  opt->rep.preclude_last_level_data_seconds = seconds;
+}
+
+void rust_rocksdb_fifo_compaction_options_set_file_temperature_age_thresholds(
+    rocksdb_fifo_compaction_options_t* fifo_opts, const int* temperatures,
+    const uint64_t* ages, size_t num_thresholds) {
+  std::vector<FileTemperatureAge> thresholds;
+  thresholds.reserve(num_thresholds);
+  for (size_t i = 0; i < num_thresholds; i++) {
+    thresholds.push_back({ToTemperature(temperatures[i]), ages[i]});
+  }
+  fifo_opts->rep.file_temperature_age_thresholds = std::move(thresholds);
+}
+
+void rust_rocksdb_fifo_compaction_options_set_allow_trivial_copy(
+    rocksdb_fifo_compaction_options_t* fifo_opts, unsigned char allow) {
+  fifo_opts->rep.allow_trivial_copy_when_change_temperature = allow != 0;
+}
+
+int rust_rocksdb_livefiles_temperature(const rocksdb_livefiles_t* files,
+                                       int index) {
+  return static_cast<int>(files->rep[index].temperature);
+}
+
+}  // end extern "C"
@@ -0,0 +1,192 @@
+#include <string>
+#include <vector>
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::ColumnFamilyHandle;
+using ROCKSDB_NAMESPACE::DeadlockInfo;
+using ROCKSDB_NAMESPACE::DeadlockPath;
+using ROCKSDB_NAMESPACE::kMaxTxnTimestamp;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+using ROCKSDB_NAMESPACE::TransactionID;
+using ROCKSDB_NAMESPACE::TxnDBWritePolicy;
+using ROCKSDB_NAMESPACE::TxnTimestamp;
+using rust_rocksdb::CopyString;
+using rust_rocksdb::SaveError;
+
+namespace {
+
+// Timestamps are encoded as by `EncodeFixed64`, little-endian.
+TxnTimestamp DecodeTxnTimestamp(const char* ts) {
+  TxnTimestamp result = 0;
+  for (size_t i = 0; i < sizeof(TxnTimestamp); ++i) {
+    result |= static_cast<TxnTimestamp>(static_cast<unsigned char>(ts[i]))
+              << (8 * i);
+  }
+  return result;
+}
+
+}  // namespace
+
+extern "C" {
+
+void rust_rocksdb_transaction_singledelete_cf(
+    rocksdb_transaction_t* txn, rocksdb_column_family_handle_t* cf,
+    const char* key, size_t klen, char** errptr) {
+  if (cf == nullptr) {
+    SaveError(errptr, txn->rep->SingleDelete(Slice(key, klen)));
+  } else {
+    SaveError(errptr, txn->rep->SingleDelete(cf->rep, Slice(key, klen)));
+  }
+}
+
+void rust_rocksdb_transactiondb_singledelete_cf(
+    rocksdb_transactiondb_t* txn_db, const rocksdb_writeoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t klen,
+    char** errptr) {
+  ColumnFamilyHandle* handle =
+      cf != nullptr ? cf->rep : txn_db->rep->DefaultColumnFamily();
+  SaveError(errptr,
+            txn_db->rep->SingleDelete(options->rep, handle, Slice(key, klen)));
+}
+
+void rust_rocksdb_transaction_set_commit_timestamp(rocksdb_transaction_t* txn,
+                                                   uint64_t ts,
+                                                   char** errptr) {
+  SaveError(errptr, txn->rep->SetCommitTimestamp(ts));
+}
+
+uint64_t rust_rocksdb_transaction_get_commit_timestamp(
+    rocksdb_transaction_t* txn) {
+  return txn->rep->GetCommitTimestamp();
+}
+
+void rust_rocksdb_transaction_set_read_timestamp_for_validation(
+    rocksdb_transaction_t* txn, uint64_t ts, char** errptr) {
+  SaveError(errptr, txn->rep->SetReadTimestampForValidation(ts));
+}
+
+void rust_rocksdb_transaction_put_cf_with_ts(
+    rocksdb_transaction_t* txn, rocksdb_column_family_handle_t* cf,
+    const char* key, size_t klen, const char* ts, size_t tslen,
+    const char* val, size_t vlen, char** errptr) {
+  if (tslen != sizeof(TxnTimestamp)) {
+    SaveError(errptr, Status::InvalidArgument(
+                          "transaction timestamps are 8 bytes long"));
+    return;
+  }
+  TxnTimestamp commit_ts = DecodeTxnTimestamp(ts);
+  TxnTimestamp current_ts = txn->rep->GetCommitTimestamp();
+  if (current_ts != kMaxTxnTimestamp && current_ts != commit_ts) {
+    SaveError(errptr, Status::InvalidArgument(
+                          "all the keys of a transaction are written with "
+                          "the same timestamp"));
+    return;
+  }
+  Status s = cf == nullptr
+                 ? txn->rep->Put(Slice(key, klen), Slice(val, vlen))
+                 : txn->rep->Put(cf->rep, Slice(key, klen), Slice(val, vlen));
+  if (s.ok()) {
+    s = txn->rep->SetCommitTimestamp(commit_ts);
+  }
+  SaveError(errptr, s);
+}
+
+char* rust_rocksdb_transactiondb_get_cf_with_ts(
+    rocksdb_transactiondb_t* txn_db, const rocksdb_readoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t klen,
+    size_t* vlen, char** ts, size_t* tslen, char** errptr) {
+  ColumnFamilyHandle* handle =
+      cf != nullptr ? cf->rep : txn_db->rep->DefaultColumnFamily();
+  std::string value;
+  std::string timestamp;
+  Status s = txn_db->rep->Get(options->rep, handle, Slice(key, klen), &value,
+                              &timestamp);
+  *vlen = 0;
+  *ts = nullptr;
+  *tslen = 0;
+  if (s.IsNotFound()) {
+    return nullptr;
+  }
+  if (SaveError(errptr, s)) {
+    return nullptr;
+  }
+  *vlen = value.size();
+  *tslen = timestamp.size();
+  *ts = CopyString(timestamp);
+  return CopyString(value);
+}
+
+uint64_t rust_rocksdb_transaction_get_id(rocksdb_transaction_t* txn) {
+  return txn->rep->GetID();
+}
+
+uint64_t rust_rocksdb_transaction_get_elapsed_time(
+    rocksdb_transaction_t* txn) {
+  return txn->rep->GetElapsedTime();
+}
+
+void rust_rocksdb_transaction_get_waiting_txns(
+    rocksdb_transaction_t* txn, void* state,
+    void (*waiting)(void* state, uint32_t column_family_id, const char* key,
+                    size_t key_len, const uint64_t* txn_ids,
+                    size_t num_txn_ids)) {
+  uint32_t column_family_id = 0;
+  std::string key;
+  std::vector<TransactionID> ids =
+      txn->rep->GetWaitingTxns(&column_family_id, &key);
+  waiting(state, column_family_id, key.data(), key.size(), ids.data(),
+          ids.size());
+}
+
+void rust_rocksdb_transactiondb_get_lock_status(
+    rocksdb_transactiondb_t* txn_db, void* state,
+    void (*lock)(void* state, uint32_t column_family_id, const char* key,
+                 size_t key_len, const uint64_t* txn_ids, size_t num_txn_ids,
+                 unsigned char exclusive)) {
+  for (const auto& entry : txn_db->rep->GetLockStatusData()) {
+    const auto& info = entry.second;
+    lock(state, entry.first, info.key.data(), info.key.size(),
+         info.ids.data(), info.ids.size(), info.exclusive);
+  }
+}
+
+void rust_rocksdb_transactiondb_get_deadlock_info(
+    rocksdb_transactiondb_t* txn_db, void* state,
+    void (*path)(void* state, const rust_rocksdb_deadlock_info_t* infos,
+                 size_t num_infos, unsigned char limit_exceeded,
+                 int64_t deadlock_time)) {
+  for (const DeadlockPath& deadlock : txn_db->rep->GetDeadlockInfoBuffer()) {
+    std::vector<rust_rocksdb_deadlock_info_t> infos;
+    infos.reserve(deadlock.path.size());
+    for (const DeadlockInfo& info : deadlock.path) {
+      infos.push_back({info.m_txn_id, info.m_cf_id, info.m_exclusive,
+                       info.m_waiting_key.data(), info.m_waiting_key.size()});
+    }
+    path(state, infos.data(), infos.size(), deadlock.limit_exceeded,
+         deadlock.deadlock_time);
+  }
+}
+
+void rust_rocksdb_transactiondb_set_deadlock_info_buffer_size(
+    rocksdb_transactiondb_t* txn_db, uint32_t size) {
+  txn_db->rep->SetDeadlockInfoBufferSize(size);
+}
+
+void rust_rocksdb_transactiondb_options_set_write_policy(
+    rocksdb_transactiondb_options_t* opt, int policy) {
+  opt->rep.write_policy = static_cast<TxnDBWritePolicy>(policy);
+}
+
+void rust_rocksdb_transactiondb_options_set_default_write_batch_flush_threshold(
+    rocksdb_transactiondb_options_t* opt, int64_t threshold) {
+  opt->rep.default_write_batch_flush_threshold = threshold;
+}
+
+void rust_rocksdb_transaction_options_set_write_batch_flush_threshold(
+    rocksdb_transaction_options_t* opt, int64_t threshold) {
+  opt->rep.write_batch_flush_threshold = threshold;
+}
+
+}  // end extern "C"
@@ -0,0 +1,25 @@
+#include <string>
+
+#include "rocksdb/version.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::GetRocksVersionAsString;
+using ROCKSDB_NAMESPACE::Status;
+using rust_rocksdb::SaveError;
+
+extern "C" {
+
+void rust_rocksdb_check_linked_version(char** errptr) {
+  // The library reports the version it was compiled with, which may differ
+  // from the one of the headers when a system library is linked
+  const std::string built =
+      std::to_string(ROCKSDB_MAJOR) + "." + std::to_string(ROCKSDB_MINOR);
+  const std::string linked = GetRocksVersionAsString(false);
+  if (linked != built) {
+    SaveError(errptr, Status::NotSupported(
+                          "rust-rocksdb was built for RocksDB " + built +
+                          " but RocksDB " + linked + " is linked"));
+  }
+}
+
+}  // end extern "C"
@@ -0,0 +1,131 @@
+#include "rocksdb/wide_columns.h"
+
+#include <vector>
+
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::PinnableWideColumns;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+using ROCKSDB_NAMESPACE::WideColumn;
+using ROCKSDB_NAMESPACE::WideColumns;
+using rust_rocksdb::ColumnFamilyOrDefault;
+using rust_rocksdb::SaveError;
+
+struct rust_rocksdb_wide_columns_t {
+  PinnableWideColumns rep;
+};
+
+static WideColumns ToWideColumns(size_t num_columns, const char* const* names,
+                                 const size_t* names_sizes,
+                                 const char* const* values,
+                                 const size_t* values_sizes) {
+  WideColumns columns;
+  columns.reserve(num_columns);
+  for (size_t i = 0; i < num_columns; i++) {
+    columns.emplace_back(Slice(names[i], names_sizes[i]),
+                         Slice(values[i], values_sizes[i]));
+  }
+  return columns;
+}
+
+static const char* ColumnName(const WideColumns& columns, size_t index,
+                              size_t* len) {
+  const Slice& name = columns[index].name();
+  *len = name.size();
+  return name.data();
+}
+
+static const char* ColumnValue(const WideColumns& columns, size_t index,
+                               size_t* len) {
+  const Slice& value = columns[index].value();
+  *len = value.size();
+  return value.data();
+}
+
+extern "C" {
+
+void rust_rocksdb_put_entity_cf(
+    rocksdb_t* db, const rocksdb_writeoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t keylen,
+    size_t num_columns, const char* const* names, const size_t* names_sizes,
+    const char* const* values, const size_t* values_sizes, char** errptr) {
+  SaveError(errptr,
+            db->rep->PutEntity(options->rep, ColumnFamilyOrDefault(db, cf),
+                               Slice(key, keylen),
+                               ToWideColumns(num_columns, names, names_sizes,
+                                             values, values_sizes)));
+}
+
+rust_rocksdb_wide_columns_t* rust_rocksdb_get_entity_cf(
+    rocksdb_t* db, const rocksdb_readoptions_t* options,
+    rocksdb_column_family_handle_t* cf, const char* key, size_t keylen,
+    char** errptr) {
+  auto* result = new rust_rocksdb_wide_columns_t;
+  Status s = db->rep->GetEntity(options->rep, ColumnFamilyOrDefault(db, cf),
+                                Slice(key, keylen), &result->rep);
+  if (!s.ok()) {
+    delete result;
+    if (!s.IsNotFound()) {
+      SaveError(errptr, s);
+    }
+    return nullptr;
+  }
+  return result;
+}
+
+size_t rust_rocksdb_wide_columns_count(
+    const rust_rocksdb_wide_columns_t* columns) {
+  return columns->rep.columns().size();
+}
+
+const char* rust_rocksdb_wide_columns_name(
+    const rust_rocksdb_wide_columns_t* columns, size_t index, size_t* len) {
+  return ColumnName(columns->rep.columns(), index, len);
+}
+
+const char* rust_rocksdb_wide_columns_value(
+    const rust_rocksdb_wide_columns_t* columns, size_t index, size_t* len) {
+  return ColumnValue(columns->rep.columns(), index, len);
+}
+
+void rust_rocksdb_wide_columns_destroy(rust_rocksdb_wide_columns_t* columns) {
+  delete columns;
+}
+
+void rust_rocksdb_writebatch_put_entity_cf(
+    rocksdb_writebatch_t* batch, rocksdb_column_family_handle_t* cf,
+    const char* key, size_t keylen, size_t num_columns,
+    const char* const* names, const size_t* names_sizes,
+    const char* const* values, const size_t* values_sizes, char** errptr) {
+  SaveError(errptr, batch->rep.PutEntity(
+                        cf->rep, Slice(key, keylen),
+                        ToWideColumns(num_columns, names, names_sizes, values,
+                                      values_sizes)));
+}
+
+void rust_rocksdb_sstfilewriter_put_entity(
+    rocksdb_sstfilewriter_t* writer, const char* key, size_t keylen,
+    size_t num_columns, const char* const* names, const size_t* names_sizes,
+    const char* const* values, const size_t* values_sizes, char** errptr) {
+  SaveError(errptr, writer->rep->PutEntity(
+                        Slice(key, keylen),
+                        ToWideColumns(num_columns, names, names_sizes, values,
+                                      values_sizes)));
+}
+
+size_t rust_rocksdb_iter_columns_count(const rocksdb_iterator_t* iter) {
+  return iter->rep->columns().size();
+}
+
+const char* rust_rocksdb_iter_column_name(const rocksdb_iterator_t* iter,
+                                          size_t index, size_t* len) {
+  return ColumnName(iter->rep->columns(), index, len);
+}
+
+const char* rust_rocksdb_iter_column_value(const rocksdb_iterator_t* iter,
+                                           size_t index, size_t* len) {
+  return ColumnValue(iter->rep->columns(), index, len);
+}
+
+}  // end extern "C"
@@ -0,0 +1,221 @@
+#include <vector>
+
+#include "rocksdb/wide_columns.h"
+#include "rust_rocksdb_internal.h"
+
+using ROCKSDB_NAMESPACE::PinnableWideColumns;
+using ROCKSDB_NAMESPACE::Slice;
+using ROCKSDB_NAMESPACE::Status;
+using ROCKSDB_NAMESPACE::WideColumn;
+using ROCKSDB_NAMESPACE::WriteBatch;
+using rust_rocksdb::SaveError;
+
+namespace {
+
+// Reports every operation of a batch through a single callback.
+class OpsHandler : public WriteBatch::Handler {
+ public:
+  OpsHandler(void* state,
+             void (*op)(void* state, const rust_rocksdb_writebatch_op_t* op))
+      : state_(state), op_(op) {}
+
+  Status PutCF(uint32_t column_family_id, const Slice& key,
+               const Slice& value) override {
+    Report(0, column_family_id, key, value);
+    return Status::OK();
+  }
+
+  Status TimedPutCF(uint32_t column_family_id, const Slice& key,
+                    const Slice& value, uint64_t /*write_time*/) override {
+    Report(0, column_family_id, key, value);
+    return Status::OK();
+  }
+
+  Status MergeCF(uint32_t column_family_id, const Slice& key,
+                 const Slice& value) override {
+    Report(1, column_family_id, key, value);
+    return Status::OK();
+  }
+
+  Status DeleteCF(uint32_t column_family_id, const Slice& key) override {
+    Report(2, column_family_id, key, Slice());
+    return Status::OK();
+  }
+
+  Status SingleDeleteCF(uint32_t column_family_id,
+                        const Slice& key) override {
+    Report(3, column_family_id, key, Slice());
+    return Status::OK();
+  }
+
+  Status DeleteRangeCF(uint32_t column_family_id, const Slice& begin_key,
+                       const Slice& end_key) override {
+    Report(4, column_family_id, begin_key, end_key);
+    return Status::OK();
+  }
+
+  Status PutEntityCF(uint32_t column_family_id, const Slice& key,
+                     const Slice& entity) override {
+    PinnableWideColumns columns;
+    Status s = columns.SetWideColumnValue(entity);
+    if (!s.ok()) {
+      return s;
+    }
+    std::vector<const char*> names;
+    std::vector<size_t> names_lens;
+    std::vector<const char*> values;
+    std::vector<size_t> values_lens;
+    for (const WideColumn& column : columns.columns()) {
+      names.push_back(column.name().data());
+      names_lens.push_back(column.name().size());
+      values.push_back(column.value().data());
+      values_lens.push_back(column.value().size());
+    }
+    rust_rocksdb_writebatch_op_t op = MakeOp(5, column_family_id, key, Slice());
+    op.num_columns = names.size();
+    op.column_names = names.data();
+    op.column_names_lens = names_lens.data();
+    op.column_values = values.data();
+    op.column_values_lens = values_lens.data();
+    op_(state_, &op);
+    return Status::OK();
+  }
+
+  void LogData(const Slice& blob) override { Report(6, 0, Slice(), blob); }
+
+  Status MarkBeginPrepare(bool /*unprepared*/) override {
+    return Status::OK();
+  }
+  Status MarkEndPrepare(const Slice& /*xid*/) override { return Status::OK(); }
+  Status MarkNoop(bool /*empty_batch*/) override { return Status::OK(); }
+  Status MarkRollback(const Slice& /*xid*/) override { return Status::OK(); }
+  Status MarkCommit(const Slice& /*xid*/) override { return Status::OK(); }
+  Status MarkCommitWithTimestamp(const Slice& /*xid*/,
+                                 const Slice& /*commit_ts*/) override {
+    return Status::OK();
+  }
+
+ private:
+  static rust_rocksdb_writebatch_op_t MakeOp(int type,
+                                             uint32_t column_family_id,
+                                             const Slice& key,
+                                             const Slice& value) {
+    rust_rocksdb_writebatch_op_t op;
+    op.type = type;
+    op.column_family_id = column_family_id;
+    op.key = key.data();
+    op.key_len = key.size();
+    op.value = value.data();
+    op.value_len = value.size();
+    op.num_columns = 0;
+    op.column_names = nullptr;
+    op.column_names_lens = nullptr;
+    op.column_values = nullptr;
+    op.column_values_lens = nullptr;
+    return op;
+  }
+
+  void Report(int type, uint32_t column_family_id, const Slice& key,
+              const Slice& value) {
+    rust_rocksdb_writebatch_op_t op =
+        MakeOp(type, column_family_id, key, value);
+    op_(state_, &op);
+  }
+
+  void* state_;
+  void (*op_)(void* state, const rust_rocksdb_writebatch_op_t* op);
+};
+
+}  // namespace
+
+extern "C" {
+
+void rust_rocksdb_writebatch_iterate(
+    rocksdb_writebatch_t* batch, void* state,
+    void (*put)(void*, const char* k, size_t klen, const char* v, size_t vlen),
+    void (*deleted)(void*, const char* k, size_t klen),
+    void (*single_deleted)(void*, const char* k, size_t klen)) {
+  class H : public WriteBatch::Handler {
+   public:
+    void* state_;
+    void (*put_)(void*, const char* k, size_t klen, const char* v,
+                 size_t vlen);
+    void (*deleted_)(void*, const char* k, size_t klen);
+    void (*single_deleted_)(void*, const char* k, size_t klen);
+    void Put(const Slice& key, const Slice& value) override {
+      (*put_)(state_, key.data(), key.size(), value.data(), value.size());
+    }
+    void Delete(const Slice& key) override {
+      (*deleted_)(state_, key.data(), key.size());
+    }
+    void SingleDelete(const Slice& key) override {
+      (*single_deleted_)(state_, key.data(), key.size());
+    }
+  };
+  H handler;
+  handler.state_ = state;
+  handler.put_ = put;
+  handler.deleted_ = deleted;
+  handler.single_deleted_ = single_deleted;
+  batch->rep.Iterate(&handler);
+}
+
+void rust_rocksdb_writebatch_iterate_cf(
+    rocksdb_writebatch_t* batch, void* state,
+    void (*put_cf)(void*, uint32_t cfid, const char* k, size_t klen,
+                   const char* v, size_t vlen),
+    void (*deleted_cf)(void*, uint32_t cfid, const char* k, size_t klen),
+    void (*merge_cf)(void*, uint32_t cfid, const char* k, size_t klen,
+                     const char* v, size_t vlen),
+    void (*single_deleted_cf)(void*, uint32_t cfid, const char* k,
+                              size_t klen)) {
+  class H : public WriteBatch::Handler {
+   public:
+    void* state_;
+    void (*put_cf_)(void*, uint32_t cfid, const char* k, size_t klen,
+                    const char* v, size_t vlen);
+    void (*deleted_cf_)(void*, uint32_t cfid, const char* k, size_t klen);
+    void (*merge_cf_)(void*, uint32_t cfid, const char* k, size_t klen,
+                      const char* v, size_t vlen);
+    void (*single_deleted_cf_)(void*, uint32_t cfid, const char* k,
+                               size_t klen);
+    Status PutCF(uint32_t column_family_id, const Slice& key,
+                 const Slice& value) override {
+      (*put_cf_)(state_, column_family_id, key.data(), key.size(),
+                 value.data(), value.size());
+      return Status::OK();
+    }
+    Status DeleteCF(uint32_t column_family_id, const Slice& key) override {
+      (*deleted_cf_)(state_, column_family_id, key.data(), key.size());
+      return Status::OK();
+    }
+    Status MergeCF(uint32_t column_family_id, const Slice& key,
+                   const Slice& value) override {
+      (*merge_cf_)(state_, column_family_id, key.data(), key.size(),
+                   value.data(), value.size());
+      return Status::OK();
+    }
+    Status SingleDeleteCF(uint32_t column_family_id,
+                          const Slice& key) override {
+      (*single_deleted_cf_)(state_, column_family_id, key.data(), key.size());
+      return Status::OK();
+    }
+  };
+  H handler;
+  handler.state_ = state;
+  handler.put_cf_ = put_cf;
+  handler.deleted_cf_ = deleted_cf;
+  handler.merge_cf_ = merge_cf;
+  handler.single_deleted_cf_ = single_deleted_cf;
+  batch->rep.Iterate(&handler);
+}
+
+void rust_rocksdb_writebatch_iterate_ops(
+    rocksdb_writebatch_t* batch, void* state,
+    void (*op)(void* state, const rust_rocksdb_writebatch_op_t* op),
+    char** errptr) {
+  OpsHandler handler(state, op);
+  SaveError(errptr, batch->rep.Iterate(&handler));
+}
+
+}  // end extern "C"
@@ -0,0 +1,294 @@
+use std::collections::{HashMap, VecDeque};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{db::DBInner, DBCommon, DBWALIterator, Error, ThreadMode, WideColumns, WriteBatchOp};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The change made to a key by a write, see [`Change`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeOp {
+    Put {
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Merge {
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        key: Vec<u8>,
+    },
+    SingleDelete {
+        key: Vec<u8>,
+    },
+    /// Deletion of the keys in `[begin_key, end_key)`
+    DeleteRange {
+        begin_key: Vec<u8>,
+        end_key: Vec<u8>,
+    },
+    /// A wide-column entity, with its columns sorted by name
+    PutEntity {
+        key: Vec<u8>,
+        columns: WideColumns,
+    },
+}
+
+/// A write decoded from the WAL by a [`ChangeStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// Sequence number of the write
+    pub sequence_number: u64,
+    /// Id of the column family written to, 0 for the default column family
+    pub column_family_id: u32,
+    /// Name of the column family written to, or `None` when it is not open in
+    /// the database, e.g. because it was dropped since
+    pub column_family_name: Option<String>,
+    pub op: ChangeOp,
+}
+
+/// An event of a [`ChangeStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeEvent {
+    Change(Change),
+    /// The writes with sequence numbers in `[start, end)` can't be read from
+    /// the WAL, because its files were purged or because the writes bypassed
+    /// it. A consumer replicating the database must resynchronize from a
+    /// snapshot.
+    Gap {
+        start: u64,
+        end: u64,
+    },
+}
+
+/// Follows the writes of a database through its WAL, see
+/// [`DBCommon::change_stream`].
+///
+/// Events are returned in sequence number order by [`poll`](Self::poll),
+/// which doesn't block, by [`poll_timeout`](Self::poll_timeout), or by the
+/// `Iterator` implementation, which waits for new writes indefinitely and
+/// checks the WAL every [poll interval](Self::set_poll_interval).
+///
+/// To resume after a restart, persist
+/// [`last_sequence_number`](Self::last_sequence_number) along with the
+/// changes consumed, and pass it to [`DBCommon::change_stream`]. The WAL is
+/// deleted once its writes are flushed unless
+/// [`Options::set_wal_ttl_seconds`] or [`Options::set_wal_size_limit_mb`] keep
+/// it around, and changes that can't be read anymore are reported as a
+/// [`ChangeEvent::Gap`]. Writes made with the WAL disabled and ingested files
+/// are reported as gaps too.
+///
+/// Log data of the write batches is skipped.
+///
+/// A stream can be moved to another thread, e.g. to consume the changes in
+/// the background while it borrows the database.
+///
+/// ```
+/// use rocksdb::{ChangeEvent, ChangeOp, Options, DB};
+///
+/// let tempdir = tempfile::Builder::new()
+///     .prefix("_path_for_rocksdb_storage_change_stream")
+///     .tempdir()
+///     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_change_stream");
+/// let path = tempdir.path();
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     let mut stream = db.change_stream(0);
+///     db.put(b"key", b"value").unwrap();
+///
+///     match stream.poll().unwrap() {
+///         Some(ChangeEvent::Change(change)) => {
+///             assert_eq!(change.column_family_name.as_deref(), Some("default"));
+///             assert_eq!(
+///                 change.op,
+///                 ChangeOp::Put { key: b"key".to_vec(), value: b"value".to_vec() }
+///             );
+///         }
+///         event => panic!("unexpected event {event:?}"),
+///     }
+///     assert!(stream.poll().unwrap().is_none());
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+///
+/// [`DBCommon::change_stream`]: crate::DBCommon::change_stream
+/// [`Options::set_wal_ttl_seconds`]: crate::Options::set_wal_ttl_seconds
+/// [`Options::set_wal_size_limit_mb`]: crate::Options::set_wal_size_limit_mb
+pub struct ChangeStream<'a, T: ThreadMode, D: DBInner> {
+    db: &'a DBCommon<T, D>,
+    wal: Option<DBWALIterator>,
+    pending: VecDeque<ChangeEvent>,
+    // sequence number of the last event returned
+    last_seq: u64,
+    // sequence number following the last write read from the WAL
+    next_seq: u64,
+    cf_names: HashMap<u32, String>,
+    poll_interval: Duration,
+}
+
+impl<'a, T: ThreadMode, D: DBInner> ChangeStream<'a, T, D> {
+    pub(crate) fn new(db: &'a DBCommon<T, D>, last_seq: u64) -> Self {
+        Self {
+            db,
+            wal: None,
+            pending: VecDeque::new(),
+            last_seq,
+            next_seq: last_seq.saturating_add(1),
+            cf_names: db.cf_names_by_id(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Returns the sequence number of the last change returned, or of the end
+    /// of the last gap, from which the stream can be resumed.
+    pub fn last_sequence_number(&self) -> u64 {
+        self.last_seq
+    }
+
+    /// Sets how often the WAL is checked for new writes while waiting for
+    /// them.
+    ///
+    /// Default: 100ms
+    pub fn set_poll_interval(&mut self, interval: Duration) {
+        self.poll_interval = interval;
+    }
+
+    /// Returns the next event, or `None` when all the writes of the WAL were
+    /// returned.
+    pub fn poll(&mut self) -> Result<Option<ChangeEvent>, Error> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                self.last_seq = match &event {
+                    ChangeEvent::Change(change) => change.sequence_number,
+                    ChangeEvent::Gap { end, .. } => end - 1,
+                };
+                return Ok(Some(event));
+            }
+            if !self.read_batch()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    /// Returns the next event, waiting up to `timeout` for new writes. Returns
+    /// `None` on timeout.
+    pub fn poll_timeout(&mut self, timeout: Duration) -> Result<Option<ChangeEvent>, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(event) = self.poll()? {
+                return Ok(Some(event));
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(None);
+            }
+            thread::sleep(self.poll_interval.min(deadline - now));
+        }
+    }
+
+    /// Reads the next batch of the WAL into `pending`. Returns `false` when
+    /// there is none.
+    fn read_batch(&mut self) -> Result<bool, Error> {
+        // latest sequence number when the iterator was just created
+        let mut latest_seq = None;
+        if self.wal.is_none() {
+            let latest = self.db.latest_sequence_number();
+            if latest < self.next_seq {
+                return Ok(false);
+            }
+            let mut wal = self.db.get_updates_since(self.next_seq)?;
+            // also return the batch holding `next_seq`, which starts before it
+            // when a previous stream stopped in the middle of the batch
+            wal.start_seq_number = 0;
+            self.wal = Some(wal);
+            latest_seq = Some(latest);
+        }
+        let next = self.wal.as_mut().and_then(Iterator::next).transpose()?;
+        let Some((seq, batch)) = next else {
+            self.wal = None;
+            let Some(latest) = latest_seq else {
+                // The iterator ends at the tail of the WAL, or on a
+                // discontinuity of the sequence numbers, which a new one
+                // reports as a gap.
+                return self.read_batch();
+            };
+            // the writes up to `latest` were purged from the WAL, or bypassed
+            // it
+            self.pending.push_back(ChangeEvent::Gap {
+                start: self.next_seq,
+                end: latest + 1,
+            });
+            self.next_seq = latest + 1;
+            return Ok(true);
+        };
+
+        if seq > self.next_seq {
+            self.pending.push_back(ChangeEvent::Gap {
+                start: self.next_seq,
+                end: seq,
+            });
+            self.next_seq = seq;
+        }
+        let mut op_seq = seq;
+        for op in batch.ops()? {
+            let (cf_id, op) = match op {
+                WriteBatchOp::Put { cf_id, key, value } => (cf_id, ChangeOp::Put { key, value }),
+                WriteBatchOp::Merge { cf_id, key, value } => {
+                    (cf_id, ChangeOp::Merge { key, value })
+                }
+                WriteBatchOp::Delete { cf_id, key } => (cf_id, ChangeOp::Delete { key }),
+                WriteBatchOp::SingleDelete { cf_id, key } => {
+                    (cf_id, ChangeOp::SingleDelete { key })
+                }
+                WriteBatchOp::DeleteRange {
+                    cf_id,
+                    begin_key,
+                    end_key,
+                } => (cf_id, ChangeOp::DeleteRange { begin_key, end_key }),
+                WriteBatchOp::PutEntity {
+                    cf_id,
+                    key,
+                    columns,
+                } => (cf_id, ChangeOp::PutEntity { key, columns }),
+                // log data takes no sequence number
+                WriteBatchOp::LogData(_) => continue,
+            };
+            if op_seq >= self.next_seq {
+                let change = Change {
+                    sequence_number: op_seq,
+                    column_family_id: cf_id,
+                    column_family_name: self.cf_name(cf_id),
+                    op,
+                };
+                self.pending.push_back(ChangeEvent::Change(change));
+            }
+            op_seq += 1;
+        }
+        self.next_seq = self.next_seq.max(op_seq);
+        Ok(true)
+    }
+
+    fn cf_name(&mut self, cf_id: u32) -> Option<String> {
+        if !self.cf_names.contains_key(&cf_id) {
+            // the column family may have been created after the stream
+            self.cf_names = self.db.cf_names_by_id();
+        }
+        self.cf_names.get(&cf_id).cloned()
+    }
+}
+
+/// Waits for new writes indefinitely, the iterator never ends.
+impl<T: ThreadMode, D: DBInner> Iterator for ChangeStream<'_, T, D> {
+    type Item = Result<ChangeEvent, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.poll() {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => thread::sleep(self.poll_interval),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
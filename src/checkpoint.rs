@@ -17,7 +17,13 @@
 //!
 //! [1]: https://github.com/facebook/rocksdb/wiki/Checkpoints
 
-use crate::{db::DBInner, ffi, ffi_util::to_cpath, DBCommon, Error, ThreadMode};
+use crate::{
+    db::DBInner,
+    ffi,
+    ffi_util::{lossy_string, raw_data, to_cpath},
+    AsColumnFamilyRef, DBCommon, Error, LiveFile, Temperature, ThreadMode,
+};
+use libc::size_t;
 use std::{marker::PhantomData, path::Path};
 
 /// Undocumented parameter for `ffi::rocksdb_checkpoint_create` function. Zero by default.
@@ -64,6 +70,29 @@ impl<'db> Checkpoint<'db> {
         }
         Ok(())
     }
+
+    /// Exports all live SST files of the column family into directory
+    /// specified by `path`, which must not exist yet.
+    ///
+    /// The returned metadata can be passed to
+    /// [`DBCommon::create_column_family_with_import`] to import the files into
+    /// another (or the same) database as a new column family. Files are hard
+    /// linked into the export directory when possible, copied otherwise.
+    pub fn export_column_family<P: AsRef<Path>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        path: P,
+    ) -> Result<ExportImportFilesMetaData, Error> {
+        let cpath = to_cpath(path)?;
+        let inner = unsafe {
+            ffi_try!(ffi::rust_rocksdb_checkpoint_export_column_family(
+                self.inner,
+                cf.inner(),
+                cpath.as_ptr(),
+            ))
+        };
+        Ok(ExportImportFilesMetaData { inner })
+    }
 }
 
 impl Drop for Checkpoint<'_> {
@@ -73,3 +102,64 @@ impl Drop for Checkpoint<'_> {
         }
     }
 }
+
+/// Describes the files of a column family exported by
+/// [`Checkpoint::export_column_family`].
+pub struct ExportImportFilesMetaData {
+    pub(crate) inner: *mut ffi::rust_rocksdb_export_import_files_metadata_t,
+}
+
+unsafe impl Send for ExportImportFilesMetaData {}
+unsafe impl Sync for ExportImportFilesMetaData {}
+
+impl ExportImportFilesMetaData {
+    /// Name of the comparator of the exported column family. The column
+    /// family it is imported into must use the same comparator.
+    pub fn db_comparator_name(&self) -> String {
+        unsafe {
+            let mut len: size_t = 0;
+            let name = ffi::rust_rocksdb_export_import_files_metadata_get_db_comparator_name(
+                self.inner, &mut len,
+            );
+            lossy_string(name, len)
+        }
+    }
+
+    /// The exported SST files. [`LiveFile::name`] is relative to the export
+    /// directory.
+    pub fn files(&self) -> Vec<LiveFile> {
+        unsafe {
+            let n = ffi::rust_rocksdb_export_import_files_metadata_get_file_count(self.inner);
+            (0..n)
+                .map(|i| {
+                    let mut file: ffi::rust_rocksdb_live_file_info_t = std::mem::zeroed();
+                    ffi::rust_rocksdb_export_import_files_metadata_get_file(
+                        self.inner, i, &mut file,
+                    );
+                    LiveFile {
+                        column_family_name: lossy_string(
+                            file.column_family_name,
+                            file.column_family_name_len,
+                        ),
+                        name: lossy_string(file.relative_filename, file.relative_filename_len),
+                        size: file.size as usize,
+                        level: file.level,
+                        start_key: raw_data(file.smallest_key, file.smallest_key_len),
+                        end_key: raw_data(file.largest_key, file.largest_key_len),
+                        num_entries: file.num_entries,
+                        num_deletions: file.num_deletions,
+                        temperature: Temperature::from_raw(file.temperature),
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+impl Drop for ExportImportFilesMetaData {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_export_import_files_metadata_destroy(self.inner);
+        }
+    }
+}
@@ -14,22 +14,30 @@
 //
 
 use crate::{
+    change_stream::ChangeStream,
+    checkpoint::ExportImportFilesMetaData,
     column_family::AsColumnFamilyRef,
     column_family::BoundColumnFamily,
     column_family::UnboundColumnFamily,
     db_options::OptionsMustOutliveDB,
     ffi,
-    ffi_util::{from_cstr, opt_bytes_to_ptr, raw_data, to_cpath, CStrLike},
-    ColumnFamily, ColumnFamilyDescriptor, CompactOptions, DBIteratorWithThreadMode,
-    DBPinnableSlice, DBRawIteratorWithThreadMode, DBWALIterator, Direction, Error, FlushOptions,
-    IngestExternalFileOptions, IteratorMode, Options, ReadOptions, SnapshotWithThreadMode,
-    WaitForCompactOptions, WriteBatch, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
+    ffi_util::{
+        check_linked_version, from_cstr, lossy_string, opt_bytes_to_ptr, raw_data, to_cpath,
+        CStrLike,
+    },
+    ColumnFamily, ColumnFamilyDescriptor, CompactOptions, CompactionOptions,
+    DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode,
+    DBTimestampedIteratorWithThreadMode, DBWALIterator, Direction, Error, FlushOptions,
+    ImportColumnFamilyOptions, IngestExternalFileOptions, IteratorMode, Options, ReadOptions,
+    SnapshotWithThreadMode, Temperature, WaitForCompactOptions, WideColumns, WriteBatchLike,
+    WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
 };
 
 use crate::column_family::ColumnFamilyTtl;
 use crate::ffi_util::CSlice;
+use crate::table_properties::{table_properties_callback, TableProperties};
 use libc::{self, c_char, c_int, c_uchar, c_void, size_t};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::fs;
@@ -43,6 +51,25 @@ use std::sync::Arc;
 use std::sync::RwLock;
 use std::time::Duration;
 
+unsafe extern "C" fn merge_operand_callback(state: *mut c_void, value: *const c_char, len: size_t) {
+    unsafe {
+        let operands = &mut *(state as *mut Vec<Vec<u8>>);
+        operands.push(raw_data(value, len).unwrap_or_default());
+    }
+}
+
+unsafe extern "C" fn compaction_output_file_callback(
+    state: *mut c_void,
+    name: *const c_char,
+    len: size_t,
+) {
+    unsafe {
+        let output_files = &mut *(state as *mut Vec<String>);
+        let name = slice::from_raw_parts(name as *const u8, len);
+        output_files.push(String::from_utf8_lossy(name).into_owned());
+    }
+}
+
 /// A range of keys, `start_key` is included, but not `end_key`.
 ///
 /// You should make sure `end_key` is not less than `start_key`.
@@ -74,6 +101,11 @@ pub trait ThreadMode {
     ) -> Self;
     /// Internal implementation for dropping column family handles
     fn drop_all_cfs_internal(&mut self);
+    /// Internal implementation for mapping column family ids to names, none
+    /// are known by default
+    fn cf_names_by_id_internal(&self) -> HashMap<u32, String> {
+        HashMap::new()
+    }
 }
 
 /// Actual marker type for the marker trait `ThreadMode`, which holds
@@ -111,6 +143,16 @@ impl ThreadMode for SingleThreaded {
         // Cause all ColumnFamily objects to be Drop::drop()-ed.
         self.cfs.clear();
     }
+
+    fn cf_names_by_id_internal(&self) -> HashMap<u32, String> {
+        self.cfs
+            .iter()
+            .map(|(name, cf)| {
+                let id = unsafe { ffi::rocksdb_column_family_handle_get_id(cf.inner) };
+                (id, name.clone())
+            })
+            .collect()
+    }
 }
 
 impl ThreadMode for MultiThreaded {
@@ -130,6 +172,18 @@ impl ThreadMode for MultiThreaded {
         // Cause all UnboundColumnFamily objects to be Drop::drop()-ed.
         self.cfs.write().unwrap().clear();
     }
+
+    fn cf_names_by_id_internal(&self) -> HashMap<u32, String> {
+        self.cfs
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(name, cf)| {
+                let id = unsafe { ffi::rocksdb_column_family_handle_get_id(cf.inner) };
+                (id, name.clone())
+            })
+            .collect()
+    }
 }
 
 /// Get underlying `rocksdb_t`.
@@ -642,6 +696,7 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
             .collect();
 
         let cpath = to_cpath(&path)?;
+        check_linked_version()?;
 
         if let Err(e) = fs::create_dir_all(&path) {
             return Err(Error::new(format!(
@@ -851,22 +906,21 @@ impl<T: ThreadMode> DBWithThreadMode<T> {
         self.delete_range_cf_opt(cf, from, to, &WriteOptions::default())
     }
 
-    pub fn write_opt(&self, batch: WriteBatch, writeopts: &WriteOptions) -> Result<(), Error> {
-        unsafe {
-            ffi_try!(ffi::rocksdb_write(
-                self.inner.inner(),
-                writeopts.inner,
-                batch.inner
-            ));
-        }
-        Ok(())
+    /// Writes the batch, a [`WriteBatch`](crate::WriteBatch) or a
+    /// [`WriteBatchWithIndex`](crate::WriteBatchWithIndex), atomically.
+    pub fn write_opt<B: WriteBatchLike>(
+        &self,
+        batch: B,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        unsafe { batch.write_to_db(self.inner.inner(), writeopts) }
     }
 
-    pub fn write(&self, batch: WriteBatch) -> Result<(), Error> {
+    pub fn write<B: WriteBatchLike>(&self, batch: B) -> Result<(), Error> {
         self.write_opt(batch, &WriteOptions::default())
     }
 
-    pub fn write_without_wal(&self, batch: WriteBatch) -> Result<(), Error> {
+    pub fn write_without_wal<B: WriteBatchLike>(&self, batch: B) -> Result<(), Error> {
         let mut wo = WriteOptions::new();
         wo.disable_wal(true);
         self.write_opt(batch, &wo)
@@ -1065,6 +1119,76 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         self.get_cf_opt(cf, key.as_ref(), &ReadOptions::default())
     }
 
+    /// Return the value associated with a key in the default column family,
+    /// with user-defined timestamps, and the timestamp of the version read.
+    ///
+    /// The timestamp to read at must be set with
+    /// [`ReadOptions::set_timestamp`].
+    pub fn get_with_ts<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        self.get_cf_with_ts_impl(ptr::null_mut(), key.as_ref(), readopts)
+    }
+
+    /// Return the value associated with a key in a column family with
+    /// user-defined timestamps, and the timestamp of the version read.
+    ///
+    /// The timestamp to read at must be set with
+    /// [`ReadOptions::set_timestamp`].
+    pub fn get_cf_with_ts<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        self.get_cf_with_ts_impl(cf.inner(), key.as_ref(), readopts)
+    }
+
+    fn get_cf_with_ts_impl(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        readopts: &ReadOptions,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        let mut val_len: size_t = 0;
+        let mut ts: *mut c_char = ptr::null_mut();
+        let mut ts_len: size_t = 0;
+        unsafe {
+            let val = if cf.is_null() {
+                ffi_try!(ffi::rocksdb_get_with_ts(
+                    self.inner.inner(),
+                    readopts.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    &mut val_len,
+                    &mut ts,
+                    &mut ts_len,
+                ))
+            } else {
+                ffi_try!(ffi::rocksdb_get_cf_with_ts(
+                    self.inner.inner(),
+                    readopts.inner,
+                    cf,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    &mut val_len,
+                    &mut ts,
+                    &mut ts_len,
+                ))
+            };
+            if val.is_null() {
+                return Ok(None);
+            }
+            let value = raw_data(val, val_len).unwrap_or_default();
+            ffi::rocksdb_free(val as *mut c_void);
+            let timestamp = raw_data(ts, ts_len).unwrap_or_default();
+            ffi::rocksdb_free(ts as *mut c_void);
+            Ok(Some((value, timestamp)))
+        }
+    }
+
     /// Return the value associated with a key using RocksDB's PinnableSlice
     /// so as to avoid unnecessary memory copy.
     pub fn get_pinned_opt<K: AsRef<[u8]>>(
@@ -1148,6 +1272,155 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         self.get_pinned_cf_opt(cf, key, &ReadOptions::default())
     }
 
+    /// Returns the wide-column entity stored under `key` in the default
+    /// column family. A plain value is returned as an entity with a single
+    /// column named [`DEFAULT_WIDE_COLUMN_NAME`](crate::DEFAULT_WIDE_COLUMN_NAME).
+    pub fn get_entity_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<WideColumns>, Error> {
+        self.get_entity_raw(ptr::null_mut(), key.as_ref(), readopts)
+    }
+
+    /// Returns the wide-column entity stored under `key` in the default
+    /// column family, using default read options.
+    pub fn get_entity<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<WideColumns>, Error> {
+        self.get_entity_opt(key, &ReadOptions::default())
+    }
+
+    /// Returns the wide-column entity stored under `key` in the given column
+    /// family.
+    pub fn get_entity_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<WideColumns>, Error> {
+        self.get_entity_raw(cf.inner(), key.as_ref(), readopts)
+    }
+
+    /// Returns the wide-column entity stored under `key` in the given column
+    /// family, using default read options.
+    pub fn get_entity_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<Option<WideColumns>, Error> {
+        self.get_entity_cf_opt(cf, key, &ReadOptions::default())
+    }
+
+    fn get_entity_raw(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        readopts: &ReadOptions,
+    ) -> Result<Option<WideColumns>, Error> {
+        if readopts.inner.is_null() {
+            return Err(Error::new(
+                "Unable to create RocksDB read options. This is a fairly trivial call, and its \
+                 failure may be indicative of a mis-compiled or mis-loaded RocksDB library."
+                    .to_owned(),
+            ));
+        }
+
+        unsafe {
+            let raw = ffi_try!(ffi::rust_rocksdb_get_entity_cf(
+                self.inner.inner(),
+                readopts.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+            if raw.is_null() {
+                return Ok(None);
+            }
+            let columns = WideColumns::from_raw(
+                ffi::rust_rocksdb_wide_columns_count(raw),
+                |i, len| ffi::rust_rocksdb_wide_columns_name(raw, i, len),
+                |i, len| ffi::rust_rocksdb_wide_columns_value(raw, i, len),
+            );
+            ffi::rust_rocksdb_wide_columns_destroy(raw);
+            Ok(Some(columns))
+        }
+    }
+
+    /// Returns the un-merged history of `key` in the default column family:
+    /// the base value (if any) followed by every merge operand, oldest first.
+    ///
+    /// See [`get_merge_operands_cf_opt`](Self::get_merge_operands_cf_opt).
+    pub fn get_merge_operands<K: AsRef<[u8]>>(&self, key: K) -> Result<Vec<Vec<u8>>, Error> {
+        self.get_merge_operands_raw(
+            ptr::null_mut(),
+            key.as_ref(),
+            &ReadOptions::default(),
+            usize::MAX,
+        )
+    }
+
+    /// Returns the un-merged history of `key` in the given column family: the
+    /// base value (if any) followed by every merge operand, oldest first.
+    ///
+    /// The merge operator is not invoked, so the operands can be inspected or
+    /// folded lazily by the caller. The base value is indistinguishable from
+    /// an operand; a key whose history ends in a deletion has no base value.
+    /// An empty vector is returned if the key does not exist.
+    pub fn get_merge_operands_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.get_merge_operands_raw(
+            cf.inner(),
+            key.as_ref(),
+            &ReadOptions::default(),
+            usize::MAX,
+        )
+    }
+
+    /// Like [`get_merge_operands_cf`](Self::get_merge_operands_cf), with read
+    /// options and a cap on the number of operands to return.
+    ///
+    /// If the key has more than `expected_max_operands` operands (including
+    /// the base value), an error of kind [`Incomplete`](crate::ErrorKind::Incomplete)
+    /// is returned instead. The cap only bounds the result, memory is only
+    /// allocated for the operands actually found. A cap of 0 is rejected with
+    /// an error of kind [`InvalidArgument`](crate::ErrorKind::InvalidArgument).
+    pub fn get_merge_operands_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+        expected_max_operands: usize,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        self.get_merge_operands_raw(cf.inner(), key.as_ref(), readopts, expected_max_operands)
+    }
+
+    fn get_merge_operands_raw(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        readopts: &ReadOptions,
+        expected_max_operands: usize,
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let mut operands: Vec<Vec<u8>> = Vec::new();
+        let mut num_operands: c_int = 0;
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_get_merge_operands_cf(
+                self.inner.inner(),
+                readopts.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                c_int::try_from(expected_max_operands).unwrap_or(c_int::MAX),
+                ptr::from_mut(&mut operands) as *mut c_void,
+                Some(merge_operand_callback),
+                &mut num_operands,
+            ));
+        }
+        Ok(operands)
+    }
+
     /// Return the values associated with the given keys.
     pub fn multi_get<K, I>(&self, keys: I) -> Vec<Result<Option<Vec<u8>>, Error>>
     where
@@ -1444,6 +1717,29 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         })
     }
 
+    fn create_inner_cf_handle_with_import(
+        &self,
+        name: impl CStrLike,
+        opts: &Options,
+        import_opts: &ImportColumnFamilyOptions,
+        metadata: &ExportImportFilesMetaData,
+    ) -> Result<*mut ffi::rocksdb_column_family_handle_t, Error> {
+        let cf_name = name.bake().map_err(|err| {
+            Error::new(format!(
+                "Failed to convert path to CString when creating cf: {err}"
+            ))
+        })?;
+        Ok(unsafe {
+            ffi_try!(ffi::rust_rocksdb_create_column_family_with_import(
+                self.inner.inner(),
+                opts.inner,
+                cf_name.as_ptr(),
+                import_opts.inner,
+                metadata.inner,
+            ))
+        })
+    }
+
     pub fn iterator<'a: 'b, 'b>(
         &'a self,
         mode: IteratorMode,
@@ -1471,6 +1767,37 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         DBIteratorWithThreadMode::new_cf(self, cf_handle.inner(), readopts, mode)
     }
 
+    /// Opens an iterator over the default column family, with user-defined
+    /// timestamps, yielding the timestamp of every entry. Set the timestamp
+    /// to read at with [`ReadOptions::set_timestamp`], and the start of a
+    /// history scan with [`ReadOptions::set_iter_start_ts`].
+    pub fn timestamped_iterator_opt<'a: 'b, 'b>(
+        &'a self,
+        mode: IteratorMode,
+        readopts: ReadOptions,
+    ) -> DBTimestampedIteratorWithThreadMode<'b, Self> {
+        DBTimestampedIteratorWithThreadMode::new(DBIteratorWithThreadMode::new(
+            self, readopts, mode,
+        ))
+    }
+
+    /// Opens an iterator over a column family with user-defined timestamps,
+    /// yielding the timestamp of every entry. See
+    /// [`timestamped_iterator_opt`](Self::timestamped_iterator_opt).
+    pub fn timestamped_iterator_cf_opt<'a: 'b, 'b>(
+        &'a self,
+        cf_handle: &impl AsColumnFamilyRef,
+        readopts: ReadOptions,
+        mode: IteratorMode,
+    ) -> DBTimestampedIteratorWithThreadMode<'b, Self> {
+        DBTimestampedIteratorWithThreadMode::new(DBIteratorWithThreadMode::new_cf(
+            self,
+            cf_handle.inner(),
+            readopts,
+            mode,
+        ))
+    }
+
     /// Opens an iterator with `set_total_order_seek` enabled.
     /// This must be used to iterate across prefixes when `set_memtable_factory` has been called
     /// with a Hash-based implementation.
@@ -1689,6 +2016,54 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         }
     }
 
+    /// Stores a wide-column entity under `key` in the default column family,
+    /// replacing any existing value or entity.
+    pub fn put_entity_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        columns: &WideColumns,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        self.put_entity_raw(ptr::null_mut(), key.as_ref(), columns, writeopts)
+    }
+
+    /// Stores a wide-column entity under `key` in the given column family,
+    /// replacing any existing value or entity.
+    pub fn put_entity_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        columns: &WideColumns,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        self.put_entity_raw(cf.inner(), key.as_ref(), columns, writeopts)
+    }
+
+    fn put_entity_raw(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        columns: &WideColumns,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        let raw = columns.as_raw_parts();
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_put_entity_cf(
+                self.inner.inner(),
+                writeopts.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                raw.len(),
+                raw.names.as_ptr(),
+                raw.names_sizes.as_ptr(),
+                raw.values.as_ptr(),
+                raw.values_sizes.as_ptr(),
+            ));
+            Ok(())
+        }
+    }
+
     pub fn merge_opt<K, V>(&self, key: K, value: V, writeopts: &WriteOptions) -> Result<(), Error>
     where
         K: AsRef<[u8]>,
@@ -1776,6 +2151,52 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         }
     }
 
+    /// Remove the database entry for "key" with WriteOptions, assuming it was
+    /// written exactly once and has not been overwritten since.
+    ///
+    /// Unlike [`delete_opt`](Self::delete_opt), the tombstone is dropped as
+    /// soon as it meets the value during compaction. The result is undefined
+    /// if the key has been put more than once or was merged into.
+    pub fn single_delete_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete(
+                self.inner.inner(),
+                writeopts.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
+    /// Remove the database entry for "key" from the given column family with
+    /// WriteOptions. See [`single_delete_opt`](Self::single_delete_opt).
+    pub fn single_delete_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi_try!(ffi::rocksdb_singledelete_cf(
+                self.inner.inner(),
+                writeopts.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+            Ok(())
+        }
+    }
+
     /// Remove the database entry (if any) for "key" with WriteOptions.
     /// Takes an additional argument `ts` as the timestamp.
     /// Note: the DB must be opened with user defined timestamp enabled.
@@ -1850,6 +2271,21 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         self.put_cf_opt(cf, key.as_ref(), value.as_ref(), &WriteOptions::default())
     }
 
+    /// Stores a wide-column entity under `key` in the default column family.
+    pub fn put_entity<K: AsRef<[u8]>>(&self, key: K, columns: &WideColumns) -> Result<(), Error> {
+        self.put_entity_opt(key, columns, &WriteOptions::default())
+    }
+
+    /// Stores a wide-column entity under `key` in the given column family.
+    pub fn put_entity_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        columns: &WideColumns,
+    ) -> Result<(), Error> {
+        self.put_entity_cf_opt(cf, key, columns, &WriteOptions::default())
+    }
+
     /// Set the database entry for "key" to "value".
     /// If "key" already exists, it will coexist with previous entry.
     /// `Get` with a timestamp ts specified in ReadOptions will return
@@ -1925,6 +2361,23 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         self.delete_cf_opt(cf, key.as_ref(), &WriteOptions::default())
     }
 
+    /// Remove the database entry for "key", assuming it was written exactly
+    /// once. See [`single_delete_opt`](Self::single_delete_opt).
+    pub fn single_delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
+        self.single_delete_opt(key.as_ref(), &WriteOptions::default())
+    }
+
+    /// Remove the database entry for "key" from the given column family,
+    /// assuming it was written exactly once. See
+    /// [`single_delete_opt`](Self::single_delete_opt).
+    pub fn single_delete_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), Error> {
+        self.single_delete_cf_opt(cf, key.as_ref(), &WriteOptions::default())
+    }
+
     /// Remove the database entry (if any) for "key".
     /// Takes an additional argument `ts` as the timestamp.
     /// Note: the DB must be opened with user defined timestamp enabled.
@@ -2033,6 +2486,46 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         }
     }
 
+    /// Compacts the given SST files of a column family into `output_level`
+    /// and returns the paths of the files that were produced.
+    ///
+    /// Input files are identified by name, e.g. [`LiveFile::name`] as
+    /// returned by [`live_files`](Self::live_files). All of them must belong
+    /// to `cf`, and `output_level` must not be lower than the level of any of
+    /// them.
+    pub fn compact_files_cf<I, P>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        files: I,
+        output_level: i32,
+        opts: &CompactionOptions,
+    ) -> Result<Vec<String>, Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let cpaths = files
+            .into_iter()
+            .map(to_cpath)
+            .collect::<Result<Vec<_>, _>>()?;
+        let cpaths: Vec<_> = cpaths.iter().map(|p| p.as_ptr()).collect();
+
+        let mut output_files: Vec<String> = Vec::new();
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_compact_files_cf(
+                self.inner.inner(),
+                opts.inner,
+                cf.inner(),
+                cpaths.len() as size_t,
+                cpaths.as_ptr(),
+                output_level,
+                ptr::from_mut(&mut output_files) as *mut c_void,
+                Some(compaction_output_file_callback),
+            ));
+        }
+        Ok(output_files)
+    }
+
     /// Wait for all flush and compactions jobs to finish. Jobs to wait include the
     /// unscheduled (queued, but not scheduled yet).
     ///
@@ -2299,6 +2792,28 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         }
     }
 
+    /// Returns a [`ChangeStream`] following the writes made after
+    /// `last_seq_number`, as they are appended to the WAL.
+    ///
+    /// Pass 0 to start from the oldest write still in the WAL, or the
+    /// [`last_sequence_number`](ChangeStream::last_sequence_number) of a
+    /// previous stream to resume it.
+    pub fn change_stream(&self, last_seq_number: u64) -> ChangeStream<'_, T, D> {
+        ChangeStream::new(self, last_seq_number)
+    }
+
+    /// Returns the names of the column families opened in this database, by
+    /// id.
+    pub(crate) fn cf_names_by_id(&self) -> HashMap<u32, String> {
+        let mut names = self.cfs.cf_names_by_id_internal();
+        // the default column family always has id 0, but is not kept in the
+        // map when the database was opened without column families
+        names
+            .entry(0)
+            .or_insert_with(|| DEFAULT_COLUMN_FAMILY_NAME.to_owned());
+        names
+    }
+
     /// Tries to catch up with the primary by reading as much as possible from the
     /// log files.
     pub fn try_catch_up_with_primary(&self) -> Result<(), Error> {
@@ -2389,19 +2904,11 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
     /// Obtains the LSM-tree meta data of the default column family of the DB
     pub fn get_column_family_metadata(&self) -> ColumnFamilyMetaData {
         unsafe {
-            let ptr = ffi::rocksdb_get_column_family_metadata(self.inner.inner());
-
-            let metadata = ColumnFamilyMetaData {
-                size: ffi::rocksdb_column_family_metadata_get_size(ptr),
-                name: from_cstr(ffi::rocksdb_column_family_metadata_get_name(ptr)),
-                file_count: ffi::rocksdb_column_family_metadata_get_file_count(ptr),
-            };
-
-            // destroy
-            ffi::rocksdb_column_family_metadata_destroy(ptr);
-
-            // return
-            metadata
+            let ptr = ffi::rust_rocksdb_get_column_family_metadata_cf(
+                self.inner.inner(),
+                ptr::null_mut(),
+            );
+            ColumnFamilyMetaData::from_c(ptr)
         }
     }
 
@@ -2411,20 +2918,92 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
         cf: &impl AsColumnFamilyRef,
     ) -> ColumnFamilyMetaData {
         unsafe {
-            let ptr = ffi::rocksdb_get_column_family_metadata_cf(self.inner.inner(), cf.inner());
+            let ptr =
+                ffi::rust_rocksdb_get_column_family_metadata_cf(self.inner.inner(), cf.inner());
+            ColumnFamilyMetaData::from_c(ptr)
+        }
+    }
 
-            let metadata = ColumnFamilyMetaData {
-                size: ffi::rocksdb_column_family_metadata_get_size(ptr),
-                name: from_cstr(ffi::rocksdb_column_family_metadata_get_name(ptr)),
-                file_count: ffi::rocksdb_column_family_metadata_get_file_count(ptr),
-            };
+    /// Returns the properties of every SST file of the default column family,
+    /// by file path.
+    pub fn get_properties_of_all_tables(&self) -> Result<HashMap<String, TableProperties>, Error> {
+        self.get_properties_of_all_tables_cfopt(None::<&ColumnFamily>)
+    }
 
-            // destroy
-            ffi::rocksdb_column_family_metadata_destroy(ptr);
+    /// Returns the properties of every SST file of the specified column
+    /// family, by file path.
+    pub fn get_properties_of_all_tables_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+    ) -> Result<HashMap<String, TableProperties>, Error> {
+        self.get_properties_of_all_tables_cfopt(Some(cf))
+    }
 
-            // return
-            metadata
+    fn get_properties_of_all_tables_cfopt(
+        &self,
+        cf: Option<&impl AsColumnFamilyRef>,
+    ) -> Result<HashMap<String, TableProperties>, Error> {
+        let mut tables = HashMap::new();
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_get_properties_of_all_tables_cf(
+                self.inner.inner(),
+                cf.map_or(ptr::null_mut(), |cf| cf.inner()),
+                ptr::from_mut(&mut tables).cast::<c_void>(),
+                Some(table_properties_callback),
+            ));
+        }
+        Ok(tables)
+    }
+
+    /// Returns the properties of the SST files of the default column family
+    /// overlapping any of the given key ranges, by file path.
+    pub fn get_properties_of_tables_in_range(
+        &self,
+        ranges: &[Range],
+    ) -> Result<HashMap<String, TableProperties>, Error> {
+        self.get_properties_of_tables_in_range_cfopt(None::<&ColumnFamily>, ranges)
+    }
+
+    /// Returns the properties of the SST files of the specified column
+    /// family overlapping any of the given key ranges, by file path.
+    pub fn get_properties_of_tables_in_range_cf(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        ranges: &[Range],
+    ) -> Result<HashMap<String, TableProperties>, Error> {
+        self.get_properties_of_tables_in_range_cfopt(Some(cf), ranges)
+    }
+
+    fn get_properties_of_tables_in_range_cfopt(
+        &self,
+        cf: Option<&impl AsColumnFamilyRef>,
+        ranges: &[Range],
+    ) -> Result<HashMap<String, TableProperties>, Error> {
+        let start_keys: Vec<*const c_char> = ranges
+            .iter()
+            .map(|x| x.start_key.as_ptr() as *const c_char)
+            .collect();
+        let start_key_lens: Vec<_> = ranges.iter().map(|x| x.start_key.len()).collect();
+        let end_keys: Vec<*const c_char> = ranges
+            .iter()
+            .map(|x| x.end_key.as_ptr() as *const c_char)
+            .collect();
+        let end_key_lens: Vec<_> = ranges.iter().map(|x| x.end_key.len()).collect();
+        let mut tables = HashMap::new();
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_get_properties_of_tables_in_range_cf(
+                self.inner.inner(),
+                cf.map_or(ptr::null_mut(), |cf| cf.inner()),
+                ranges.len(),
+                start_keys.as_ptr(),
+                start_key_lens.as_ptr(),
+                end_keys.as_ptr(),
+                end_key_lens.as_ptr(),
+                ptr::from_mut(&mut tables).cast::<c_void>(),
+                Some(table_properties_callback),
+            ));
         }
+        Ok(tables)
     }
 
     /// Returns a list of all table files with their level, start key
@@ -2464,6 +3043,9 @@ impl<T: ThreadMode, D: DBInner> DBCommon<T, D> {
                         end_key: largest_key,
                         num_entries: ffi::rocksdb_livefiles_entries(files, i),
                         num_deletions: ffi::rocksdb_livefiles_deletions(files, i),
+                        temperature: Temperature::from_raw(
+                            ffi::rust_rocksdb_livefiles_temperature(files, i),
+                        ),
                     });
                 }
 
@@ -2612,6 +3194,24 @@ impl<I: DBInner> DBCommon<SingleThreaded, I> {
         Ok(())
     }
 
+    /// Creates a column family with the given name and options from the SST
+    /// files described by `metadata`, as produced by
+    /// [`Checkpoint::export_column_family`](crate::checkpoint::Checkpoint::export_column_family).
+    pub fn create_column_family_with_import<N: AsRef<str>>(
+        &mut self,
+        name: N,
+        opts: &Options,
+        import_opts: &ImportColumnFamilyOptions,
+        metadata: &ExportImportFilesMetaData,
+    ) -> Result<(), Error> {
+        let inner =
+            self.create_inner_cf_handle_with_import(name.as_ref(), opts, import_opts, metadata)?;
+        self.cfs
+            .cfs
+            .insert(name.as_ref().to_string(), ColumnFamily { inner });
+        Ok(())
+    }
+
     /// Drops the column family with the given name
     pub fn drop_cf(&mut self, name: &str) -> Result<(), Error> {
         if let Some(cf) = self.cfs.cfs.remove(name) {
@@ -2641,6 +3241,26 @@ impl<I: DBInner> DBCommon<MultiThreaded, I> {
         Ok(())
     }
 
+    /// Creates a column family with the given name and options from the SST
+    /// files described by `metadata`, as produced by
+    /// [`Checkpoint::export_column_family`](crate::checkpoint::Checkpoint::export_column_family).
+    pub fn create_column_family_with_import<N: AsRef<str>>(
+        &self,
+        name: N,
+        opts: &Options,
+        import_opts: &ImportColumnFamilyOptions,
+        metadata: &ExportImportFilesMetaData,
+    ) -> Result<(), Error> {
+        let mut cfs = self.cfs.cfs.write().unwrap();
+        let inner =
+            self.create_inner_cf_handle_with_import(name.as_ref(), opts, import_opts, metadata)?;
+        cfs.insert(
+            name.as_ref().to_string(),
+            Arc::new(UnboundColumnFamily { inner }),
+        );
+        Ok(())
+    }
+
     /// Drops the column family with the given name by internally locking the inner column
     /// family map. This avoids needing `&mut self` reference
     pub fn drop_cf(&self, name: &str) -> Result<(), Error> {
@@ -2677,6 +3297,7 @@ impl<T: ThreadMode, I: DBInner> fmt::Debug for DBCommon<T, I> {
 
 /// The metadata that describes a column family.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct ColumnFamilyMetaData {
     // The size of this column family in bytes, which is equal to the sum of
     // the file size of its "levels".
@@ -2685,10 +3306,184 @@ pub struct ColumnFamilyMetaData {
     pub name: String,
     // The number of files in this column family.
     pub file_count: usize,
+    /// The metadata of all levels in this column family.
+    pub levels: Vec<LevelMetaData>,
+    /// The total size of all blob files.
+    pub blob_file_size: u64,
+    /// The number of blob files in this column family.
+    pub blob_file_count: usize,
+    /// The metadata of the blob files in this column family.
+    pub blob_files: Vec<BlobMetaData>,
+}
+
+impl ColumnFamilyMetaData {
+    /// Copies the metadata out of `ptr` and destroys it.
+    unsafe fn from_c(ptr: *mut ffi::rust_rocksdb_column_family_metadata_t) -> Self {
+        unsafe {
+            let levels = (0..ffi::rust_rocksdb_column_family_metadata_get_level_count(ptr))
+                .map(|level_index| {
+                    let mut level: ffi::rust_rocksdb_level_metadata_info_t = std::mem::zeroed();
+                    ffi::rust_rocksdb_column_family_metadata_get_level(
+                        ptr,
+                        level_index,
+                        &mut level,
+                    );
+                    let files = (0..level.file_count)
+                        .map(|file_index| {
+                            let mut file: ffi::rust_rocksdb_sst_file_metadata_info_t =
+                                std::mem::zeroed();
+                            ffi::rust_rocksdb_column_family_metadata_get_sst_file(
+                                ptr,
+                                level_index,
+                                file_index,
+                                &mut file,
+                            );
+                            SstFileMetaData::from_c(&file)
+                        })
+                        .collect();
+                    LevelMetaData {
+                        level: level.level,
+                        size: level.size,
+                        files,
+                    }
+                })
+                .collect();
+
+            let blob_files = (0..ffi::rust_rocksdb_column_family_metadata_get_blob_file_count(ptr))
+                .map(|blob_index| {
+                    let mut blob: ffi::rust_rocksdb_blob_metadata_info_t = std::mem::zeroed();
+                    ffi::rust_rocksdb_column_family_metadata_get_blob_file(
+                        ptr, blob_index, &mut blob,
+                    );
+                    BlobMetaData {
+                        file_number: blob.file_number,
+                        file_name: lossy_string(blob.file_name, blob.file_name_len),
+                        file_path: lossy_string(blob.file_path, blob.file_path_len),
+                        size: blob.size,
+                        total_blob_count: blob.total_blob_count,
+                        total_blob_bytes: blob.total_blob_bytes,
+                        garbage_blob_count: blob.garbage_blob_count,
+                        garbage_blob_bytes: blob.garbage_blob_bytes,
+                    }
+                })
+                .collect();
+
+            let metadata = ColumnFamilyMetaData {
+                size: ffi::rust_rocksdb_column_family_metadata_get_size(ptr),
+                name: from_cstr(ffi::rust_rocksdb_column_family_metadata_get_name(ptr)),
+                file_count: ffi::rust_rocksdb_column_family_metadata_get_file_count(ptr),
+                levels,
+                blob_file_size: ffi::rust_rocksdb_column_family_metadata_get_blob_file_size(ptr),
+                blob_file_count: ffi::rust_rocksdb_column_family_metadata_get_blob_file_count(ptr),
+                blob_files,
+            };
+
+            ffi::rust_rocksdb_column_family_metadata_destroy(ptr);
+            metadata
+        }
+    }
+}
+
+/// The metadata that describes a level of a column family.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct LevelMetaData {
+    /// The level which this metadata describes.
+    pub level: i32,
+    /// The size of this level in bytes, which is equal to the sum of
+    /// the file size of its "files".
+    pub size: u64,
+    /// The metadata of all sst files in this level.
+    pub files: Vec<SstFileMetaData>,
+}
+
+/// The metadata that describes a SST file of a column family.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SstFileMetaData {
+    /// File name, relative to `directory`, e.g. `000012.sst`
+    pub relative_filename: String,
+    /// Directory containing the file
+    pub directory: String,
+    /// Number of the file, as used in its name
+    pub file_number: u64,
+    /// Size of the file in bytes
+    pub size: u64,
+    /// Smallest user defined key in the file
+    pub smallest_key: Vec<u8>,
+    /// Largest user defined key in the file
+    pub largest_key: Vec<u8>,
+    /// Smallest sequence number in the file
+    pub smallest_seqno: u64,
+    /// Largest sequence number in the file
+    pub largest_seqno: u64,
+    /// Number of entries in the file
+    pub num_entries: u64,
+    /// Number of deletions/tomb key(s) in the file
+    pub num_deletions: u64,
+    /// Whether the file is currently being compacted
+    pub being_compacted: bool,
+    /// Number of the oldest blob file referenced by the file, or 0 if none
+    pub oldest_blob_file_number: u64,
+    /// Oldest time of the data the file was made from, in seconds since the
+    /// epoch, or 0 if unknown
+    pub oldest_ancester_time: u64,
+    /// Time the file was created, in seconds since the epoch, or 0 if unknown
+    pub file_creation_time: u64,
+    /// Temperature the file was written with
+    pub temperature: Temperature,
+}
+
+impl SstFileMetaData {
+    unsafe fn from_c(file: &ffi::rust_rocksdb_sst_file_metadata_info_t) -> Self {
+        unsafe {
+            SstFileMetaData {
+                relative_filename: lossy_string(file.relative_filename, file.relative_filename_len),
+                directory: lossy_string(file.directory, file.directory_len),
+                file_number: file.file_number,
+                size: file.size,
+                smallest_key: raw_data(file.smallest_key, file.smallest_key_len)
+                    .unwrap_or_default(),
+                largest_key: raw_data(file.largest_key, file.largest_key_len).unwrap_or_default(),
+                smallest_seqno: file.smallest_seqno,
+                largest_seqno: file.largest_seqno,
+                num_entries: file.num_entries,
+                num_deletions: file.num_deletions,
+                being_compacted: file.being_compacted != 0,
+                oldest_blob_file_number: file.oldest_blob_file_number,
+                oldest_ancester_time: file.oldest_ancester_time,
+                file_creation_time: file.file_creation_time,
+                temperature: Temperature::from_raw(file.temperature),
+            }
+        }
+    }
+}
+
+/// The metadata that describes a blob file of a column family.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct BlobMetaData {
+    /// Number of the blob file, as used in its name
+    pub file_number: u64,
+    /// Name of the blob file
+    pub file_name: String,
+    /// Directory containing the blob file
+    pub file_path: String,
+    /// Size of the blob file in bytes
+    pub size: u64,
+    /// Number of blobs in the file
+    pub total_blob_count: u64,
+    /// Total size of the blobs in the file, in bytes
+    pub total_blob_bytes: u64,
+    /// Number of blobs in the file that are no longer referenced
+    pub garbage_blob_count: u64,
+    /// Total size of the blobs in the file that are no longer referenced
+    pub garbage_blob_bytes: u64,
 }
 
 /// The metadata that describes a SST file
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct LiveFile {
     /// Name of the column family the file belongs to
     pub column_family_name: String,
@@ -2706,6 +3501,8 @@ pub struct LiveFile {
     pub num_entries: u64,
     /// Number of deletions/tomb key(s) in the file
     pub num_deletions: u64,
+    /// Temperature the file was written with
+    pub temperature: Temperature,
 }
 
 fn convert_options(opts: &[(&str, &str)]) -> Result<Vec<(CString, CString)>, Error> {
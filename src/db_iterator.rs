@@ -14,10 +14,13 @@
 
 use crate::{
     db::{DBAccess, DB},
-    ffi, Error, ReadOptions, WriteBatch,
+    ffi, Error, ReadOptions, WideColumns, WriteBatch,
 };
 use libc::{c_char, c_uchar, size_t};
-use std::{marker::PhantomData, slice};
+use std::{marker::PhantomData, mem::ManuallyDrop, slice};
+
+/// Size of the sequence number and entry type appended to internal keys.
+const INTERNAL_KEY_FOOTER_SIZE: usize = 8;
 
 /// A type alias to keep compatibility. See [`DBRawIteratorWithThreadMode`] for details
 pub type DBRawIterator<'a> = DBRawIteratorWithThreadMode<'a, DB>;
@@ -117,6 +120,22 @@ impl<'a, D: DBAccess> DBRawIteratorWithThreadMode<'a, D> {
         }
     }
 
+    /// Replaces the C iterator with the one `wrap` creates from it, which
+    /// takes ownership of it, keeping the read options alive.
+    pub(crate) fn map_inner<'b>(
+        self,
+        wrap: impl FnOnce(*mut ffi::rocksdb_iterator_t) -> *mut ffi::rocksdb_iterator_t,
+    ) -> DBRawIteratorWithThreadMode<'b, D>
+    where
+        'a: 'b,
+    {
+        let this = ManuallyDrop::new(self);
+        // Safety: `this` is never dropped, so the read options are moved out
+        // exactly once.
+        let readopts = unsafe { std::ptr::read(&this._readopts) };
+        DBRawIteratorWithThreadMode::from_inner(wrap(this.inner.as_ptr()), readopts)
+    }
+
     /// Returns `true` if the iterator is valid. An iterator is invalidated when
     /// it reaches the end of its defined range, or when it encounters an error.
     ///
@@ -351,6 +370,43 @@ impl<'a, D: DBAccess> DBRawIteratorWithThreadMode<'a, D> {
         }
     }
 
+    /// Returns a slice of the timestamp of the current entry, for column
+    /// families with user-defined timestamps. Empty for other column
+    /// families.
+    ///
+    /// When [`ReadOptions::set_iter_start_ts`] is set, the iterator visits
+    /// every version of the keys in the timestamp range, and [`key`] returns
+    /// the internal key: the user key followed by the timestamp and 8 bytes
+    /// of sequence number and entry type.
+    ///
+    /// [`key`]: Self::key
+    pub fn timestamp(&self) -> Option<&[u8]> {
+        if self.valid() {
+            Some(self.timestamp_impl())
+        } else {
+            None
+        }
+    }
+
+    /// Returns the wide columns of the current entry.
+    ///
+    /// Entries written with a plain `put` are reported as a single column
+    /// named [`DEFAULT_WIDE_COLUMN_NAME`](crate::DEFAULT_WIDE_COLUMN_NAME).
+    pub fn columns(&self) -> Option<WideColumns> {
+        if self.valid() {
+            let iter = self.inner.as_ptr();
+            unsafe {
+                Some(WideColumns::from_raw(
+                    ffi::rust_rocksdb_iter_columns_count(iter),
+                    |i, len| ffi::rust_rocksdb_iter_column_name(iter, i, len),
+                    |i, len| ffi::rust_rocksdb_iter_column_value(iter, i, len),
+                ))
+            }
+        } else {
+            None
+        }
+    }
+
     /// Returns a slice of the current key; assumes the iterator is valid.
     fn key_impl(&self) -> &[u8] {
         // Safety Note: This is safe as all methods that may invalidate the buffer returned
@@ -374,6 +430,54 @@ impl<'a, D: DBAccess> DBRawIteratorWithThreadMode<'a, D> {
             slice::from_raw_parts(val_ptr as *const c_uchar, val_len)
         }
     }
+
+    /// Returns a slice of the current timestamp; assumes the iterator is valid.
+    fn timestamp_impl(&self) -> &[u8] {
+        unsafe {
+            let mut ts_len: size_t = 0;
+            let ts_ptr = ffi::rocksdb_iter_timestamp(self.inner.as_ptr(), &mut ts_len);
+            slice::from_raw_parts(ts_ptr as *const c_uchar, ts_len)
+        }
+    }
+
+    /// Returns the current entry as yielded by timestamped iterators;
+    /// assumes the iterator is valid.
+    fn version_impl(&self) -> Result<KVTBytes, Error> {
+        let key = self.key_impl();
+        let timestamp: Box<[u8]> = Box::from(self.timestamp_impl());
+        if self._readopts.iter_start_ts().is_none() {
+            let value = VersionValue::Put(Box::from(self.value_impl()));
+            return Ok((Box::from(key), value, timestamp));
+        }
+
+        // History scans return internal keys: the user key, the timestamp and
+        // a little endian footer packing the sequence number and the entry
+        // type in its low byte.
+        let user_key_len = key
+            .len()
+            .checked_sub(timestamp.len() + INTERNAL_KEY_FOOTER_SIZE)
+            .ok_or_else(|| {
+                Error::new(format!(
+                    "Corruption: internal key of {} bytes is shorter than its timestamp and footer",
+                    key.len()
+                ))
+            })?;
+        let value = match key[key.len() - INTERNAL_KEY_FOOTER_SIZE] {
+            // regular values, blob references, wide-column entities (their
+            // default column) and timed puts
+            0x01 | 0x11 | 0x16 | 0x18 => VersionValue::Put(Box::from(self.value_impl())),
+            0x02 => VersionValue::Merge(Box::from(self.value_impl())),
+            // deletions, with or without timestamp
+            0x00 | 0x14 => VersionValue::Delete,
+            0x07 => VersionValue::SingleDelete,
+            entry_type => {
+                return Err(Error::new(format!(
+                    "Corruption: unexpected entry type {entry_type:#x} in history scan"
+                )))
+            }
+        };
+        Ok((Box::from(&key[..user_key_len]), value, timestamp))
+    }
 }
 
 impl<D: DBAccess> Drop for DBRawIteratorWithThreadMode<'_, D> {
@@ -445,6 +549,24 @@ pub enum Direction {
 
 pub type KVBytes = (Box<[u8]>, Box<[u8]>);
 
+/// A key, value and timestamp, see [`DBTimestampedIteratorWithThreadMode`].
+pub type KVTBytes = (Box<[u8]>, VersionValue, Box<[u8]>);
+
+/// The value of a version yielded by a
+/// [`DBTimestampedIteratorWithThreadMode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionValue {
+    /// A value written by a put, or the value of the key outside of history
+    /// scans
+    Put(Box<[u8]>),
+    /// A merge operand, only yielded by history scans
+    Merge(Box<[u8]>),
+    /// A deletion, only yielded by history scans
+    Delete,
+    /// A single deletion, only yielded by history scans
+    SingleDelete,
+}
+
 #[derive(Copy, Clone)]
 pub enum IteratorMode<'a> {
     Start,
@@ -500,16 +622,16 @@ impl<'a, D: DBAccess> DBIteratorWithThreadMode<'a, D> {
             }
         };
     }
-}
-
-impl<D: DBAccess> Iterator for DBIteratorWithThreadMode<'_, D> {
-    type Item = Result<KVBytes, Error>;
 
-    fn next(&mut self) -> Option<Result<KVBytes, Error>> {
+    /// Reads the current entry with `read` and moves to the next one.
+    fn next_with<T>(
+        &mut self,
+        read: impl FnOnce(&DBRawIteratorWithThreadMode<'a, D>) -> T,
+    ) -> Option<Result<T, Error>> {
         if self.done {
             None
-        } else if let Some((key, value)) = self.raw.item() {
-            let item = (Box::from(key), Box::from(value));
+        } else if self.raw.valid() {
+            let item = read(&self.raw);
             match self.direction {
                 Direction::Forward => self.raw.next(),
                 Direction::Reverse => self.raw.prev(),
@@ -522,6 +644,14 @@ impl<D: DBAccess> Iterator for DBIteratorWithThreadMode<'_, D> {
     }
 }
 
+impl<D: DBAccess> Iterator for DBIteratorWithThreadMode<'_, D> {
+    type Item = Result<KVBytes, Error>;
+
+    fn next(&mut self) -> Option<Result<KVBytes, Error>> {
+        self.next_with(|raw| (Box::from(raw.key_impl()), Box::from(raw.value_impl())))
+    }
+}
+
 impl<D: DBAccess> std::iter::FusedIterator for DBIteratorWithThreadMode<'_, D> {}
 
 impl<'a, D: DBAccess> Into<DBRawIteratorWithThreadMode<'a, D>> for DBIteratorWithThreadMode<'a, D> {
@@ -530,6 +660,45 @@ impl<'a, D: DBAccess> Into<DBRawIteratorWithThreadMode<'a, D>> for DBIteratorWit
     }
 }
 
+/// A type alias to keep compatibility with [`DBIterator`]. See
+/// [`DBTimestampedIteratorWithThreadMode`] for details
+pub type DBTimestampedIterator<'a> = DBTimestampedIteratorWithThreadMode<'a, DB>;
+
+/// A standard Rust [`Iterator`] over a column family with user-defined
+/// timestamps, yielding the key, value and timestamp of every entry, created
+/// by [`DBCommon::timestamped_iterator_cf_opt`](crate::DBCommon::timestamped_iterator_cf_opt).
+///
+/// With [`ReadOptions::set_timestamp`] alone, it yields the latest version of
+/// every key visible at that timestamp. When
+/// [`ReadOptions::set_iter_start_ts`] is set too, it yields every version
+/// written in the timestamp range, newest first for each key, including
+/// deletions and merge operands, see [`VersionValue`].
+pub struct DBTimestampedIteratorWithThreadMode<'a, D: DBAccess> {
+    iter: DBIteratorWithThreadMode<'a, D>,
+}
+
+impl<'a, D: DBAccess> DBTimestampedIteratorWithThreadMode<'a, D> {
+    pub(crate) fn new(iter: DBIteratorWithThreadMode<'a, D>) -> Self {
+        Self { iter }
+    }
+
+    pub fn set_mode(&mut self, mode: IteratorMode) {
+        self.iter.set_mode(mode);
+    }
+}
+
+impl<D: DBAccess> Iterator for DBTimestampedIteratorWithThreadMode<'_, D> {
+    type Item = Result<KVTBytes, Error>;
+
+    fn next(&mut self) -> Option<Result<KVTBytes, Error>> {
+        self.iter
+            .next_with(DBRawIteratorWithThreadMode::version_impl)
+            .map(|version| version.and_then(|version| version))
+    }
+}
+
+impl<D: DBAccess> std::iter::FusedIterator for DBTimestampedIteratorWithThreadMode<'_, D> {}
+
 /// Iterates the batches of writes since a given sequence number.
 ///
 /// `DBWALIterator` is returned by `DB::get_updates_since()` and will return the
@@ -619,3 +788,5 @@ impl Drop for DBWALIterator {
         }
     }
 }
+
+unsafe impl Send for DBWALIterator {}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::path::Path;
 use std::ptr::{null_mut, NonNull};
@@ -30,14 +31,17 @@ use crate::{
     },
     db::DBAccess,
     env::Env,
+    event_listener::{self, EventListener},
     ffi,
     ffi_util::{from_cstr, to_cpath, CStrLike},
+    logger::{self, Logger},
     merge_operator::{
         self, full_merge_callback, partial_merge_callback, MergeFn, MergeOperatorCallback,
     },
     slice_transform::SliceTransform,
     statistics::Ticker,
-    ColumnFamilyDescriptor, Error, SnapshotWithThreadMode,
+    table_properties::{self, TablePropertiesCollectorFactory},
+    ColumnFamilyDescriptor, Error, SnapshotWithThreadMode, SstFileManager,
 };
 
 pub(crate) struct WriteBufferManagerWrapper {
@@ -140,6 +144,166 @@ impl WriteBufferManager {
     }
 }
 
+pub(crate) struct RateLimiterWrapper {
+    pub(crate) inner: NonNull<ffi::rocksdb_ratelimiter_t>,
+}
+
+impl Drop for RateLimiterWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_ratelimiter_destroy(self.inner.as_ptr());
+        }
+    }
+}
+
+/// I/O priorities, used by the rate limiter to order the requests.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(i32)]
+pub enum IoPriority {
+    Low = 0,
+    Mid,
+    High,
+    User,
+    /// All priorities when querying statistics. In read and write options,
+    /// disables rate limiting.
+    Total,
+}
+
+/// Which I/O a [`RateLimiter`] limits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(i32)]
+pub enum RateLimiterMode {
+    ReadsOnly = 0,
+    WritesOnly,
+    AllIo,
+}
+
+/// A rate limiter that can be shared by several databases, set with
+/// [`Options::set_shared_rate_limiter`], and tuned while they are open.
+///
+/// Flushes and compactions are always limited. User reads and writes are
+/// only limited when their priority is set with
+/// [`ReadOptions::set_rate_limiter_priority`] or
+/// [`WriteOptions::set_rate_limiter_priority`].
+#[derive(Clone)]
+pub struct RateLimiter(pub(crate) Arc<RateLimiterWrapper>);
+
+impl RateLimiter {
+    /// Creates a rate limiter of writes.
+    ///
+    /// rate_bytes_per_sec: the total write rate. Must be greater than 0.
+    /// refill_period_us: how often tokens are refilled, in microseconds.
+    /// A shorter period smooths the writes but costs more CPU.
+    /// fairness: the odds, 1 in `fairness`, that a low priority request is
+    /// granted before pending high priority ones. 10 is a good value.
+    pub fn new(rate_bytes_per_sec: i64, refill_period_us: i64, fairness: i32) -> Self {
+        let inner = NonNull::new(unsafe {
+            ffi::rocksdb_ratelimiter_create(rate_bytes_per_sec, refill_period_us, fairness)
+        })
+        .unwrap();
+        RateLimiter(Arc::new(RateLimiterWrapper { inner }))
+    }
+
+    /// Creates a rate limiter of writes whose rate is tuned automatically
+    /// between `rate_bytes_per_sec / 20` and `rate_bytes_per_sec`, depending
+    /// on the demand.
+    pub fn new_auto_tuned(rate_bytes_per_sec: i64, refill_period_us: i64, fairness: i32) -> Self {
+        let inner = NonNull::new(unsafe {
+            ffi::rocksdb_ratelimiter_create_auto_tuned(
+                rate_bytes_per_sec,
+                refill_period_us,
+                fairness,
+            )
+        })
+        .unwrap();
+        RateLimiter(Arc::new(RateLimiterWrapper { inner }))
+    }
+
+    /// Creates a rate limiter of the I/O selected by `mode`.
+    pub fn new_with_mode(
+        rate_bytes_per_sec: i64,
+        refill_period_us: i64,
+        fairness: i32,
+        mode: RateLimiterMode,
+        auto_tuned: bool,
+    ) -> Self {
+        let inner = NonNull::new(unsafe {
+            ffi::rust_rocksdb_ratelimiter_create_with_mode(
+                rate_bytes_per_sec,
+                refill_period_us,
+                fairness,
+                mode as c_int,
+                c_uchar::from(auto_tuned),
+            )
+        })
+        .unwrap();
+        RateLimiter(Arc::new(RateLimiterWrapper { inner }))
+    }
+
+    /// Changes the rate, taking effect for all the databases sharing the
+    /// limiter. Must be greater than 0.
+    pub fn set_bytes_per_second(&self, bytes_per_second: i64) {
+        unsafe {
+            ffi::rust_rocksdb_ratelimiter_set_bytes_per_second(
+                self.0.inner.as_ptr(),
+                bytes_per_second,
+            );
+        }
+    }
+
+    /// Returns the current rate. For auto-tuned limiters, this is the tuned
+    /// rate.
+    pub fn get_bytes_per_second(&self) -> i64 {
+        unsafe { ffi::rust_rocksdb_ratelimiter_get_bytes_per_second(self.0.inner.as_ptr()) }
+    }
+
+    /// Returns the maximum number of bytes that can be granted by a single
+    /// request.
+    pub fn get_single_burst_bytes(&self) -> i64 {
+        unsafe { ffi::rust_rocksdb_ratelimiter_get_single_burst_bytes(self.0.inner.as_ptr()) }
+    }
+
+    /// Returns the total number of bytes that went through the limiter at
+    /// `priority`, or at all priorities with [`IoPriority::Total`].
+    pub fn get_total_bytes_through(&self, priority: IoPriority) -> i64 {
+        unsafe {
+            ffi::rust_rocksdb_ratelimiter_get_total_bytes_through(
+                self.0.inner.as_ptr(),
+                priority as c_int,
+            )
+        }
+    }
+
+    /// Returns the total number of requests at `priority`, or at all
+    /// priorities with [`IoPriority::Total`].
+    pub fn get_total_requests(&self, priority: IoPriority) -> i64 {
+        unsafe {
+            ffi::rust_rocksdb_ratelimiter_get_total_requests(
+                self.0.inner.as_ptr(),
+                priority as c_int,
+            )
+        }
+    }
+
+    /// Blocks until `bytes` are granted, letting the application share the
+    /// I/O budget of the databases.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` exceeds [`get_single_burst_bytes`].
+    ///
+    /// [`get_single_burst_bytes`]: RateLimiter::get_single_burst_bytes
+    pub fn request(&self, bytes: i64, priority: IoPriority) {
+        assert!(
+            bytes <= self.get_single_burst_bytes(),
+            "requested more than the single burst bytes"
+        );
+        unsafe {
+            ffi::rust_rocksdb_ratelimiter_request(self.0.inner.as_ptr(), bytes, priority as c_int);
+        }
+    }
+}
+
 pub(crate) struct CacheWrapper {
     pub(crate) inner: NonNull<ffi::rocksdb_cache_t>,
 }
@@ -198,6 +362,27 @@ impl Cache {
         }))
     }
 
+    /// Creates a HyperClockCache with `capacity` in bytes, backed by a
+    /// compressed secondary cache holding the blocks evicted from it.
+    ///
+    /// See [`Cache::new_hyper_clock_cache`] for `estimated_entry_charge`.
+    pub fn new_hyper_clock_cache_with_secondary_cache(
+        capacity: size_t,
+        estimated_entry_charge: size_t,
+        secondary_cache: &CompressedSecondaryCache,
+    ) -> Cache {
+        Cache(Arc::new(CacheWrapper {
+            inner: NonNull::new(unsafe {
+                ffi::rust_rocksdb_cache_create_hyper_clock_with_secondary(
+                    capacity,
+                    estimated_entry_charge,
+                    secondary_cache.0.inner.as_ptr(),
+                )
+            })
+            .unwrap(),
+        }))
+    }
+
     /// Returns the cache memory usage in bytes.
     pub fn get_usage(&self) -> usize {
         unsafe { ffi::rocksdb_cache_get_usage(self.0.inner.as_ptr()) }
@@ -214,6 +399,112 @@ impl Cache {
             ffi::rocksdb_cache_set_capacity(self.0.inner.as_ptr(), capacity);
         }
     }
+
+    /// Returns the cache capacity in bytes.
+    pub fn get_capacity(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_capacity(self.0.inner.as_ptr()) }
+    }
+
+    /// Returns the number of entries in the cache.
+    pub fn get_occupancy_count(&self) -> usize {
+        unsafe { ffi::rocksdb_cache_get_occupancy_count(self.0.inner.as_ptr()) }
+    }
+
+    /// Returns whether inserting into a full cache fails instead of going
+    /// over the capacity.
+    pub fn has_strict_capacity_limit(&self) -> bool {
+        unsafe { ffi::rust_rocksdb_cache_has_strict_capacity_limit(self.0.inner.as_ptr()) != 0 }
+    }
+
+    /// Returns the total charge and number of the entries of every role,
+    /// showing which consumers use the cache.
+    ///
+    /// This walks the whole cache, so it is too expensive to call often on
+    /// large caches.
+    pub fn entry_stats(&self) -> HashMap<CacheEntryRole, CacheEntryRoleStats> {
+        let mut charges = [0; CacheEntryRole::ALL.len()];
+        let mut counts = [0; CacheEntryRole::ALL.len()];
+        unsafe {
+            ffi::rust_rocksdb_cache_get_entry_stats(
+                self.0.inner.as_ptr(),
+                charges.as_mut_ptr(),
+                counts.as_mut_ptr(),
+                CacheEntryRole::ALL.len(),
+            );
+        }
+        CacheEntryRole::ALL
+            .iter()
+            .zip(charges.iter().zip(counts.iter()))
+            .map(|(&role, (&charge, &count))| (role, CacheEntryRoleStats { charge, count }))
+            .collect()
+    }
+}
+
+/// The kind of data held by a cache entry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CacheEntryRole {
+    /// Block-based table data block
+    DataBlock,
+    /// Block-based table filter block (full or partitioned)
+    FilterBlock,
+    /// Block-based table metadata block for partitioned filters
+    FilterMetaBlock,
+    /// Obsolete
+    DeprecatedFilterBlock,
+    /// Block-based table index block
+    IndexBlock,
+    /// Other kinds of block-based table block
+    OtherBlock,
+    /// Memory reserved for the memtables by a `WriteBufferManager`
+    WriteBuffer,
+    /// Memory reserved for the buffers of the compression dictionary
+    CompressionDictionaryBuildingBuffer,
+    /// Memory reserved while building filters
+    FilterConstruction,
+    /// Memory reserved for the block-based table readers
+    BlockBasedTableReader,
+    /// Memory reserved for the metadata of the table files
+    FileMetadata,
+    /// Blob value
+    BlobValue,
+    /// Memory reserved for a blob cache sharing this cache
+    BlobCache,
+    /// Anything else
+    Misc,
+}
+
+impl CacheEntryRole {
+    // In the order of RocksDB's `CacheEntryRole`.
+    const ALL: [CacheEntryRole; 14] = [
+        CacheEntryRole::DataBlock,
+        CacheEntryRole::FilterBlock,
+        CacheEntryRole::FilterMetaBlock,
+        CacheEntryRole::DeprecatedFilterBlock,
+        CacheEntryRole::IndexBlock,
+        CacheEntryRole::OtherBlock,
+        CacheEntryRole::WriteBuffer,
+        CacheEntryRole::CompressionDictionaryBuildingBuffer,
+        CacheEntryRole::FilterConstruction,
+        CacheEntryRole::BlockBasedTableReader,
+        CacheEntryRole::FileMetadata,
+        CacheEntryRole::BlobValue,
+        CacheEntryRole::BlobCache,
+        CacheEntryRole::Misc,
+    ];
+}
+
+const _: () = assert!(
+    CacheEntryRole::ALL.len() == ffi::RUST_ROCKSDB_NUM_CACHE_ENTRY_ROLES as usize,
+    "CacheEntryRole is missing roles of RocksDB"
+);
+
+/// Usage of a cache by the entries of one [`CacheEntryRole`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CacheEntryRoleStats {
+    /// Total charge of the entries, usually in bytes
+    pub charge: usize,
+    /// Number of entries
+    pub count: usize,
 }
 
 #[derive(Default)]
@@ -223,6 +514,8 @@ pub(crate) struct OptionsMustOutliveDB {
     blob_cache: Option<Cache>,
     block_based: Option<BlockBasedOptionsMustOutliveDB>,
     write_buffer_manager: Option<WriteBufferManager>,
+    sst_file_manager: Option<SstFileManager>,
+    info_logger: Option<Arc<dyn Logger>>,
 }
 
 impl OptionsMustOutliveDB {
@@ -236,6 +529,8 @@ impl OptionsMustOutliveDB {
                 .as_ref()
                 .map(BlockBasedOptionsMustOutliveDB::clone),
             write_buffer_manager: self.write_buffer_manager.clone(),
+            sst_file_manager: self.sst_file_manager.clone(),
+            info_logger: self.info_logger.clone(),
         }
     }
 }
@@ -327,6 +622,150 @@ pub struct LruCacheOptions {
     pub(crate) inner: *mut ffi::rocksdb_lru_cache_options_t,
 }
 
+pub(crate) struct CompressedSecondaryCacheWrapper {
+    pub(crate) inner: NonNull<ffi::rust_rocksdb_compressed_secondary_cache_t>,
+}
+
+impl Drop for CompressedSecondaryCacheWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_compressed_secondary_cache_destroy(self.inner.as_ptr());
+        }
+    }
+}
+
+/// A second cache tier keeping the blocks evicted from a [`Cache`]
+/// compressed in memory, so that more hot blocks fit in RAM before falling
+/// back to reading them from disk.
+///
+/// Attach it to an LRU cache with [`LruCacheOptions::set_secondary_cache`]
+/// or to a HyperClockCache with
+/// [`Cache::new_hyper_clock_cache_with_secondary_cache`]. A block is only
+/// stored once it was evicted from the primary cache twice.
+///
+/// # Examples
+///
+/// ```
+/// use rocksdb::{
+///     Cache, CompressedSecondaryCache, CompressedSecondaryCacheOptions, LruCacheOptions,
+/// };
+///
+/// let mut secondary_opts = CompressedSecondaryCacheOptions::default();
+/// secondary_opts.set_capacity(64 << 20);
+/// let secondary_cache = CompressedSecondaryCache::new(&secondary_opts);
+///
+/// let mut lru_opts = LruCacheOptions::default();
+/// lru_opts.set_capacity(16 << 20);
+/// lru_opts.set_secondary_cache(&secondary_cache);
+/// let cache = Cache::new_lru_cache_opts(&lru_opts);
+/// ```
+#[derive(Clone)]
+pub struct CompressedSecondaryCache(pub(crate) Arc<CompressedSecondaryCacheWrapper>);
+
+impl CompressedSecondaryCache {
+    /// Creates a compressed secondary cache with the given options.
+    pub fn new(opts: &CompressedSecondaryCacheOptions) -> Self {
+        let inner = NonNull::new(unsafe {
+            ffi::rust_rocksdb_compressed_secondary_cache_create(
+                opts.capacity,
+                opts.num_shard_bits,
+                c_uchar::from(opts.strict_capacity_limit),
+                opts.compression_type as c_int,
+                opts.compress_format_version,
+            )
+        })
+        .unwrap();
+        Self(Arc::new(CompressedSecondaryCacheWrapper { inner }))
+    }
+
+    /// Returns the capacity of the cache in bytes.
+    pub fn get_capacity(&self) -> usize {
+        unsafe { ffi::rust_rocksdb_compressed_secondary_cache_get_capacity(self.0.inner.as_ptr()) }
+    }
+
+    /// Sets the capacity of the cache in bytes, evicting blocks if it
+    /// shrinks.
+    pub fn set_capacity(&self, capacity: size_t) {
+        unsafe {
+            ffi::rust_rocksdb_compressed_secondary_cache_set_capacity(
+                self.0.inner.as_ptr(),
+                capacity,
+            );
+        }
+    }
+
+    /// Returns an approximation of the memory held by the entries of the
+    /// cache in bytes, which is mostly the size of the compressed blocks.
+    /// The bookkeeping of the cache is not included, and the blocks it
+    /// decompresses and hands to the primary cache count towards the usage of
+    /// the primary cache instead.
+    pub fn get_usage(&self) -> usize {
+        unsafe { ffi::rust_rocksdb_compressed_secondary_cache_get_usage(self.0.inner.as_ptr()) }
+    }
+}
+
+/// Options to create a [`CompressedSecondaryCache`].
+#[derive(Debug, Clone)]
+pub struct CompressedSecondaryCacheOptions {
+    capacity: usize,
+    num_shard_bits: c_int,
+    strict_capacity_limit: bool,
+    compression_type: DBCompressionType,
+    compress_format_version: u32,
+}
+
+impl CompressedSecondaryCacheOptions {
+    /// Capacity of the cache in bytes.
+    ///
+    /// Default: 0
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    /// Cache is sharded into 2^num_shard_bits shards, by hash of key.
+    /// If < 0, a good default is chosen based on the capacity.
+    ///
+    /// Default: -1
+    pub fn set_num_shard_bits(&mut self, num_shard_bits: c_int) {
+        self.num_shard_bits = num_shard_bits;
+    }
+
+    /// If true, inserting a block into a full cache fails instead of going
+    /// over the capacity.
+    ///
+    /// Default: false
+    pub fn set_strict_capacity_limit(&mut self, strict_capacity_limit: bool) {
+        self.strict_capacity_limit = strict_capacity_limit;
+    }
+
+    /// Compression algorithm of the cached blocks.
+    ///
+    /// Default: `DBCompressionType::Lz4`
+    pub fn set_compression_type(&mut self, compression_type: DBCompressionType) {
+        self.compression_type = compression_type;
+    }
+
+    /// Format version of the compressed blocks, 2 stores the uncompressed
+    /// size along with the block.
+    ///
+    /// Default: 2
+    pub fn set_compress_format_version(&mut self, version: u32) {
+        self.compress_format_version = version;
+    }
+}
+
+impl Default for CompressedSecondaryCacheOptions {
+    fn default() -> Self {
+        Self {
+            capacity: 0,
+            num_shard_bits: -1,
+            strict_capacity_limit: false,
+            compression_type: DBCompressionType::Lz4,
+            compress_format_version: 2,
+        }
+    }
+}
+
 /// Optionally wait for the memtable flush to be performed.
 ///
 /// # Examples
@@ -427,7 +866,11 @@ unsafe impl Send for ReadOptions {}
 unsafe impl Send for IngestExternalFileOptions {}
 unsafe impl Send for CacheWrapper {}
 unsafe impl Send for CompactOptions {}
+unsafe impl Send for CompactionOptions {}
+unsafe impl Send for ImportColumnFamilyOptions {}
 unsafe impl Send for WriteBufferManagerWrapper {}
+unsafe impl Send for RateLimiterWrapper {}
+unsafe impl Send for CompressedSecondaryCacheWrapper {}
 
 // Sync is similarly safe for many types because they do not expose interior mutability, and their
 // use within the rocksdb library is generally behind a const reference
@@ -441,7 +884,11 @@ unsafe impl Sync for ReadOptions {}
 unsafe impl Sync for IngestExternalFileOptions {}
 unsafe impl Sync for CacheWrapper {}
 unsafe impl Sync for CompactOptions {}
+unsafe impl Sync for CompactionOptions {}
+unsafe impl Sync for ImportColumnFamilyOptions {}
 unsafe impl Sync for WriteBufferManagerWrapper {}
+unsafe impl Sync for RateLimiterWrapper {}
+unsafe impl Sync for CompressedSecondaryCacheWrapper {}
 
 impl Drop for Options {
     fn drop(&mut self) {
@@ -1742,6 +2189,46 @@ impl Options {
         }
     }
 
+    /// Adds a listener that is notified about flushes, compactions, table
+    /// file creation and deletion, write stalls and background errors.
+    ///
+    /// The listener is shared with clones of these options and stays alive
+    /// as long as any of them, or any database opened with them, does.
+    pub fn add_event_listener<L: EventListener>(&mut self, listener: L) {
+        let listener = Box::new(listener);
+        let callbacks = event_listener::callbacks::<L>();
+
+        unsafe {
+            ffi::rust_rocksdb_options_add_eventlistener(
+                self.inner,
+                Box::into_raw(listener).cast::<c_void>(),
+                Some(event_listener::destructor_callback::<L>),
+                &callbacks,
+            );
+        }
+    }
+
+    /// Adds a factory creating a collector of custom properties for every
+    /// SST file written, see [`table_properties`](crate::table_properties).
+    ///
+    /// The factory is shared with clones of these options and stays alive
+    /// as long as any of them, or any database opened with them, does.
+    pub fn add_table_properties_collector_factory<F>(&mut self, factory: F)
+    where
+        F: TablePropertiesCollectorFactory,
+    {
+        let factory = Box::new(factory);
+        let callbacks = table_properties::callbacks::<F>();
+        unsafe {
+            ffi::rust_rocksdb_options_add_table_properties_collector_factory(
+                self.inner,
+                Box::into_raw(factory).cast::<c_void>(),
+                Some(table_properties::destructor_callback::<F>),
+                &callbacks,
+            );
+        }
+    }
+
     /// Sets the comparator used to define the order of keys in the table.
     /// Default: a comparator that uses lexicographic byte-wise ordering
     ///
@@ -1957,6 +2444,35 @@ impl Options {
         }
     }
 
+    /// Sends the info log to `logger` instead of the `LOG` file. Only lines
+    /// at `level` or above are passed on; the level set with
+    /// [`set_log_level`](Self::set_log_level) does not apply to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{LogLevel, Options};
+    ///
+    /// let mut opts = Options::default();
+    /// opts.set_info_logger(LogLevel::Warn, |level: LogLevel, message: &str| {
+    ///     eprintln!("rocksdb {level:?}: {message}");
+    /// });
+    /// ```
+    pub fn set_info_logger<L: Logger>(&mut self, level: LogLevel, logger: L) {
+        let logger = Arc::new(logger);
+
+        unsafe {
+            let raw = ffi::rocksdb_logger_create_callback_logger(
+                level as c_int,
+                Some(logger::log_callback::<L>),
+                Arc::as_ptr(&logger) as *mut c_void,
+            );
+            ffi::rocksdb_options_set_info_log(self.inner, raw);
+            ffi::rocksdb_logger_destroy(raw);
+        }
+        self.outlive.info_logger = Some(logger);
+    }
+
     /// Allows OS to incrementally sync files to disk while they are being
     /// written, asynchronously, in the background. This operation can be used
     /// to smooth out write I/Os over time. Users shouldn't rely on it for
@@ -2524,6 +3040,32 @@ impl Options {
         }
     }
 
+    /// Sets the temperature of the files written to the last level, so that
+    /// a [`FileSystem`](crate::FileSystem) can place the coldest data on
+    /// cheaper storage: the files are opened with it as
+    /// [`FileOptions::temperature`](crate::file_system::FileOptions::temperature).
+    /// The temperature of a file is reported by
+    /// [`LiveFile::temperature`](crate::LiveFile::temperature).
+    ///
+    /// Default: `Temperature::Unknown`
+    pub fn set_last_level_temperature(&mut self, temperature: Temperature) {
+        unsafe {
+            ffi::rust_rocksdb_options_set_last_level_temperature(self.inner, temperature as c_int);
+        }
+    }
+
+    /// Keeps the data written in the last `seconds` out of the last level,
+    /// so that only the data older than that gets the last level
+    /// temperature. Only supported with level and universal compaction. 0
+    /// disables it.
+    ///
+    /// Default: 0
+    pub fn set_preclude_last_level_data_seconds(&mut self, seconds: u64) {
+        unsafe {
+            ffi::rust_rocksdb_options_set_preclude_last_level_data_seconds(self.inner, seconds);
+        }
+    }
+
     /// Sets unordered_write to true trades higher write throughput with
     /// relaxing the immutability guarantee of snapshots. This violates the
     /// repeatability one expects from ::Get from a snapshot, as well as
@@ -3343,6 +3885,8 @@ impl Options {
     /// Use to control write rate of flush and compaction. Flush has higher
     /// priority than compaction.
     /// If rate limiter is enabled, bytes_per_sync is set to 1MB by default.
+    /// The limiter belongs to these options, use
+    /// [`Options::set_shared_rate_limiter`] to share one between databases.
     ///
     /// Default: disable
     ///
@@ -3390,6 +3934,27 @@ impl Options {
         }
     }
 
+    /// Sets a rate limiter that can be shared with other databases, see
+    /// [`RateLimiter`]. Unlike [`Options::set_ratelimiter`], the limiter can
+    /// be tuned while the databases are open.
+    ///
+    /// Default: disable
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{Options, RateLimiter};
+    ///
+    /// let rate_limiter = RateLimiter::new(1024 * 1024, 100 * 1000, 10);
+    /// let mut options = Options::default();
+    /// options.set_shared_rate_limiter(&rate_limiter);
+    /// ```
+    pub fn set_shared_rate_limiter(&mut self, rate_limiter: &RateLimiter) {
+        unsafe {
+            ffi::rocksdb_options_set_ratelimiter(self.inner, rate_limiter.0.inner.as_ptr());
+        }
+    }
+
     /// Sets the maximal size of the info log file.
     ///
     /// If the log file is larger than `max_log_file_size`, a new info log file
@@ -3666,6 +4231,31 @@ impl Options {
         self.outlive.write_buffer_manager = Some(write_buffer_manager.clone());
     }
 
+    /// Sets the [`SstFileManager`] tracking the SST files of the database,
+    /// enforcing its space limit and rate limiting the deletion of obsolete
+    /// files. The same manager can be shared by several databases, which
+    /// then share the space limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{Env, Options, SstFileManager};
+    ///
+    /// let sst_file_manager = SstFileManager::new(&Env::new().unwrap()).unwrap();
+    /// sst_file_manager.set_max_allowed_space_usage(64 << 30);
+    /// let mut options = Options::default();
+    /// options.set_sst_file_manager(&sst_file_manager);
+    /// ```
+    pub fn set_sst_file_manager(&mut self, sst_file_manager: &SstFileManager) {
+        unsafe {
+            ffi::rust_rocksdb_options_set_sst_file_manager(
+                self.inner,
+                sst_file_manager.0.inner.as_ptr(),
+            );
+        }
+        self.outlive.sst_file_manager = Some(sst_file_manager.clone());
+    }
+
     /// If true, working thread may avoid doing unnecessary and long-latency
     /// operation (such as deleting obsolete files directly or deleting memtable)
     /// and will instead schedule a background job to do it.
@@ -3864,6 +4454,17 @@ impl WriteOptions {
             );
         }
     }
+
+    /// Rate limits the WAL writes of this request at `priority`, with the
+    /// rate limiter of the database. Only [`IoPriority::User`] is supported,
+    /// and the limiter must not be in [`RateLimiterMode::ReadsOnly`] mode.
+    ///
+    /// Default: [`IoPriority::Total`], i.e. not rate limited
+    pub fn set_rate_limiter_priority(&mut self, priority: IoPriority) {
+        unsafe {
+            ffi::rust_rocksdb_writeoptions_set_rate_limiter_priority(self.inner, priority as c_int);
+        }
+    }
 }
 
 impl Default for WriteOptions {
@@ -3897,6 +4498,17 @@ impl LruCacheOptions {
             ffi::rocksdb_lru_cache_options_set_num_shard_bits(self.inner, val);
         }
     }
+
+    /// Sets a compressed secondary cache holding the blocks evicted from
+    /// the cache.
+    pub fn set_secondary_cache(&mut self, secondary_cache: &CompressedSecondaryCache) {
+        unsafe {
+            ffi::rust_rocksdb_lru_cache_options_set_secondary_cache(
+                self.inner,
+                secondary_cache.0.inner.as_ptr(),
+            );
+        }
+    }
 }
 
 impl Default for LruCacheOptions {
@@ -4203,6 +4815,17 @@ impl ReadOptions {
         }
     }
 
+    /// Rate limits the file reads of this request at `priority`, with the
+    /// rate limiter of the database. The limiter must not be in
+    /// [`RateLimiterMode::WritesOnly`] mode.
+    ///
+    /// Default: [`IoPriority::Total`], i.e. not rate limited
+    pub fn set_rate_limiter_priority(&mut self, priority: IoPriority) {
+        unsafe {
+            ffi::rust_rocksdb_readoptions_set_rate_limiter_priority(self.inner, priority as c_int);
+        }
+    }
+
     /// Timestamp of operation. Read should return the latest data visible to the
     /// specified timestamp. All timestamps of the same database must be of the
     /// same length and format. The user is responsible for providing a customized
@@ -4239,6 +4862,10 @@ impl ReadOptions {
         self.set_iter_start_ts_impl(Some(ts.into()));
     }
 
+    pub(crate) fn iter_start_ts(&self) -> Option<&[u8]> {
+        self.iter_start_ts.as_deref()
+    }
+
     fn set_iter_start_ts_impl(&mut self, ts: Option<Vec<u8>>) {
         let (ptr, len) = if let Some(ref ts) = ts {
             (ts.as_ptr() as *const c_char, ts.len())
@@ -4460,6 +5087,33 @@ pub enum DBRecoveryMode {
     SkipAnyCorruptedRecord = ffi::rocksdb_skip_any_corrupted_records_recovery as isize,
 }
 
+/// The temperature of a file, a hint about how often its data is accessed,
+/// used to place it on a suitable storage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum Temperature {
+    Unknown = 0,
+    Hot = 0x04,
+    Warm = 0x08,
+    Cool = 0x0A,
+    Cold = 0x0C,
+    Ice = 0x10,
+}
+
+impl Temperature {
+    pub(crate) fn from_raw(raw: c_int) -> Self {
+        match raw {
+            0x04 => Self::Hot,
+            0x08 => Self::Warm,
+            0x0A => Self::Cool,
+            0x0C => Self::Cold,
+            0x10 => Self::Ice,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 pub struct FifoCompactOptions {
     pub(crate) inner: *mut ffi::rocksdb_fifo_compaction_options_t,
 }
@@ -4496,6 +5150,59 @@ impl FifoCompactOptions {
             ffi::rocksdb_fifo_compaction_options_set_max_table_files_size(self.inner, nbytes);
         }
     }
+
+    /// Sets the age thresholds at which files change temperature: a file
+    /// older than the age of a threshold, in seconds, is rewritten with its
+    /// temperature. Ages must be increasing.
+    ///
+    /// Default: empty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rocksdb::{FifoCompactOptions, FileTemperatureAge, Temperature};
+    ///
+    /// let mut fifo_opts = FifoCompactOptions::default();
+    /// fifo_opts.set_file_temperature_age_thresholds(&[
+    ///     FileTemperatureAge { temperature: Temperature::Warm, age: 24 * 60 * 60 },
+    ///     FileTemperatureAge { temperature: Temperature::Cold, age: 7 * 24 * 60 * 60 },
+    /// ]);
+    /// ```
+    pub fn set_file_temperature_age_thresholds(&mut self, thresholds: &[FileTemperatureAge]) {
+        let temperatures: Vec<c_int> = thresholds.iter().map(|t| t.temperature as c_int).collect();
+        let ages: Vec<u64> = thresholds.iter().map(|t| t.age).collect();
+        unsafe {
+            ffi::rust_rocksdb_fifo_compaction_options_set_file_temperature_age_thresholds(
+                self.inner,
+                temperatures.as_ptr(),
+                ages.as_ptr(),
+                thresholds.len(),
+            );
+        }
+    }
+
+    /// If true, a file changing temperature is copied as is instead of being
+    /// rewritten by a compaction.
+    ///
+    /// Default: false
+    pub fn set_allow_trivial_copy_when_change_temperature(&mut self, allow: bool) {
+        unsafe {
+            ffi::rust_rocksdb_fifo_compaction_options_set_allow_trivial_copy(
+                self.inner,
+                c_uchar::from(allow),
+            );
+        }
+    }
+}
+
+/// An age at which the files of a FIFO compaction change temperature, see
+/// [`FifoCompactOptions::set_file_temperature_age_thresholds`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FileTemperatureAge {
+    /// Temperature of the files older than `age`
+    pub temperature: Temperature,
+    /// Age of the files, in seconds
+    pub age: u64,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -4714,6 +5421,103 @@ impl CompactOptions {
     }
 }
 
+/// Options for [`DBCommon::compact_files_cf`](crate::DBCommon::compact_files_cf).
+pub struct CompactionOptions {
+    pub(crate) inner: *mut ffi::rust_rocksdb_compactionoptions_t,
+}
+
+impl Default for CompactionOptions {
+    fn default() -> Self {
+        let opts = unsafe { ffi::rust_rocksdb_compactionoptions_create() };
+        assert!(
+            !opts.is_null(),
+            "Could not create RocksDB Compaction Options"
+        );
+
+        Self { inner: opts }
+    }
+}
+
+impl Drop for CompactionOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_compactionoptions_destroy(self.inner);
+        }
+    }
+}
+
+impl CompactionOptions {
+    /// Sets the size limit of the files produced by the compaction.
+    ///
+    /// Default: `u64::MAX`, i.e. a single output file per subcompaction
+    pub fn set_output_file_size_limit(&mut self, limit: u64) {
+        unsafe {
+            ffi::rust_rocksdb_compactionoptions_set_output_file_size_limit(self.inner, limit);
+        }
+    }
+
+    /// Sets the compression algorithm used for the output files.
+    ///
+    /// If not set, the compression configured for the output level of the
+    /// column family is used.
+    pub fn set_compression(&mut self, t: DBCompressionType) {
+        unsafe {
+            ffi::rust_rocksdb_compactionoptions_set_compression(self.inner, t as c_int);
+        }
+    }
+
+    /// Sets the maximum number of threads that will concurrently perform the
+    /// compaction. 0 means the `max_subcompactions` DB option is used.
+    ///
+    /// Default: 0
+    pub fn set_max_subcompactions(&mut self, max: u32) {
+        unsafe {
+            ffi::rust_rocksdb_compactionoptions_set_max_subcompactions(self.inner, max);
+        }
+    }
+}
+
+/// Options for
+/// [`DBCommon::create_column_family_with_import`](crate::DBCommon::create_column_family_with_import).
+pub struct ImportColumnFamilyOptions {
+    pub(crate) inner: *mut ffi::rust_rocksdb_import_column_family_options_t,
+}
+
+impl Default for ImportColumnFamilyOptions {
+    fn default() -> Self {
+        let opts = unsafe { ffi::rust_rocksdb_import_column_family_options_create() };
+        assert!(
+            !opts.is_null(),
+            "Could not create RocksDB Import Column Family Options"
+        );
+
+        Self { inner: opts }
+    }
+}
+
+impl Drop for ImportColumnFamilyOptions {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_import_column_family_options_destroy(self.inner);
+        }
+    }
+}
+
+impl ImportColumnFamilyOptions {
+    /// Can be set to true to move the files instead of copying them.
+    /// The input files will be unlinked after a successful import.
+    ///
+    /// Default: false
+    pub fn set_move_files(&mut self, v: bool) {
+        unsafe {
+            ffi::rust_rocksdb_import_column_family_options_set_move_files(
+                self.inner,
+                c_uchar::from(v),
+            );
+        }
+    }
+}
+
 pub struct WaitForCompactOptions {
     pub(crate) inner: *mut ffi::rocksdb_wait_for_compact_options_t,
 }
@@ -0,0 +1,349 @@
+//! Encryption at rest.
+//!
+//! [`Env::encrypted`] wraps an env so that every file it writes, including
+//! SST files, WAL files and the MANIFEST, is encrypted by an
+//! [`EncryptionProvider`]. The provider writes a plaintext prefix at the
+//! beginning of each new file and derives from it the [`CipherStream`] that
+//! encrypts the rest of the file.
+//!
+//! [`CtrEncryptionProvider`] implements counter (CTR) mode on top of any
+//! [`BlockCipher`], such as AES from a crypto crate:
+//!
+//! ```
+//! use rocksdb::encryption::{BlockCipher, CtrEncryptionProvider};
+//! use rocksdb::{Env, Options, DB};
+//!
+//! /// NOT a secure cipher, use a real one.
+//! struct Xor([u8; 16]);
+//!
+//! impl BlockCipher for Xor {
+//!     fn block_size(&self) -> usize {
+//!         16
+//!     }
+//!
+//!     fn encrypt(&self, block: &mut [u8]) {
+//!         block.iter_mut().zip(self.0).for_each(|(b, k)| *b ^= k);
+//!     }
+//! }
+//!
+//! let tempdir = tempfile::Builder::new()
+//!     .prefix("_path_for_rocksdb_storage_encryption")
+//!     .tempdir()
+//!     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_encryption");
+//! let path = tempdir.path();
+//! let provider = CtrEncryptionProvider::new(Xor([7; 16]), |prefix| {
+//!     getrandom::fill(prefix).map_err(Into::into)
+//! });
+//! let env = Env::encrypted(&Env::new().unwrap(), provider).unwrap();
+//! let mut opts = Options::default();
+//! opts.create_if_missing(true);
+//! opts.set_env(&env);
+//! let db = DB::open(&opts, path).unwrap();
+//! db.put(b"key", b"secret").unwrap();
+//! ```
+
+use std::io;
+use std::path::Path;
+use std::slice;
+use std::sync::Arc;
+
+use libc::{c_char, c_void, size_t};
+
+use crate::{
+    ffi,
+    file_system::{path_from_raw, set_error},
+    Env,
+};
+
+/// Encrypts or decrypts the data of one file.
+///
+/// Data is passed with its offset in the file, so that any part of a file
+/// can be decrypted on its own. Decryption may happen concurrently from
+/// several threads.
+pub trait CipherStream: Send + Sync {
+    /// Encrypts `data`, found at `file_offset` in the file, in place.
+    fn encrypt(&self, file_offset: u64, data: &mut [u8]) -> io::Result<()>;
+
+    /// Decrypts `data`, found at `file_offset` in the file, in place.
+    fn decrypt(&self, file_offset: u64, data: &mut [u8]) -> io::Result<()>;
+}
+
+/// Supplies the cipher stream of every file of an encrypted env.
+pub trait EncryptionProvider: Send + Sync + 'static {
+    /// Length of the prefix stored at the beginning of every file. The
+    /// encrypted data starts right after it.
+    fn prefix_len(&self) -> usize;
+
+    /// Fills the prefix of a new file, e.g. with a random nonce. The prefix
+    /// is written to the file as is.
+    fn create_new_prefix(&self, path: &Path, prefix: &mut [u8]) -> io::Result<()>;
+
+    /// Returns the cipher stream of a file, given its prefix.
+    fn create_cipher_stream(&self, path: &Path, prefix: &[u8])
+        -> io::Result<Box<dyn CipherStream>>;
+}
+
+/// A block cipher, such as AES, used by [`CtrEncryptionProvider`].
+///
+/// Counter mode only ever runs the cipher forward, so no decryption is
+/// needed.
+pub trait BlockCipher: Send + Sync + 'static {
+    /// Size of a block in bytes, at least 8.
+    fn block_size(&self) -> usize;
+
+    /// Encrypts a block of `block_size()` bytes in place.
+    fn encrypt(&self, block: &mut [u8]);
+}
+
+/// Counter (CTR) mode encryption with a [`BlockCipher`].
+///
+/// Every file starts with a 4096 bytes prefix holding a random initial
+/// counter (first 8 bytes, little endian) and a random initialization
+/// vector (second block); the rest of the prefix is encrypted. The key
+/// stream block of the n-th block of the file is the initialization vector,
+/// with its first 8 bytes replaced by the initial counter plus n, run
+/// through the cipher.
+pub struct CtrEncryptionProvider<C> {
+    cipher: Arc<C>,
+    fill_random: Box<dyn Fn(&mut [u8]) -> io::Result<()> + Send + Sync>,
+}
+
+impl<C: BlockCipher> CtrEncryptionProvider<C> {
+    const PREFIX_LEN: usize = 4096;
+
+    /// Creates a provider drawing the prefix of every new file from
+    /// `fill_random`. The counter and initialization vector must never
+    /// repeat for a key, so it must be a cryptographically secure generator,
+    /// such as `getrandom::fill`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block size of `cipher` is smaller than 8 bytes, or
+    /// does not fit twice in the prefix.
+    pub fn new<F>(cipher: C, fill_random: F) -> Self
+    where
+        F: Fn(&mut [u8]) -> io::Result<()> + Send + Sync + 'static,
+    {
+        let block_size = cipher.block_size();
+        assert!(
+            (8..=Self::PREFIX_LEN / 2).contains(&block_size),
+            "unsupported block size {block_size}"
+        );
+        Self {
+            cipher: Arc::new(cipher),
+            fill_random: Box::new(fill_random),
+        }
+    }
+
+    fn stream(&self, prefix: &[u8]) -> io::Result<CtrCipherStream<C>> {
+        let block_size = self.cipher.block_size();
+        if prefix.len() < 2 * block_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "encryption prefix too short",
+            ));
+        }
+        let mut counter = [0; 8];
+        counter.copy_from_slice(&prefix[..8]);
+        Ok(CtrCipherStream {
+            cipher: self.cipher.clone(),
+            initial_counter: u64::from_le_bytes(counter),
+            iv: prefix[block_size..2 * block_size].to_vec(),
+        })
+    }
+}
+
+impl<C: BlockCipher> EncryptionProvider for CtrEncryptionProvider<C> {
+    fn prefix_len(&self) -> usize {
+        Self::PREFIX_LEN
+    }
+
+    fn create_new_prefix(&self, _path: &Path, prefix: &mut [u8]) -> io::Result<()> {
+        (self.fill_random)(prefix)?;
+        let block_size = self.cipher.block_size();
+        let stream = self.stream(prefix)?;
+        stream.apply(0, &mut prefix[2 * block_size..]);
+        Ok(())
+    }
+
+    fn create_cipher_stream(
+        &self,
+        _path: &Path,
+        prefix: &[u8],
+    ) -> io::Result<Box<dyn CipherStream>> {
+        Ok(Box::new(self.stream(prefix)?))
+    }
+}
+
+struct CtrCipherStream<C> {
+    cipher: Arc<C>,
+    initial_counter: u64,
+    iv: Vec<u8>,
+}
+
+impl<C: BlockCipher> CtrCipherStream<C> {
+    /// XORs `data` with the key stream; encryption and decryption are the
+    /// same operation.
+    fn apply(&self, file_offset: u64, data: &mut [u8]) {
+        let block_size = self.iv.len();
+        let mut block_index = file_offset / block_size as u64;
+        // the data may start in the middle of a block
+        let mut skip = (file_offset % block_size as u64) as usize;
+        let mut key_stream = vec![0; block_size];
+        let mut rest = data;
+        while !rest.is_empty() {
+            key_stream.copy_from_slice(&self.iv);
+            key_stream[..8]
+                .copy_from_slice(&self.initial_counter.wrapping_add(block_index).to_le_bytes());
+            self.cipher.encrypt(&mut key_stream);
+            let n = rest.len().min(block_size - skip);
+            let (head, tail) = std::mem::take(&mut rest).split_at_mut(n);
+            head.iter_mut()
+                .zip(&key_stream[skip..])
+                .for_each(|(b, k)| *b ^= k);
+            rest = tail;
+            skip = 0;
+            block_index += 1;
+        }
+    }
+}
+
+impl<C: BlockCipher> CipherStream for CtrCipherStream<C> {
+    fn encrypt(&self, file_offset: u64, data: &mut [u8]) -> io::Result<()> {
+        self.apply(file_offset, data);
+        Ok(())
+    }
+
+    fn decrypt(&self, file_offset: u64, data: &mut [u8]) -> io::Result<()> {
+        self.apply(file_offset, data);
+        Ok(())
+    }
+}
+
+/// State of the providers of the envs created by `Env::encrypted`.
+pub(crate) struct ProviderState<P> {
+    pub(crate) provider: P,
+    // the encrypted env does not own the base env
+    pub(crate) _base: Env,
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<P: EncryptionProvider>(raw_cb: *mut c_void) {
+    drop(unsafe { Box::from_raw(raw_cb as *mut ProviderState<P>) });
+}
+
+unsafe extern "C" fn prefix_length_callback<P: EncryptionProvider>(raw_cb: *mut c_void) -> size_t {
+    let state = unsafe { &*(raw_cb as *const ProviderState<P>) };
+    state.provider.prefix_len()
+}
+
+unsafe extern "C" fn create_new_prefix_callback<P: EncryptionProvider>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    fname_len: size_t,
+    prefix: *mut c_char,
+    prefix_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let state = &*(raw_cb as *const ProviderState<P>);
+        let prefix: &mut [u8] = if prefix_len == 0 {
+            &mut []
+        } else {
+            slice::from_raw_parts_mut(prefix as *mut u8, prefix_len)
+        };
+        if let Err(e) = path_from_raw(fname, fname_len)
+            .and_then(|fname| state.provider.create_new_prefix(&fname, prefix))
+        {
+            set_error(err, &e);
+        }
+    }
+}
+
+unsafe extern "C" fn create_cipher_stream_callback<P: EncryptionProvider>(
+    raw_cb: *mut c_void,
+    fname: *const c_char,
+    fname_len: size_t,
+    prefix: *const c_char,
+    prefix_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> *mut c_void {
+    unsafe {
+        let state = &*(raw_cb as *const ProviderState<P>);
+        let prefix: &[u8] = if prefix_len == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(prefix as *const u8, prefix_len)
+        };
+        match path_from_raw(fname, fname_len)
+            .and_then(|fname| state.provider.create_cipher_stream(&fname, prefix))
+        {
+            Ok(stream) => Box::into_raw(Box::new(stream)).cast::<c_void>(),
+            Err(e) => {
+                set_error(err, &e);
+                std::ptr::null_mut()
+            }
+        }
+    }
+}
+
+unsafe fn stream_data<'a>(
+    stream: *mut c_void,
+    data: *mut c_char,
+    len: size_t,
+) -> (&'a dyn CipherStream, &'a mut [u8]) {
+    unsafe {
+        let stream = &**(stream as *const Box<dyn CipherStream>);
+        let data: &mut [u8] = if len == 0 {
+            &mut []
+        } else {
+            slice::from_raw_parts_mut(data as *mut u8, len)
+        };
+        (stream, data)
+    }
+}
+
+unsafe extern "C" fn cipher_stream_encrypt_callback(
+    stream: *mut c_void,
+    file_offset: u64,
+    data: *mut c_char,
+    len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let (stream, data) = stream_data(stream, data, len);
+        if let Err(e) = stream.encrypt(file_offset, data) {
+            set_error(err, &e);
+        }
+    }
+}
+
+unsafe extern "C" fn cipher_stream_decrypt_callback(
+    stream: *mut c_void,
+    file_offset: u64,
+    data: *mut c_char,
+    len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let (stream, data) = stream_data(stream, data, len);
+        if let Err(e) = stream.decrypt(file_offset, data) {
+            set_error(err, &e);
+        }
+    }
+}
+
+unsafe extern "C" fn cipher_stream_destroy_callback(stream: *mut c_void) {
+    drop(unsafe { Box::from_raw(stream as *mut Box<dyn CipherStream>) });
+}
+
+pub(crate) fn callbacks<P: EncryptionProvider>() -> ffi::rust_rocksdb_encryption_provider_callbacks_t
+{
+    ffi::rust_rocksdb_encryption_provider_callbacks_t {
+        prefix_length: Some(prefix_length_callback::<P>),
+        create_new_prefix: Some(create_new_prefix_callback::<P>),
+        create_cipher_stream: Some(create_cipher_stream_callback::<P>),
+        cipher_stream_encrypt: Some(cipher_stream_encrypt_callback),
+        cipher_stream_decrypt: Some(cipher_stream_decrypt_callback),
+        cipher_stream_destroy: Some(cipher_stream_destroy_callback),
+    }
+}
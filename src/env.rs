@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use libc::{self, c_int};
+use libc::{self, c_int, c_void};
 
-use crate::{ffi, Error};
+use crate::{
+    encryption::{self, EncryptionProvider},
+    ffi, file_system, Error, FileSystem,
+};
 
 /// An Env is an interface used by the rocksdb implementation to access
 /// operating system functionality like the filesystem etc. Callers
@@ -71,6 +74,52 @@ impl Env {
         Self(Arc::new(EnvWrapper { inner: env }))
     }
 
+    /// Returns a new environment that performs all file I/O through `fs`
+    /// and delegates everything else, such as the thread pools, to the
+    /// default env.
+    ///
+    /// See the [`file_system`](crate::file_system) module for an example.
+    pub fn from_file_system<F: FileSystem>(fs: F) -> Result<Self, Error> {
+        let callbacks = file_system::callbacks::<F>();
+        let env = unsafe {
+            ffi::rust_rocksdb_create_env_from_filesystem(
+                Box::into_raw(Box::new(fs)).cast::<c_void>(),
+                Some(file_system::destructor_callback::<F>),
+                &callbacks,
+            )
+        };
+        if env.is_null() {
+            Err(Error::new("Could not create env".to_owned()))
+        } else {
+            Ok(Self(Arc::new(EnvWrapper { inner: env })))
+        }
+    }
+
+    /// Returns a new environment that encrypts all the files of `base` with
+    /// `provider`. `base` is kept alive by the returned env.
+    ///
+    /// See the [`encryption`](crate::encryption) module for an example.
+    pub fn encrypted<P: EncryptionProvider>(base: &Env, provider: P) -> Result<Self, Error> {
+        let callbacks = encryption::callbacks::<P>();
+        let state = Box::new(encryption::ProviderState {
+            provider,
+            _base: base.clone(),
+        });
+        let env = unsafe {
+            ffi::rust_rocksdb_create_encrypted_env(
+                base.0.inner,
+                Box::into_raw(state).cast::<c_void>(),
+                Some(encryption::destructor_callback::<P>),
+                &callbacks,
+            )
+        };
+        if env.is_null() {
+            Err(Error::new("Could not create encrypted env".to_owned()))
+        } else {
+            Ok(Self(Arc::new(EnvWrapper { inner: env })))
+        }
+    }
+
     /// Sets the number of background worker threads of a specific thread pool for this environment.
     /// `LOW` is the default pool.
     ///
@@ -0,0 +1,575 @@
+//! Notifications about flushes, compactions, file creation/deletion, write
+//! stalls and background errors.
+//!
+//! Implement [`EventListener`] and register it with
+//! [`Options::add_event_listener`](crate::Options::add_event_listener).
+
+use crate::{
+    ffi,
+    ffi_util::{from_cstr, lossy_string},
+    Error,
+};
+use libc::{c_char, c_int, c_void, size_t};
+
+/// Receives notifications about what RocksDB is doing in the background.
+///
+/// All methods have empty default implementations. They are invoked from
+/// RocksDB's background threads, possibly concurrently, and block the job
+/// that triggered them, so they should return quickly. Calling back into the
+/// database that emitted the event from a callback is not supported.
+pub trait EventListener: Send + Sync + 'static {
+    /// Called before a flush job starts.
+    fn on_flush_begin(&self, _info: &FlushJobInfo) {}
+
+    /// Called after a flush job finished and its output file was installed.
+    fn on_flush_completed(&self, _info: &FlushJobInfo) {}
+
+    /// Called before a compaction job starts.
+    fn on_compaction_begin(&self, _info: &CompactionJobInfo) {}
+
+    /// Called after a compaction job finished, successfully or not.
+    fn on_compaction_completed(&self, _info: &CompactionJobInfo) {}
+
+    /// Called after an SST file was created by a flush, compaction or
+    /// recovery, or failed to be created.
+    fn on_table_file_created(&self, _info: &TableFileCreationInfo) {}
+
+    /// Called after an SST file was deleted.
+    fn on_table_file_deleted(&self, _info: &TableFileDeletionInfo) {}
+
+    /// Called when the write stall condition of a column family changes.
+    fn on_stall_conditions_changed(&self, _info: &WriteStallInfo) {}
+
+    /// Called when a background error puts the database into read-only
+    /// mode.
+    fn on_background_error(&self, _reason: BackgroundErrorReason, _error: &Error) {}
+
+    /// Called when RocksDB stopped trying to recover from a background
+    /// error, whether or not the recovery succeeded.
+    fn on_error_recovery_end(&self, _info: &ErrorRecoveryInfo) {}
+}
+
+/// The reason a flush was started.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FlushReason {
+    Others,
+    GetLiveFiles,
+    ShutDown,
+    ExternalFileIngestion,
+    ManualCompaction,
+    WriteBufferManager,
+    WriteBufferFull,
+    Test,
+    DeleteFiles,
+    AutoCompaction,
+    ManualFlush,
+    ErrorRecovery,
+    ErrorRecoveryRetryFlush,
+    WalFull,
+    CatchUpAfterErrorRecovery,
+}
+
+impl FlushReason {
+    fn from_raw(reason: c_int) -> Self {
+        match reason {
+            0x01 => Self::GetLiveFiles,
+            0x02 => Self::ShutDown,
+            0x03 => Self::ExternalFileIngestion,
+            0x04 => Self::ManualCompaction,
+            0x05 => Self::WriteBufferManager,
+            0x06 => Self::WriteBufferFull,
+            0x07 => Self::Test,
+            0x08 => Self::DeleteFiles,
+            0x09 => Self::AutoCompaction,
+            0x0a => Self::ManualFlush,
+            0x0b => Self::ErrorRecovery,
+            0x0c => Self::ErrorRecoveryRetryFlush,
+            0x0d => Self::WalFull,
+            0x0e => Self::CatchUpAfterErrorRecovery,
+            _ => Self::Others,
+        }
+    }
+}
+
+/// The reason a compaction was started.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CompactionReason {
+    Unknown,
+    /// [Level] number of L0 files > level0_file_num_compaction_trigger
+    LevelL0FilesNum,
+    /// [Level] total size of level > MaxBytesForLevel()
+    LevelMaxLevelSize,
+    /// [Universal] Compacting for size amplification
+    UniversalSizeAmplification,
+    /// [Universal] Compacting for size ratio
+    UniversalSizeRatio,
+    /// [Universal] number of sorted runs > level0_file_num_compaction_trigger
+    UniversalSortedRunNum,
+    /// [FIFO] total size > max_table_files_size
+    FifoMaxSize,
+    /// [FIFO] reduce number of files.
+    FifoReduceNumFiles,
+    /// [FIFO] files with creation time < (current_time - interval)
+    FifoTtl,
+    /// Manual compaction
+    ManualCompaction,
+    /// DB::SuggestCompactRange() marked files for compaction
+    FilesMarkedForCompaction,
+    /// [Level] Automatic compaction within bottommost level to cleanup duplicate
+    /// versions of same user key, usually due to a released snapshot.
+    BottommostFiles,
+    /// Compaction based on TTL
+    Ttl,
+    /// According to the comments in flush_job.cc, RocksDB treats flush as
+    /// a level 0 compaction in internal stats.
+    Flush,
+    /// [InternalOnly] External sst file ingestion treated as a compaction
+    /// with placeholder input level L0 as file ingestion
+    /// technically does not have an input level like other compactions.
+    ExternalSstIngestion,
+    /// Compaction due to SST file being too old
+    PeriodicCompaction,
+    /// Compaction in order to move files to temperature
+    ChangeTemperature,
+    /// Compaction scheduled to force garbage collection of blob files
+    ForcedBlobGc,
+    /// A special TTL compaction for RoundRobin policy, which basically the same
+    /// as kLevelMaxLevelSize, but the goal is to compact TTLed files.
+    RoundRobinTtl,
+    /// [InternalOnly] DBImpl::ReFitLevel treated as a compaction,
+    /// Used only for internal conflict checking with other compactions
+    RefitLevel,
+}
+
+impl CompactionReason {
+    fn from_raw(reason: c_int) -> Self {
+        match reason {
+            1 => Self::LevelL0FilesNum,
+            2 => Self::LevelMaxLevelSize,
+            3 => Self::UniversalSizeAmplification,
+            4 => Self::UniversalSizeRatio,
+            5 => Self::UniversalSortedRunNum,
+            6 => Self::FifoMaxSize,
+            7 => Self::FifoReduceNumFiles,
+            8 => Self::FifoTtl,
+            9 => Self::ManualCompaction,
+            10 => Self::FilesMarkedForCompaction,
+            11 => Self::BottommostFiles,
+            12 => Self::Ttl,
+            13 => Self::Flush,
+            14 => Self::ExternalSstIngestion,
+            15 => Self::PeriodicCompaction,
+            16 => Self::ChangeTemperature,
+            17 => Self::ForcedBlobGc,
+            18 => Self::RoundRobinTtl,
+            19 => Self::RefitLevel,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The job that created a table file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TableFileCreationReason {
+    Flush,
+    Compaction,
+    Recovery,
+    Misc,
+}
+
+impl TableFileCreationReason {
+    fn from_raw(reason: c_int) -> Self {
+        match reason {
+            0 => Self::Flush,
+            1 => Self::Compaction,
+            2 => Self::Recovery,
+            _ => Self::Misc,
+        }
+    }
+}
+
+/// Whether writes to a column family are currently throttled.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum WriteStallCondition {
+    Normal,
+    /// Writes are slowed down.
+    Delayed,
+    /// Writes are stopped until background work catches up.
+    Stopped,
+    /// A condition this version of the crate does not know about, with its
+    /// value in RocksDB.
+    Unknown(u8),
+}
+
+impl WriteStallCondition {
+    fn from_raw(condition: c_int) -> Self {
+        match condition {
+            0 => Self::Normal,
+            1 => Self::Delayed,
+            2 => Self::Stopped,
+            _ => Self::Unknown(u8::try_from(condition - 3).unwrap_or(u8::MAX)),
+        }
+    }
+}
+
+/// The operation that failed with a background error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BackgroundErrorReason {
+    Flush,
+    Compaction,
+    WriteCallback,
+    MemTable,
+    ManifestWrite,
+    FlushNoWal,
+    ManifestWriteNoWal,
+    AsyncFileOpen,
+    /// A reason this version of the crate does not know about.
+    Unknown,
+}
+
+impl BackgroundErrorReason {
+    fn from_raw(reason: c_int) -> Self {
+        match reason {
+            0 => Self::Flush,
+            1 => Self::Compaction,
+            2 => Self::WriteCallback,
+            3 => Self::MemTable,
+            4 => Self::ManifestWrite,
+            5 => Self::FlushNoWal,
+            6 => Self::ManifestWriteNoWal,
+            7 => Self::AsyncFileOpen,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Describes a flush job.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FlushJobInfo {
+    /// Id of the flushed column family
+    pub cf_id: u32,
+    /// Name of the flushed column family
+    pub cf_name: String,
+    /// Path to the newly created SST file
+    pub file_path: String,
+    /// File number of the newly created SST file
+    pub file_number: u64,
+    /// Id of the thread that completed the flush
+    pub thread_id: u64,
+    /// Job id, unique within the same process
+    pub job_id: i32,
+    /// Whether writes were slowed down because of too many L0 files
+    pub triggered_writes_slowdown: bool,
+    /// Whether writes were stopped because of too many L0 files
+    pub triggered_writes_stop: bool,
+    /// Smallest sequence number in the flushed memtables
+    pub smallest_seqno: u64,
+    /// Largest sequence number in the flushed memtables
+    pub largest_seqno: u64,
+    /// Why the flush was started
+    pub flush_reason: FlushReason,
+}
+
+impl FlushJobInfo {
+    unsafe fn from_c(info: &ffi::rust_rocksdb_flush_job_info_t) -> Self {
+        unsafe {
+            Self {
+                cf_id: info.cf_id,
+                cf_name: lossy_string(info.cf_name, info.cf_name_len),
+                file_path: lossy_string(info.file_path, info.file_path_len),
+                file_number: info.file_number,
+                thread_id: info.thread_id,
+                job_id: info.job_id,
+                triggered_writes_slowdown: info.triggered_writes_slowdown != 0,
+                triggered_writes_stop: info.triggered_writes_stop != 0,
+                smallest_seqno: info.smallest_seqno,
+                largest_seqno: info.largest_seqno,
+                flush_reason: FlushReason::from_raw(info.flush_reason),
+            }
+        }
+    }
+}
+
+/// Describes a compaction job.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct CompactionJobInfo {
+    /// Id of the compacted column family
+    pub cf_id: u32,
+    /// Name of the compacted column family
+    pub cf_name: String,
+    /// Outcome of the compaction, always `Ok` when it is about to begin
+    pub status: Result<(), Error>,
+    /// Id of the thread that ran the compaction
+    pub thread_id: u64,
+    /// Job id, unique within the same process
+    pub job_id: i32,
+    /// Smallest input level of the compaction
+    pub base_input_level: i32,
+    /// Output level of the compaction
+    pub output_level: i32,
+    /// Paths of the input files
+    pub input_files: Vec<String>,
+    /// Paths of the output files
+    pub output_files: Vec<String>,
+    /// Why the compaction was started
+    pub compaction_reason: CompactionReason,
+    /// Time spent compacting, in microseconds
+    pub elapsed_micros: u64,
+    /// Number of records read from the input files
+    pub num_input_records: u64,
+    /// Number of records written to the output files
+    pub num_output_records: u64,
+    /// Total size of the input files
+    pub total_input_bytes: u64,
+    /// Total size of the output files
+    pub total_output_bytes: u64,
+}
+
+impl CompactionJobInfo {
+    unsafe fn from_c(info: &ffi::rust_rocksdb_compaction_job_info_t) -> Self {
+        unsafe {
+            Self {
+                cf_id: info.cf_id,
+                cf_name: lossy_string(info.cf_name, info.cf_name_len),
+                status: status_from_c(info.status),
+                thread_id: info.thread_id,
+                job_id: info.job_id,
+                base_input_level: info.base_input_level,
+                output_level: info.output_level,
+                input_files: strings_from_c(
+                    info.num_input_files,
+                    info.input_files,
+                    info.input_files_lens,
+                ),
+                output_files: strings_from_c(
+                    info.num_output_files,
+                    info.output_files,
+                    info.output_files_lens,
+                ),
+                compaction_reason: CompactionReason::from_raw(info.compaction_reason),
+                elapsed_micros: info.elapsed_micros,
+                num_input_records: info.num_input_records,
+                num_output_records: info.num_output_records,
+                total_input_bytes: info.total_input_bytes,
+                total_output_bytes: info.total_output_bytes,
+            }
+        }
+    }
+}
+
+/// Describes the creation of a table file.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TableFileCreationInfo {
+    /// Name of the database the file belongs to
+    pub db_name: String,
+    /// Name of the column family the file belongs to
+    pub cf_name: String,
+    /// Path to the created file
+    pub file_path: String,
+    /// Id of the job that created the file
+    pub job_id: i32,
+    /// The kind of job that created the file
+    pub reason: TableFileCreationReason,
+    /// Size of the file in bytes
+    pub file_size: u64,
+    /// Whether the file was created successfully
+    pub status: Result<(), Error>,
+}
+
+/// Describes the deletion of a table file.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TableFileDeletionInfo {
+    /// Name of the database the file belonged to
+    pub db_name: String,
+    /// Path to the deleted file
+    pub file_path: String,
+    /// Id of the job that deleted the file
+    pub job_id: i32,
+    /// Whether the file was deleted successfully
+    pub status: Result<(), Error>,
+}
+
+/// Describes a change of the write stall condition of a column family.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct WriteStallInfo {
+    /// Name of the column family
+    pub cf_name: String,
+    /// The new condition
+    pub cur: WriteStallCondition,
+    /// The previous condition
+    pub prev: WriteStallCondition,
+}
+
+/// Describes the end of an attempt to recover from a background error.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ErrorRecoveryInfo {
+    /// The background error recovery was attempted for
+    pub old_error: Option<Error>,
+    /// `Ok` if the database recovered, otherwise the error it is left with
+    pub result: Result<(), Error>,
+}
+
+unsafe fn status_from_c(status: *const c_char) -> Result<(), Error> {
+    if status.is_null() {
+        Ok(())
+    } else {
+        Err(Error::new(unsafe { from_cstr(status) }))
+    }
+}
+
+unsafe fn strings_from_c(
+    count: size_t,
+    ptrs: *const *const c_char,
+    lens: *const size_t,
+) -> Vec<String> {
+    (0..count)
+        .map(|i| unsafe { lossy_string(*ptrs.add(i), *lens.add(i)) })
+        .collect()
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<L: EventListener>(raw_cb: *mut c_void) {
+    drop(unsafe { Box::from_raw(raw_cb as *mut L) });
+}
+
+unsafe extern "C" fn on_flush_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_flush_job_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        cb.on_flush_begin(&FlushJobInfo::from_c(&*info));
+    }
+}
+
+unsafe extern "C" fn on_flush_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_flush_job_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        cb.on_flush_completed(&FlushJobInfo::from_c(&*info));
+    }
+}
+
+unsafe extern "C" fn on_compaction_begin_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_compaction_job_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        cb.on_compaction_begin(&CompactionJobInfo::from_c(&*info));
+    }
+}
+
+unsafe extern "C" fn on_compaction_completed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_compaction_job_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        cb.on_compaction_completed(&CompactionJobInfo::from_c(&*info));
+    }
+}
+
+unsafe extern "C" fn on_table_file_created_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_table_file_creation_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        let info = &*info;
+        cb.on_table_file_created(&TableFileCreationInfo {
+            db_name: lossy_string(info.db_name, info.db_name_len),
+            cf_name: lossy_string(info.cf_name, info.cf_name_len),
+            file_path: lossy_string(info.file_path, info.file_path_len),
+            job_id: info.job_id,
+            reason: TableFileCreationReason::from_raw(info.reason),
+            file_size: info.file_size,
+            status: status_from_c(info.status),
+        });
+    }
+}
+
+unsafe extern "C" fn on_table_file_deleted_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_table_file_deletion_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        let info = &*info;
+        cb.on_table_file_deleted(&TableFileDeletionInfo {
+            db_name: lossy_string(info.db_name, info.db_name_len),
+            file_path: lossy_string(info.file_path, info.file_path_len),
+            job_id: info.job_id,
+            status: status_from_c(info.status),
+        });
+    }
+}
+
+unsafe extern "C" fn on_stall_conditions_changed_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_write_stall_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        let info = &*info;
+        cb.on_stall_conditions_changed(&WriteStallInfo {
+            cf_name: lossy_string(info.cf_name, info.cf_name_len),
+            cur: WriteStallCondition::from_raw(info.cur),
+            prev: WriteStallCondition::from_raw(info.prev),
+        });
+    }
+}
+
+unsafe extern "C" fn on_background_error_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    reason: c_int,
+    error: *const c_char,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        cb.on_background_error(
+            BackgroundErrorReason::from_raw(reason),
+            &Error::new(from_cstr(error)),
+        );
+    }
+}
+
+unsafe extern "C" fn on_error_recovery_end_callback<L: EventListener>(
+    raw_cb: *mut c_void,
+    info: *const ffi::rust_rocksdb_error_recovery_info_t,
+) {
+    unsafe {
+        let cb = &*(raw_cb as *const L);
+        let info = &*info;
+        cb.on_error_recovery_end(&ErrorRecoveryInfo {
+            old_error: status_from_c(info.old_bg_error).err(),
+            result: status_from_c(info.new_bg_error),
+        });
+    }
+}
+
+pub(crate) fn callbacks<L: EventListener>() -> ffi::rust_rocksdb_eventlistener_callbacks_t {
+    ffi::rust_rocksdb_eventlistener_callbacks_t {
+        on_flush_begin: Some(on_flush_begin_callback::<L>),
+        on_flush_completed: Some(on_flush_completed_callback::<L>),
+        on_compaction_begin: Some(on_compaction_begin_callback::<L>),
+        on_compaction_completed: Some(on_compaction_completed_callback::<L>),
+        on_table_file_created: Some(on_table_file_created_callback::<L>),
+        on_table_file_deleted: Some(on_table_file_deleted_callback::<L>),
+        on_stall_conditions_changed: Some(on_stall_conditions_changed_callback::<L>),
+        on_background_error: Some(on_background_error_callback::<L>),
+        on_error_recovery_end: Some(on_error_recovery_end_callback::<L>),
+    }
+}
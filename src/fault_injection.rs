@@ -0,0 +1,575 @@
+//! An [`Env`] that injects I/O faults, for testing crash consistency and
+//! error handling.
+//!
+//! [`FaultInjectionEnv`] wraps an existing `Env` and tracks how much of every
+//! file written through it has been synced. A crash is simulated by
+//! deactivating the file system, so that nothing more reaches the files,
+//! closing the database and dropping the data that was never synced:
+//!
+//! ```
+//! use rocksdb::{Env, FaultInjectionEnv, Options, WriteOptions, DB};
+//!
+//! let tempdir = tempfile::Builder::new()
+//!     .prefix("_path_for_rocksdb_storage_fault_injection")
+//!     .tempdir()
+//!     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_fault_injection");
+//! let path = tempdir.path();
+//! let fault_env = FaultInjectionEnv::new(&Env::new().unwrap()).unwrap();
+//! let mut opts = Options::default();
+//! opts.create_if_missing(true);
+//! opts.set_env(fault_env.env());
+//! {
+//!     let db = DB::open(&opts, path).unwrap();
+//!     let mut sync = WriteOptions::default();
+//!     sync.set_sync(true);
+//!     db.put_opt(b"synced", b"1", &sync).unwrap();
+//!     db.put(b"unsynced", b"2").unwrap();
+//!
+//!     // the power goes out
+//!     fault_env.set_filesystem_active(false);
+//! }
+//! fault_env.drop_unsynced_data().unwrap();
+//! fault_env.set_filesystem_active(true);
+//!
+//! let db = DB::open(&opts, path).unwrap();
+//! assert!(db.get(b"synced").unwrap().is_some());
+//! assert!(db.get(b"unsynced").unwrap().is_none());
+//! ```
+//!
+//! Reads, writes and syncs can also be made to fail, either at random with
+//! [`FaultInjectionEnv::set_failure_probability`] or on the files whose name
+//! contains a pattern with [`FaultInjectionEnv::fail_files`]. Everything that
+//! was injected is reported by [`FaultInjectionEnv::injected_faults`].
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    file_system::{
+        EnvFileSystem, FileLock, FileOptions, RandomAccessFile, SequentialFile, WritableFile,
+    },
+    Env, Error, FileSystem,
+};
+
+/// The kinds of I/O operations faults can be injected into.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IoOperation {
+    /// Reads from sequential and random access files.
+    Read,
+    /// Appends to writable files.
+    Write,
+    /// Syncs of writable files.
+    Sync,
+}
+
+/// A fault injected by a [`FaultInjectionEnv`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InjectedFault {
+    /// An operation on `path` failed.
+    Error {
+        path: PathBuf,
+        operation: IoOperation,
+    },
+    /// `bytes` bytes written to `path` but never synced were dropped by
+    /// [`FaultInjectionEnv::drop_unsynced_data`].
+    DroppedUnsyncedData { path: PathBuf, bytes: u64 },
+}
+
+/// An [`Env`] wrapping another one and injecting faults into its file
+/// system.
+///
+/// Clones share the same state. See the [module
+/// documentation](crate::fault_injection) for an example.
+#[derive(Clone)]
+pub struct FaultInjectionEnv {
+    env: Env,
+    shared: Arc<Shared>,
+}
+
+impl FaultInjectionEnv {
+    /// Wraps the file system of `base`. The returned env uses the default
+    /// env's thread pools.
+    pub fn new(base: &Env) -> Result<Self, Error> {
+        let shared = Arc::new(Shared {
+            base: EnvFileSystem::new(base),
+            _base_env: base.clone(),
+            state: Mutex::new(State::default()),
+        });
+        let env = Env::from_file_system(FaultInjectionFileSystem(shared.clone()))?;
+        Ok(Self { env, shared })
+    }
+
+    /// The env to pass to [`Options::set_env`](crate::Options::set_env).
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    /// While the file system is inactive, all writes and syncs fail, as if
+    /// the machine had lost power. Active by default.
+    pub fn set_filesystem_active(&self, active: bool) {
+        self.shared.state().active = active;
+    }
+
+    pub fn is_filesystem_active(&self) -> bool {
+        self.shared.state().active
+    }
+
+    /// Makes each `operation` fail with the given probability, between 0.0
+    /// (never, the default) and 1.0 (always).
+    pub fn set_failure_probability(&self, operation: IoOperation, probability: f64) {
+        self.shared
+            .state()
+            .probabilities
+            .insert(operation, probability.clamp(0.0, 1.0));
+    }
+
+    /// Seeds the generator behind [`set_failure_probability`], making the
+    /// injected faults reproducible.
+    ///
+    /// [`set_failure_probability`]: FaultInjectionEnv::set_failure_probability
+    pub fn set_seed(&self, seed: u64) {
+        self.shared.state().rng = Rng::new(seed);
+    }
+
+    /// Makes every `operation` on the files whose name contains `pattern`
+    /// fail, e.g. `".sst"` or `"MANIFEST"`.
+    pub fn fail_files(&self, operation: IoOperation, pattern: impl Into<String>) {
+        self.shared
+            .state()
+            .file_faults
+            .push((operation, pattern.into()));
+    }
+
+    /// Removes the failures configured with [`set_failure_probability`] and
+    /// [`fail_files`].
+    ///
+    /// [`set_failure_probability`]: FaultInjectionEnv::set_failure_probability
+    /// [`fail_files`]: FaultInjectionEnv::fail_files
+    pub fn clear_failures(&self) {
+        let mut state = self.shared.state();
+        state.probabilities.clear();
+        state.file_faults.clear();
+    }
+
+    /// Truncates every file written through this env to the size it had
+    /// when it was last synced, or to zero if it never was.
+    ///
+    /// Must only be called while no database is using the env.
+    pub fn drop_unsynced_data(&self) -> io::Result<()> {
+        let mut state = self.shared.state();
+        let mut dropped = Vec::new();
+        for (path, file) in state.files.iter_mut() {
+            if file.size > file.synced {
+                truncate(&self.shared.base, path, file.synced)?;
+                dropped.push(InjectedFault::DroppedUnsyncedData {
+                    path: path.clone(),
+                    bytes: file.size - file.synced,
+                });
+                file.size = file.synced;
+            }
+        }
+        state.injected.extend(dropped);
+        Ok(())
+    }
+
+    /// Returns the faults injected so far, oldest first.
+    pub fn injected_faults(&self) -> Vec<InjectedFault> {
+        self.shared.state().injected.clone()
+    }
+
+    /// Clears the list returned by [`injected_faults`].
+    ///
+    /// [`injected_faults`]: FaultInjectionEnv::injected_faults
+    pub fn clear_injected_faults(&self) {
+        self.shared.state().injected.clear();
+    }
+}
+
+/// Rewrites `path` with its first `len` bytes.
+fn truncate(fs: &EnvFileSystem, path: &Path, len: u64) -> io::Result<()> {
+    let mut data = vec![0; len as usize];
+    if len > 0 {
+        let mut file = fs.new_sequential_file(path, &FileOptions::default())?;
+        let mut read = 0;
+        while read < data.len() {
+            let n = file.read(&mut data[read..])?;
+            if n == 0 {
+                break;
+            }
+            read += n;
+        }
+        data.truncate(read);
+    }
+    let mut file = fs.new_writable_file(path, &FileOptions::default())?;
+    file.append(&data)?;
+    file.sync()?;
+    file.close()
+}
+
+/// Sizes of a file written through the env.
+#[derive(Default)]
+struct TrackedFile {
+    size: u64,
+    synced: u64,
+}
+
+struct State {
+    active: bool,
+    probabilities: HashMap<IoOperation, f64>,
+    file_faults: Vec<(IoOperation, String)>,
+    rng: Rng,
+    files: HashMap<PathBuf, TrackedFile>,
+    injected: Vec<InjectedFault>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            active: true,
+            probabilities: HashMap::new(),
+            file_faults: Vec::new(),
+            rng: Rng::new(0),
+            files: HashMap::new(),
+            injected: Vec::new(),
+        }
+    }
+}
+
+struct Shared {
+    base: EnvFileSystem,
+    // keeps the env owning `base` alive
+    _base_env: Env,
+    state: Mutex<State>,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        // a panic in a test thread must not hide the faults from the others
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Fails if a fault should be injected into `operation` on `path`.
+    fn check(&self, path: &Path, operation: IoOperation) -> io::Result<()> {
+        let mut state = self.state();
+        let inject = if !state.active && operation != IoOperation::Read {
+            true
+        } else {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let on_file = state
+                .file_faults
+                .iter()
+                .any(|(op, pattern)| *op == operation && name.contains(pattern.as_str()));
+            let probability = state.probabilities.get(&operation).copied().unwrap_or(0.0);
+            on_file || (probability > 0.0 && state.rng.next_f64() < probability)
+        };
+        if !inject {
+            return Ok(());
+        }
+        state.injected.push(InjectedFault::Error {
+            path: path.to_owned(),
+            operation,
+        });
+        Err(io::Error::other(format!(
+            "injected {operation:?} error on {}",
+            path.display()
+        )))
+    }
+
+    /// Fails the operations changing the file system while it is inactive.
+    fn check_active(&self, path: &Path) -> io::Result<()> {
+        if self.state().active {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "file system inactive, can't modify {}",
+                path.display()
+            )))
+        }
+    }
+}
+
+/// xorshift64*, good enough for picking faults.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // the state must not be zero
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let n = self.0.wrapping_mul(0x2545_F491_4F6C_DD1D);
+        (n >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+struct FaultSequentialFile {
+    inner: Box<dyn SequentialFile>,
+    path: PathBuf,
+    shared: Arc<Shared>,
+}
+
+impl SequentialFile for FaultSequentialFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.shared.check(&self.path, IoOperation::Read)?;
+        self.inner.read(buf)
+    }
+
+    fn positioned_read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.shared.check(&self.path, IoOperation::Read)?;
+        self.inner.positioned_read(offset, buf)
+    }
+
+    fn skip(&mut self, n: u64) -> io::Result<()> {
+        self.inner.skip(n)
+    }
+
+    fn use_direct_io(&self) -> bool {
+        self.inner.use_direct_io()
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        self.inner.required_buffer_alignment()
+    }
+}
+
+struct FaultRandomAccessFile {
+    inner: Box<dyn RandomAccessFile>,
+    path: PathBuf,
+    shared: Arc<Shared>,
+}
+
+impl RandomAccessFile for FaultRandomAccessFile {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        self.shared.check(&self.path, IoOperation::Read)?;
+        self.inner.read_at(offset, buf)
+    }
+
+    fn use_direct_io(&self) -> bool {
+        self.inner.use_direct_io()
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        self.inner.required_buffer_alignment()
+    }
+}
+
+struct FaultWritableFile {
+    inner: Box<dyn WritableFile>,
+    path: PathBuf,
+    shared: Arc<Shared>,
+}
+
+impl WritableFile for FaultWritableFile {
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        self.shared.check(&self.path, IoOperation::Write)?;
+        self.inner.append(data)?;
+        self.update_size();
+        Ok(())
+    }
+
+    fn positioned_append(&mut self, data: &[u8], offset: u64) -> io::Result<()> {
+        self.shared.check(&self.path, IoOperation::Write)?;
+        self.inner.positioned_append(data, offset)?;
+        self.update_size();
+        Ok(())
+    }
+
+    fn truncate(&mut self, size: u64) -> io::Result<()> {
+        self.shared.check_active(&self.path)?;
+        self.inner.truncate(size)?;
+        self.update_size();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.shared.check_active(&self.path)?;
+        self.inner.flush()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.shared.check(&self.path, IoOperation::Sync)?;
+        self.inner.sync()?;
+        let size = self.inner.size();
+        if let Some(file) = self.shared.state().files.get_mut(&self.path) {
+            file.size = size;
+            file.synced = size;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.inner.close()
+    }
+
+    fn size(&self) -> u64 {
+        self.inner.size()
+    }
+
+    fn use_direct_io(&self) -> bool {
+        self.inner.use_direct_io()
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        self.inner.required_buffer_alignment()
+    }
+}
+
+impl FaultWritableFile {
+    fn update_size(&self) {
+        let size = self.inner.size();
+        if let Some(file) = self.shared.state().files.get_mut(&self.path) {
+            file.size = size;
+        }
+    }
+}
+
+struct FaultInjectionFileSystem(Arc<Shared>);
+
+impl FaultInjectionFileSystem {
+    fn writable_file(
+        &self,
+        path: &Path,
+        inner: Box<dyn WritableFile>,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        // what was in the file before is considered synced
+        let size = inner.size();
+        self.0
+            .state()
+            .files
+            .insert(path.to_owned(), TrackedFile { size, synced: size });
+        Ok(Box::new(FaultWritableFile {
+            inner,
+            path: path.to_owned(),
+            shared: self.0.clone(),
+        }))
+    }
+}
+
+impl FileSystem for FaultInjectionFileSystem {
+    fn new_sequential_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn SequentialFile>> {
+        let inner = self.0.base.new_sequential_file(path, opts)?;
+        Ok(Box::new(FaultSequentialFile {
+            inner,
+            path: path.to_owned(),
+            shared: self.0.clone(),
+        }))
+    }
+
+    fn new_random_access_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn RandomAccessFile>> {
+        let inner = self.0.base.new_random_access_file(path, opts)?;
+        Ok(Box::new(FaultRandomAccessFile {
+            inner,
+            path: path.to_owned(),
+            shared: self.0.clone(),
+        }))
+    }
+
+    fn new_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        self.0.check_active(path)?;
+        let inner = self.0.base.new_writable_file(path, opts)?;
+        self.writable_file(path, inner)
+    }
+
+    fn reopen_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        self.0.check_active(path)?;
+        let inner = self.0.base.reopen_writable_file(path, opts)?;
+        self.writable_file(path, inner)
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool> {
+        self.0.base.file_exists(path)
+    }
+
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<String>> {
+        self.0.base.get_children(dir)
+    }
+
+    fn delete_file(&self, path: &Path) -> io::Result<()> {
+        self.0.check_active(path)?;
+        self.0.base.delete_file(path)?;
+        self.0.state().files.remove(path);
+        Ok(())
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.0.check_active(path)?;
+        self.0.base.create_dir(path)
+    }
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+        self.0.check_active(path)?;
+        self.0.base.create_dir_if_missing(path)
+    }
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()> {
+        self.0.check_active(path)?;
+        self.0.base.delete_dir(path)
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+        self.0.base.get_file_size(path)
+    }
+
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+        self.0.base.get_file_modification_time(path)
+    }
+
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        self.0.check_active(target)?;
+        self.0.base.rename_file(src, target)?;
+        let mut state = self.0.state();
+        match state.files.remove(src) {
+            Some(file) => state.files.insert(target.to_owned(), file),
+            None => state.files.remove(target),
+        };
+        Ok(())
+    }
+
+    fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        self.0.check_active(target)?;
+        self.0.base.link_file(src, target)
+    }
+
+    fn lock_file(&self, path: &Path) -> io::Result<Box<dyn FileLock>> {
+        self.0.base.lock_file(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool> {
+        self.0.base.is_directory(path)
+    }
+
+    fn open_dir(&self, path: &Path) -> io::Result<()> {
+        self.0.base.open_dir(path)
+    }
+
+    fn sync_dir(&self, path: &Path) -> io::Result<()> {
+        self.0.check(path, IoOperation::Sync)?;
+        self.0.base.sync_dir(path)
+    }
+
+    fn absolute_path(&self, path: &Path) -> io::Result<PathBuf> {
+        self.0.base.absolute_path(path)
+    }
+}
@@ -18,6 +18,7 @@ use libc::{self, c_char, c_void, size_t};
 use std::ffi::{CStr, CString};
 use std::path::Path;
 use std::ptr;
+use std::sync::OnceLock;
 
 pub(crate) unsafe fn from_cstr(ptr: *const c_char) -> String {
     let cstr = unsafe { CStr::from_ptr(ptr as *const _) };
@@ -35,6 +36,16 @@ pub(crate) unsafe fn raw_data(ptr: *const c_char, size: usize) -> Option<Vec<u8>
     }
 }
 
+/// Copies `size` bytes at `ptr` into a `String`, replacing invalid UTF-8.
+/// Returns an empty string if `ptr` is null.
+pub(crate) unsafe fn lossy_string(ptr: *const c_char, size: usize) -> String {
+    unsafe {
+        raw_data(ptr, size)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default()
+    }
+}
+
 pub fn error_message(ptr: *const c_char) -> String {
     unsafe {
         let s = from_cstr(ptr);
@@ -85,6 +96,19 @@ macro_rules! ffi_try_impl {
     }};
 }
 
+/// Fails if the linked RocksDB is not the version `librocksdb-sys` was built
+/// for, whose C API internals its extensions rely on. This can only happen
+/// with a system library, the check runs once per process.
+pub(crate) fn check_linked_version() -> Result<(), Error> {
+    static CHECKED: OnceLock<Result<(), Error>> = OnceLock::new();
+    CHECKED
+        .get_or_init(|| unsafe {
+            ffi_try!(ffi::rust_rocksdb_check_linked_version());
+            Ok(())
+        })
+        .clone()
+}
+
 /// Value which can be converted into a C string.
 ///
 /// The trait is used as argument to functions which wish to accept either
@@ -0,0 +1,1484 @@
+//! Storage layer implemented in Rust.
+//!
+//! RocksDB performs all file I/O through a file system object. Implementing
+//! [`FileSystem`] and turning it into an [`Env`] with
+//! [`Env::from_file_system`] routes that I/O through Rust code, which makes
+//! it possible to instrument it, enforce quotas or store the files somewhere
+//! else entirely. [`EnvFileSystem`] exposes the file system of an existing
+//! `Env`, and is the natural base for wrappers that only change part of the
+//! behavior:
+//!
+//! ```
+//! use std::io;
+//! use std::path::Path;
+//! use std::sync::atomic::{AtomicUsize, Ordering};
+//! use std::sync::Arc;
+//!
+//! use rocksdb::file_system::{
+//!     EnvFileSystem, FileLock, FileOptions, RandomAccessFile, SequentialFile, WritableFile,
+//! };
+//! use rocksdb::{Env, FileSystem, Options, DB};
+//!
+//! /// Counts the files created by RocksDB.
+//! struct CountingFileSystem {
+//!     base: EnvFileSystem,
+//!     created: Arc<AtomicUsize>,
+//! }
+//!
+//! impl FileSystem for CountingFileSystem {
+//!     fn new_writable_file(
+//!         &self,
+//!         path: &Path,
+//!         opts: &FileOptions,
+//!     ) -> io::Result<Box<dyn WritableFile>> {
+//!         self.created.fetch_add(1, Ordering::Relaxed);
+//!         self.base.new_writable_file(path, opts)
+//!     }
+//!
+//!     // The remaining methods just forward to `base`.
+//!     # fn new_sequential_file(
+//!     #     &self,
+//!     #     path: &Path,
+//!     #     opts: &FileOptions,
+//!     # ) -> io::Result<Box<dyn SequentialFile>> {
+//!     #     self.base.new_sequential_file(path, opts)
+//!     # }
+//!     # fn new_random_access_file(
+//!     #     &self,
+//!     #     path: &Path,
+//!     #     opts: &FileOptions,
+//!     # ) -> io::Result<Box<dyn RandomAccessFile>> {
+//!     #     self.base.new_random_access_file(path, opts)
+//!     # }
+//!     # fn reopen_writable_file(
+//!     #     &self,
+//!     #     path: &Path,
+//!     #     opts: &FileOptions,
+//!     # ) -> io::Result<Box<dyn WritableFile>> {
+//!     #     self.base.reopen_writable_file(path, opts)
+//!     # }
+//!     # fn file_exists(&self, path: &Path) -> io::Result<bool> {
+//!     #     self.base.file_exists(path)
+//!     # }
+//!     # fn get_children(&self, dir: &Path) -> io::Result<Vec<String>> {
+//!     #     self.base.get_children(dir)
+//!     # }
+//!     # fn delete_file(&self, path: &Path) -> io::Result<()> {
+//!     #     self.base.delete_file(path)
+//!     # }
+//!     # fn create_dir(&self, path: &Path) -> io::Result<()> {
+//!     #     self.base.create_dir(path)
+//!     # }
+//!     # fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+//!     #     self.base.create_dir_if_missing(path)
+//!     # }
+//!     # fn delete_dir(&self, path: &Path) -> io::Result<()> {
+//!     #     self.base.delete_dir(path)
+//!     # }
+//!     # fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+//!     #     self.base.get_file_size(path)
+//!     # }
+//!     # fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+//!     #     self.base.get_file_modification_time(path)
+//!     # }
+//!     # fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+//!     #     self.base.rename_file(src, target)
+//!     # }
+//!     # fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+//!     #     self.base.link_file(src, target)
+//!     # }
+//!     # fn lock_file(&self, path: &Path) -> io::Result<Box<dyn FileLock>> {
+//!     #     self.base.lock_file(path)
+//!     # }
+//!     # fn is_directory(&self, path: &Path) -> io::Result<bool> {
+//!     #     self.base.is_directory(path)
+//!     # }
+//!     # fn sync_dir(&self, path: &Path) -> io::Result<()> {
+//!     #     self.base.sync_dir(path)
+//!     # }
+//!     # fn absolute_path(&self, path: &Path) -> io::Result<std::path::PathBuf> {
+//!     #     self.base.absolute_path(path)
+//!     # }
+//! }
+//!
+//! let tempdir = tempfile::Builder::new()
+//!     .prefix("_path_for_rocksdb_storage_file_system")
+//!     .tempdir()
+//!     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_file_system");
+//! let path = tempdir.path();
+//! let created = Arc::new(AtomicUsize::new(0));
+//! let env = Env::from_file_system(CountingFileSystem {
+//!     base: EnvFileSystem::default(),
+//!     created: created.clone(),
+//! })
+//! .unwrap();
+//!
+//! let mut opts = Options::default();
+//! opts.create_if_missing(true);
+//! opts.set_env(&env);
+//! {
+//!     let db = DB::open(&opts, path).unwrap();
+//!     db.put(b"key", b"value").unwrap();
+//! }
+//! assert!(created.load(Ordering::Relaxed) > 0);
+//! let _ = DB::destroy(&opts, path);
+//! ```
+
+use std::ffi::CString;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::slice;
+
+use libc::{c_char, c_int, c_uchar, c_void, size_t};
+
+use crate::{
+    ffi,
+    ffi_util::{error_message, lossy_string, to_cpath},
+    Env, Error, ErrorKind, ErrorSubKind, IoPriority, Temperature,
+};
+
+/// RocksDB's default alignment of direct I/O, the usual page size.
+const DEFAULT_BUFFER_ALIGNMENT: usize = 4096;
+
+/// The options RocksDB opens a file with, derived from the database options
+/// such as [`Options::set_use_direct_reads`](crate::Options::set_use_direct_reads).
+///
+/// They are hints: a file system that ignores them opens files for buffered
+/// I/O, which RocksDB always supports. A file opened for direct I/O must
+/// say so with `use_direct_io`, as RocksDB then aligns its I/O.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FileOptions {
+    pub use_mmap_reads: bool,
+    pub use_mmap_writes: bool,
+    /// Bypass the OS page cache when reading.
+    pub use_direct_reads: bool,
+    /// Bypass the OS page cache when writing.
+    pub use_direct_writes: bool,
+    pub allow_fallocate: bool,
+    pub fallocate_with_keep_size: bool,
+    pub set_fd_cloexec: bool,
+    /// Sync the file every `bytes_per_sync` bytes written, 0 to never.
+    pub bytes_per_sync: u64,
+    pub strict_bytes_per_sync: bool,
+    /// The priority of the I/O with the rate limiter.
+    pub io_priority: IoPriority,
+    /// The temperature of a file being written, e.g. the one set with
+    /// [`Options::set_last_level_temperature`](crate::Options::set_last_level_temperature),
+    /// which a file system can use to place it on a suitable storage.
+    pub temperature: Temperature,
+}
+
+impl FileOptions {
+    fn from_raw(raw: &ffi::rust_rocksdb_fs_file_options_t) -> Self {
+        Self {
+            use_mmap_reads: raw.use_mmap_reads != 0,
+            use_mmap_writes: raw.use_mmap_writes != 0,
+            use_direct_reads: raw.use_direct_reads != 0,
+            use_direct_writes: raw.use_direct_writes != 0,
+            allow_fallocate: raw.allow_fallocate != 0,
+            fallocate_with_keep_size: raw.fallocate_with_keep_size != 0,
+            set_fd_cloexec: raw.set_fd_cloexec != 0,
+            bytes_per_sync: raw.bytes_per_sync,
+            strict_bytes_per_sync: raw.strict_bytes_per_sync != 0,
+            io_priority: io_priority_from_raw(raw.io_priority),
+            temperature: Temperature::from_raw(raw.temperature),
+        }
+    }
+
+    fn to_raw(self) -> ffi::rust_rocksdb_fs_file_options_t {
+        ffi::rust_rocksdb_fs_file_options_t {
+            use_mmap_reads: c_uchar::from(self.use_mmap_reads),
+            use_mmap_writes: c_uchar::from(self.use_mmap_writes),
+            use_direct_reads: c_uchar::from(self.use_direct_reads),
+            use_direct_writes: c_uchar::from(self.use_direct_writes),
+            allow_fallocate: c_uchar::from(self.allow_fallocate),
+            fallocate_with_keep_size: c_uchar::from(self.fallocate_with_keep_size),
+            set_fd_cloexec: c_uchar::from(self.set_fd_cloexec),
+            strict_bytes_per_sync: c_uchar::from(self.strict_bytes_per_sync),
+            bytes_per_sync: self.bytes_per_sync,
+            io_priority: self.io_priority as c_int,
+            temperature: self.temperature as c_int,
+        }
+    }
+}
+
+impl Default for FileOptions {
+    /// RocksDB's defaults.
+    fn default() -> Self {
+        unsafe {
+            let mut raw: ffi::rust_rocksdb_fs_file_options_t = std::mem::zeroed();
+            ffi::rust_rocksdb_fs_file_options_init(&mut raw);
+            Self::from_raw(&raw)
+        }
+    }
+}
+
+fn io_priority_from_raw(raw: c_int) -> IoPriority {
+    match raw {
+        0 => IoPriority::Low,
+        1 => IoPriority::Mid,
+        2 => IoPriority::High,
+        3 => IoPriority::User,
+        _ => IoPriority::Total,
+    }
+}
+
+/// A file read from start to end, such as a WAL or MANIFEST file.
+pub trait SequentialFile: Send {
+    /// Reads up to `buf.len()` bytes, returning how many were read. Fewer
+    /// bytes than requested must only be returned at the end of the file.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Reads up to `buf.len()` bytes at `offset`, like `read`. Only called
+    /// on files using direct I/O, and unsupported by default.
+    fn positioned_read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let _ = (offset, buf);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "positioned_read is not supported",
+        ))
+    }
+
+    /// Skips `n` bytes.
+    fn skip(&mut self, n: u64) -> io::Result<()>;
+
+    /// Whether the file bypasses the OS page cache. RocksDB then reads it
+    /// with [`positioned_read`](Self::positioned_read), with buffers,
+    /// offsets and sizes aligned to
+    /// [`required_buffer_alignment`](Self::required_buffer_alignment).
+    fn use_direct_io(&self) -> bool {
+        false
+    }
+
+    /// The alignment direct I/O requires, the page size by default.
+    fn required_buffer_alignment(&self) -> usize {
+        DEFAULT_BUFFER_ALIGNMENT
+    }
+}
+
+/// A file read at arbitrary offsets, such as an SST file. Reads may happen
+/// concurrently from several threads.
+pub trait RandomAccessFile: Send + Sync {
+    /// Reads up to `buf.len()` bytes at `offset`, returning how many were
+    /// read. Fewer bytes than requested must only be returned at the end of
+    /// the file.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Whether the file bypasses the OS page cache. RocksDB then aligns the
+    /// buffers, offsets and sizes of its reads to
+    /// [`required_buffer_alignment`](Self::required_buffer_alignment).
+    fn use_direct_io(&self) -> bool {
+        false
+    }
+
+    /// The alignment direct I/O requires, the page size by default.
+    fn required_buffer_alignment(&self) -> usize {
+        DEFAULT_BUFFER_ALIGNMENT
+    }
+}
+
+/// A file written sequentially.
+pub trait WritableFile: Send {
+    /// Appends `data` to the end of the file.
+    fn append(&mut self, data: &[u8]) -> io::Result<()>;
+
+    /// Writes `data` at `offset`. Only called on files using direct I/O,
+    /// which RocksDB writes with aligned buffers, offsets and sizes instead
+    /// of appending. Unsupported by default.
+    fn positioned_append(&mut self, data: &[u8], offset: u64) -> io::Result<()> {
+        let _ = (data, offset);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "positioned_append is not supported",
+        ))
+    }
+
+    /// Truncates the file to `size` bytes. RocksDB uses it to drop the
+    /// padding of the last aligned write of files using direct I/O. Does
+    /// nothing by default.
+    fn truncate(&mut self, size: u64) -> io::Result<()> {
+        let _ = size;
+        Ok(())
+    }
+
+    /// Flushes buffered data to the underlying storage, without necessarily
+    /// making it durable.
+    fn flush(&mut self) -> io::Result<()>;
+
+    /// Makes the data written so far durable.
+    fn sync(&mut self) -> io::Result<()>;
+
+    /// Closes the file. No other method is called afterwards.
+    fn close(&mut self) -> io::Result<()>;
+
+    /// Current size of the file, including data not yet flushed.
+    fn size(&self) -> u64;
+
+    /// Whether the file bypasses the OS page cache. RocksDB then writes it
+    /// with [`positioned_append`](Self::positioned_append).
+    fn use_direct_io(&self) -> bool {
+        false
+    }
+
+    /// The alignment direct I/O requires, the page size by default.
+    fn required_buffer_alignment(&self) -> usize {
+        DEFAULT_BUFFER_ALIGNMENT
+    }
+}
+
+/// A lock acquired with [`FileSystem::lock_file`], released when dropped.
+pub trait FileLock: Send {}
+
+/// The file operations RocksDB needs from the storage layer.
+///
+/// Paths are passed as RocksDB builds them from the database path, so they
+/// are usually absolute. Methods are called concurrently from the foreground
+/// and background threads. Files are opened with the [`FileOptions`]
+/// RocksDB derived from the database options; wrappers should pass them on
+/// to the file system they wrap.
+pub trait FileSystem: Send + Sync + 'static {
+    /// Opens an existing file for sequential reading.
+    fn new_sequential_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn SequentialFile>>;
+
+    /// Opens an existing file for random reads.
+    fn new_random_access_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn RandomAccessFile>>;
+
+    /// Creates a file for writing, truncating it if it exists.
+    fn new_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>>;
+
+    /// Opens a file for appending, creating it if it does not exist.
+    ///
+    /// Unsupported by default.
+    fn reopen_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        let _ = (path, opts);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reopen_writable_file is not supported",
+        ))
+    }
+
+    /// Returns whether `path` exists.
+    fn file_exists(&self, path: &Path) -> io::Result<bool>;
+
+    /// Returns the names, not paths, of the entries of the directory.
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<String>>;
+
+    fn delete_file(&self, path: &Path) -> io::Result<()>;
+
+    /// Creates a directory, failing if it exists.
+    fn create_dir(&self, path: &Path) -> io::Result<()>;
+
+    /// Creates a directory unless it exists.
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()>;
+
+    /// Deletes an empty directory.
+    fn delete_dir(&self, path: &Path) -> io::Result<()>;
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64>;
+
+    /// Returns the modification time of the file in seconds since the epoch.
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64>;
+
+    /// Renames `src` to `target`, replacing `target` if it exists.
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()>;
+
+    /// Creates a hard link. RocksDB falls back to copying when this is not
+    /// supported, which is the default.
+    fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        let _ = (src, target);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "link_file is not supported",
+        ))
+    }
+
+    /// Locks `path`, creating it if needed, so that a database is not opened
+    /// twice. Must fail if the file is already locked, including by the
+    /// current process.
+    fn lock_file(&self, path: &Path) -> io::Result<Box<dyn FileLock>>;
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool>;
+
+    /// Opens a directory that RocksDB will sync with
+    /// [`sync_dir`](Self::sync_dir), failing if it can't be synced. By
+    /// default checks that `path` is a directory.
+    fn open_dir(&self, path: &Path) -> io::Result<()> {
+        if self.is_directory(path)? {
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("{} is not a directory", path.display()),
+            ))
+        }
+    }
+
+    /// Makes the entries of the directory durable.
+    fn sync_dir(&self, path: &Path) -> io::Result<()>;
+
+    /// Returns the absolute form of `path`.
+    fn absolute_path(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+/// The file system of an [`Env`].
+///
+/// Used on its own it behaves exactly like the env; it is meant as the base
+/// of [`FileSystem`] implementations that wrap another file system.
+/// `EnvFileSystem::default()` is the file system of the default env, i.e.
+/// the local file system.
+pub struct EnvFileSystem {
+    inner: *mut ffi::rust_rocksdb_filesystem_t,
+}
+
+unsafe impl Send for EnvFileSystem {}
+unsafe impl Sync for EnvFileSystem {}
+
+impl EnvFileSystem {
+    /// Returns the file system used by `env`.
+    pub fn new(env: &Env) -> Self {
+        let inner = unsafe { ffi::rust_rocksdb_env_get_filesystem(env.0.inner) };
+        Self { inner }
+    }
+}
+
+impl EnvFileSystem {
+    fn open_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+        reopen: bool,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        let path = io_cpath(path)?;
+        let opts = opts.to_raw();
+        let file = io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_new_writable_file(
+                self.inner,
+                path.as_ptr(),
+                &opts,
+                c_uchar::from(reopen),
+                err,
+            )
+        })?;
+        Ok(Box::new(EnvWritableFile(file)))
+    }
+}
+
+impl Default for EnvFileSystem {
+    fn default() -> Self {
+        let inner = unsafe { ffi::rust_rocksdb_filesystem_create_default() };
+        Self { inner }
+    }
+}
+
+impl Drop for EnvFileSystem {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_filesystem_destroy(self.inner);
+        }
+    }
+}
+
+/// Converts an error reported by RocksDB into an `io::Error`, keeping the
+/// kinds RocksDB and [`FileSystem`] implementations care about.
+fn io_error(message: String) -> io::Error {
+    let err = Error::new(message);
+    let kind = match (err.kind(), err.sub_kind()) {
+        (ErrorKind::NotFound, _) | (_, ErrorSubKind::PathNotFound) => io::ErrorKind::NotFound,
+        (ErrorKind::NotSupported, _) => io::ErrorKind::Unsupported,
+        (_, ErrorSubKind::NoSpace | ErrorSubKind::SpaceLimit) => io::ErrorKind::StorageFull,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, err)
+}
+
+/// Calls a shim function taking an `errptr`.
+fn io_try<T>(f: impl FnOnce(*mut *mut c_char) -> T) -> io::Result<T> {
+    let mut err: *mut c_char = ptr::null_mut();
+    let result = f(&mut err);
+    if err.is_null() {
+        Ok(result)
+    } else {
+        Err(io_error(error_message(err)))
+    }
+}
+
+fn io_cpath(path: &Path) -> io::Result<CString> {
+    to_cpath(path).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+struct EnvSequentialFile(*mut ffi::rust_rocksdb_fs_sequential_file_t);
+
+unsafe impl Send for EnvSequentialFile {}
+
+impl SequentialFile for EnvSequentialFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_fs_sequential_file_read(
+                self.0,
+                buf.as_mut_ptr() as *mut c_char,
+                buf.len(),
+                err,
+            )
+        })
+    }
+
+    fn positioned_read(&mut self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_fs_sequential_file_positioned_read(
+                self.0,
+                offset,
+                buf.as_mut_ptr() as *mut c_char,
+                buf.len(),
+                err,
+            )
+        })
+    }
+
+    fn skip(&mut self, n: u64) -> io::Result<()> {
+        io_try(|err| unsafe { ffi::rust_rocksdb_fs_sequential_file_skip(self.0, n, err) })
+    }
+
+    fn use_direct_io(&self) -> bool {
+        let mut alignment: size_t = 0;
+        unsafe { ffi::rust_rocksdb_fs_sequential_file_use_direct_io(self.0, &mut alignment) != 0 }
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        let mut alignment: size_t = 0;
+        unsafe { ffi::rust_rocksdb_fs_sequential_file_use_direct_io(self.0, &mut alignment) };
+        alignment
+    }
+}
+
+impl Drop for EnvSequentialFile {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_fs_sequential_file_destroy(self.0);
+        }
+    }
+}
+
+struct EnvRandomAccessFile(*mut ffi::rust_rocksdb_fs_random_access_file_t);
+
+unsafe impl Send for EnvRandomAccessFile {}
+unsafe impl Sync for EnvRandomAccessFile {}
+
+impl RandomAccessFile for EnvRandomAccessFile {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_fs_random_access_file_read(
+                self.0,
+                offset,
+                buf.as_mut_ptr() as *mut c_char,
+                buf.len(),
+                err,
+            )
+        })
+    }
+
+    fn use_direct_io(&self) -> bool {
+        let mut alignment: size_t = 0;
+        unsafe {
+            ffi::rust_rocksdb_fs_random_access_file_use_direct_io(self.0, &mut alignment) != 0
+        }
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        let mut alignment: size_t = 0;
+        unsafe { ffi::rust_rocksdb_fs_random_access_file_use_direct_io(self.0, &mut alignment) };
+        alignment
+    }
+}
+
+impl Drop for EnvRandomAccessFile {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_fs_random_access_file_destroy(self.0);
+        }
+    }
+}
+
+struct EnvWritableFile(*mut ffi::rust_rocksdb_fs_writable_file_t);
+
+unsafe impl Send for EnvWritableFile {}
+
+impl WritableFile for EnvWritableFile {
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_fs_writable_file_append(
+                self.0,
+                data.as_ptr() as *const c_char,
+                data.len(),
+                err,
+            );
+        })
+    }
+
+    fn positioned_append(&mut self, data: &[u8], offset: u64) -> io::Result<()> {
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_fs_writable_file_positioned_append(
+                self.0,
+                data.as_ptr() as *const c_char,
+                data.len(),
+                offset,
+                err,
+            );
+        })
+    }
+
+    fn truncate(&mut self, size: u64) -> io::Result<()> {
+        io_try(|err| unsafe { ffi::rust_rocksdb_fs_writable_file_truncate(self.0, size, err) })
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io_try(|err| unsafe { ffi::rust_rocksdb_fs_writable_file_flush(self.0, err) })
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        io_try(|err| unsafe { ffi::rust_rocksdb_fs_writable_file_sync(self.0, err) })
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        io_try(|err| unsafe { ffi::rust_rocksdb_fs_writable_file_close(self.0, err) })
+    }
+
+    fn size(&self) -> u64 {
+        unsafe { ffi::rust_rocksdb_fs_writable_file_get_size(self.0) }
+    }
+
+    fn use_direct_io(&self) -> bool {
+        let mut alignment: size_t = 0;
+        unsafe { ffi::rust_rocksdb_fs_writable_file_use_direct_io(self.0, &mut alignment) != 0 }
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        let mut alignment: size_t = 0;
+        unsafe { ffi::rust_rocksdb_fs_writable_file_use_direct_io(self.0, &mut alignment) };
+        alignment
+    }
+}
+
+impl Drop for EnvWritableFile {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_fs_writable_file_destroy(self.0);
+        }
+    }
+}
+
+struct EnvFileLock(*mut ffi::rust_rocksdb_fs_file_lock_t);
+
+unsafe impl Send for EnvFileLock {}
+
+impl FileLock for EnvFileLock {}
+
+impl Drop for EnvFileLock {
+    fn drop(&mut self) {
+        // Errors can't be reported from here; the handle is released anyway.
+        let _ = io_try(|err| unsafe { ffi::rust_rocksdb_fs_file_lock_unlock(self.0, err) });
+    }
+}
+
+impl FileSystem for EnvFileSystem {
+    fn new_sequential_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn SequentialFile>> {
+        let path = io_cpath(path)?;
+        let opts = opts.to_raw();
+        let file = io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_new_sequential_file(self.inner, path.as_ptr(), &opts, err)
+        })?;
+        Ok(Box::new(EnvSequentialFile(file)))
+    }
+
+    fn new_random_access_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn RandomAccessFile>> {
+        let path = io_cpath(path)?;
+        let opts = opts.to_raw();
+        let file = io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_new_random_access_file(
+                self.inner,
+                path.as_ptr(),
+                &opts,
+                err,
+            )
+        })?;
+        Ok(Box::new(EnvRandomAccessFile(file)))
+    }
+
+    fn new_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        self.open_writable_file(path, opts, false)
+    }
+
+    fn reopen_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        self.open_writable_file(path, opts, true)
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_file_exists(self.inner, path.as_ptr(), err) != 0
+        })
+    }
+
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<String>> {
+        let dir = io_cpath(dir)?;
+        let mut children: Vec<String> = Vec::new();
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_get_children(
+                self.inner,
+                dir.as_ptr(),
+                ptr::from_mut(&mut children) as *mut c_void,
+                Some(push_string_callback),
+                err,
+            );
+        })?;
+        Ok(children)
+    }
+
+    fn delete_file(&self, path: &Path) -> io::Result<()> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_delete_file(self.inner, path.as_ptr(), err);
+        })
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_create_dir(self.inner, path.as_ptr(), 0, err);
+        })
+    }
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_create_dir(self.inner, path.as_ptr(), 1, err);
+        })
+    }
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_delete_dir(self.inner, path.as_ptr(), err);
+        })
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_get_file_size(self.inner, path.as_ptr(), err)
+        })
+    }
+
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_get_file_modification_time(self.inner, path.as_ptr(), err)
+        })
+    }
+
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        let src = io_cpath(src)?;
+        let target = io_cpath(target)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_rename_file(
+                self.inner,
+                src.as_ptr(),
+                target.as_ptr(),
+                err,
+            );
+        })
+    }
+
+    fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        let src = io_cpath(src)?;
+        let target = io_cpath(target)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_link_file(self.inner, src.as_ptr(), target.as_ptr(), err);
+        })
+    }
+
+    fn lock_file(&self, path: &Path) -> io::Result<Box<dyn FileLock>> {
+        let path = io_cpath(path)?;
+        let lock = io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_lock_file(self.inner, path.as_ptr(), err)
+        })?;
+        Ok(Box::new(EnvFileLock(lock)))
+    }
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_is_directory(self.inner, path.as_ptr(), err) != 0
+        })
+    }
+
+    fn open_dir(&self, path: &Path) -> io::Result<()> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_open_dir(self.inner, path.as_ptr(), err);
+        })
+    }
+
+    fn sync_dir(&self, path: &Path) -> io::Result<()> {
+        let path = io_cpath(path)?;
+        io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_sync_dir(self.inner, path.as_ptr(), err);
+        })
+    }
+
+    fn absolute_path(&self, path: &Path) -> io::Result<PathBuf> {
+        let path = io_cpath(path)?;
+        let mut len: size_t = 0;
+        let absolute = io_try(|err| unsafe {
+            ffi::rust_rocksdb_filesystem_get_absolute_path(self.inner, path.as_ptr(), &mut len, err)
+        })?;
+        unsafe {
+            let path = path_from_raw(absolute, len);
+            ffi::rocksdb_free(absolute as *mut c_void);
+            path
+        }
+    }
+}
+
+unsafe extern "C" fn push_string_callback(out: *mut c_void, s: *const c_char, len: size_t) {
+    unsafe {
+        let out = &mut *(out as *mut Vec<String>);
+        out.push(lossy_string(s, len));
+    }
+}
+
+// Callbacks of the file systems created by `Env::from_file_system`.
+
+/// Returns a path passed by RocksDB.
+pub(crate) unsafe fn path_from_raw(path: *const c_char, len: size_t) -> io::Result<PathBuf> {
+    let bytes = if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(path as *const u8, len) }
+    };
+    path_from_bytes(bytes)
+}
+
+// RocksDB paths are byte strings, which are taken as is on Unix and must be
+// UTF-8 elsewhere, as RocksDB itself requires on Windows.
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    Ok(PathBuf::from(OsStr::from_bytes(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    std::str::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))
+}
+
+#[cfg(unix)]
+fn path_to_bytes(path: &Path) -> io::Result<&[u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_to_bytes(path: &Path) -> io::Result<&[u8]> {
+    path.to_str().map(str::as_bytes).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("path is not valid UTF-8: {}", path.display()),
+        )
+    })
+}
+
+pub(crate) unsafe fn set_error(err: *mut ffi::rust_rocksdb_fs_error_t, e: &io::Error) {
+    let code = match e.kind() {
+        io::ErrorKind::NotFound => 1,
+        io::ErrorKind::Unsupported => 2,
+        io::ErrorKind::StorageFull => 3,
+        _ => 0,
+    };
+    let msg = e.to_string();
+    unsafe {
+        ffi::rust_rocksdb_fs_error_set(err, code, msg.as_ptr() as *const c_char, msg.len());
+    }
+}
+
+/// Returns the value, or reports the error and returns the default.
+unsafe fn report<T: Default>(err: *mut ffi::rust_rocksdb_fs_error_t, result: io::Result<T>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            unsafe { set_error(err, &e) };
+            T::default()
+        }
+    }
+}
+
+/// Boxes the value for the C++ side, or reports the error and returns null.
+unsafe fn report_boxed<T>(
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+    result: io::Result<T>,
+) -> *mut c_void {
+    match result {
+        Ok(value) => Box::into_raw(Box::new(value)).cast::<c_void>(),
+        Err(e) => {
+            unsafe { set_error(err, &e) };
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns a `&mut [u8]` over a read buffer provided by RocksDB, which may
+/// be uninitialized.
+///
+/// The buffer is zeroed first, as a `&mut [u8]` must not point to
+/// uninitialized memory. This costs a memset of the requested size per read,
+/// which is accepted so that [`SequentialFile`] and [`RandomAccessFile`] can
+/// take the same buffers as `std::io::Read` and be implemented on top of it.
+/// RocksDB reads blocks and WAL records, so the memset is small next to the
+/// I/O itself.
+unsafe fn read_buffer<'a>(buf: *mut c_char, n: size_t) -> &'a mut [u8] {
+    if n == 0 {
+        return &mut [];
+    }
+    unsafe {
+        ptr::write_bytes(buf, 0, n);
+        slice::from_raw_parts_mut(buf as *mut u8, n)
+    }
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<F: FileSystem>(raw_cb: *mut c_void) {
+    drop(unsafe { Box::from_raw(raw_cb as *mut F) });
+}
+
+unsafe extern "C" fn new_sequential_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    opts: *const ffi::rust_rocksdb_fs_file_options_t,
+    use_direct_io: *mut c_uchar,
+    alignment: *mut size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> *mut c_void {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        let opts = FileOptions::from_raw(&*opts);
+        let file =
+            path_from_raw(path, path_len).and_then(|path| fs.new_sequential_file(&path, &opts));
+        if let Ok(file) = &file {
+            *use_direct_io = c_uchar::from(file.use_direct_io());
+            *alignment = file.required_buffer_alignment();
+        }
+        report_boxed(err, file)
+    }
+}
+
+unsafe extern "C" fn new_random_access_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    opts: *const ffi::rust_rocksdb_fs_file_options_t,
+    use_direct_io: *mut c_uchar,
+    alignment: *mut size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> *mut c_void {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        let opts = FileOptions::from_raw(&*opts);
+        let file =
+            path_from_raw(path, path_len).and_then(|path| fs.new_random_access_file(&path, &opts));
+        if let Ok(file) = &file {
+            *use_direct_io = c_uchar::from(file.use_direct_io());
+            *alignment = file.required_buffer_alignment();
+        }
+        report_boxed(err, file)
+    }
+}
+
+unsafe extern "C" fn new_writable_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    opts: *const ffi::rust_rocksdb_fs_file_options_t,
+    reopen: c_uchar,
+    use_direct_io: *mut c_uchar,
+    alignment: *mut size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> *mut c_void {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        let opts = FileOptions::from_raw(&*opts);
+        let file = path_from_raw(path, path_len).and_then(|path| {
+            if reopen == 0 {
+                fs.new_writable_file(&path, &opts)
+            } else {
+                fs.reopen_writable_file(&path, &opts)
+            }
+        });
+        if let Ok(file) = &file {
+            *use_direct_io = c_uchar::from(file.use_direct_io());
+            *alignment = file.required_buffer_alignment();
+        }
+        report_boxed(err, file)
+    }
+}
+
+unsafe extern "C" fn file_exists_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> c_uchar {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        c_uchar::from(report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.file_exists(&path)),
+        ))
+    }
+}
+
+unsafe extern "C" fn get_children_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    out: *mut c_void,
+    push: ffi::rust_rocksdb_fs_push_string_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        let children = report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.get_children(&path)),
+        );
+        if let Some(push) = push {
+            for name in children {
+                push(out, name.as_ptr() as *const c_char, name.len());
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn delete_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.delete_file(&path)),
+        );
+    }
+}
+
+unsafe extern "C" fn create_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    if_missing: c_uchar,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        let result = path_from_raw(path, path_len).and_then(|path| {
+            if if_missing == 0 {
+                fs.create_dir(&path)
+            } else {
+                fs.create_dir_if_missing(&path)
+            }
+        });
+        report(err, result);
+    }
+}
+
+unsafe extern "C" fn delete_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.delete_dir(&path)),
+        );
+    }
+}
+
+unsafe extern "C" fn get_file_size_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> u64 {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.get_file_size(&path)),
+        )
+    }
+}
+
+unsafe extern "C" fn get_file_modification_time_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> u64 {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.get_file_modification_time(&path)),
+        )
+    }
+}
+
+unsafe extern "C" fn rename_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    src: *const c_char,
+    src_len: size_t,
+    target: *const c_char,
+    target_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(src, src_len)
+                .and_then(|src| fs.rename_file(&src, &path_from_raw(target, target_len)?)),
+        );
+    }
+}
+
+unsafe extern "C" fn link_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    src: *const c_char,
+    src_len: size_t,
+    target: *const c_char,
+    target_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(src, src_len)
+                .and_then(|src| fs.link_file(&src, &path_from_raw(target, target_len)?)),
+        );
+    }
+}
+
+unsafe extern "C" fn lock_file_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> *mut c_void {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report_boxed(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.lock_file(&path)),
+        )
+    }
+}
+
+unsafe extern "C" fn unlock_file_callback(
+    _raw_cb: *mut c_void,
+    lock: *mut c_void,
+    _err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    drop(unsafe { Box::from_raw(lock as *mut Box<dyn FileLock>) });
+}
+
+unsafe extern "C" fn is_directory_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> c_uchar {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        c_uchar::from(report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.is_directory(&path)),
+        ))
+    }
+}
+
+unsafe extern "C" fn open_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.open_dir(&path)),
+        );
+    }
+}
+
+unsafe extern "C" fn sync_dir_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        report(
+            err,
+            path_from_raw(path, path_len).and_then(|path| fs.sync_dir(&path)),
+        );
+    }
+}
+
+unsafe extern "C" fn get_absolute_path_callback<F: FileSystem>(
+    raw_cb: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    out: *mut c_void,
+    push: ffi::rust_rocksdb_fs_push_string_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let fs = &*(raw_cb as *const F);
+        match path_from_raw(path, path_len).and_then(|path| fs.absolute_path(&path)) {
+            Ok(absolute) => match path_to_bytes(&absolute) {
+                Ok(absolute) => {
+                    if let Some(push) = push {
+                        push(out, absolute.as_ptr() as *const c_char, absolute.len());
+                    }
+                }
+                Err(e) => set_error(err, &e),
+            },
+            Err(e) => set_error(err, &e),
+        }
+    }
+}
+
+unsafe extern "C" fn sequential_file_read_callback(
+    file: *mut c_void,
+    buf: *mut c_char,
+    n: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> size_t {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn SequentialFile>);
+        report(err, file.read(read_buffer(buf, n)))
+    }
+}
+
+unsafe extern "C" fn sequential_file_positioned_read_callback(
+    file: *mut c_void,
+    offset: u64,
+    buf: *mut c_char,
+    n: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> size_t {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn SequentialFile>);
+        report(err, file.positioned_read(offset, read_buffer(buf, n)))
+    }
+}
+
+unsafe extern "C" fn sequential_file_skip_callback(
+    file: *mut c_void,
+    n: u64,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn SequentialFile>);
+        report(err, file.skip(n));
+    }
+}
+
+unsafe extern "C" fn sequential_file_destroy_callback(file: *mut c_void) {
+    drop(unsafe { Box::from_raw(file as *mut Box<dyn SequentialFile>) });
+}
+
+unsafe extern "C" fn random_access_file_read_callback(
+    file: *mut c_void,
+    offset: u64,
+    buf: *mut c_char,
+    n: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) -> size_t {
+    unsafe {
+        let file = &*(file as *const Box<dyn RandomAccessFile>);
+        report(err, file.read_at(offset, read_buffer(buf, n)))
+    }
+}
+
+unsafe extern "C" fn random_access_file_destroy_callback(file: *mut c_void) {
+    drop(unsafe { Box::from_raw(file as *mut Box<dyn RandomAccessFile>) });
+}
+
+unsafe extern "C" fn writable_file_append_callback(
+    file: *mut c_void,
+    data: *const c_char,
+    n: size_t,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        let data = if n == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(data as *const u8, n)
+        };
+        report(err, file.append(data));
+    }
+}
+
+unsafe extern "C" fn writable_file_positioned_append_callback(
+    file: *mut c_void,
+    data: *const c_char,
+    n: size_t,
+    offset: u64,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        let data = if n == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(data as *const u8, n)
+        };
+        report(err, file.positioned_append(data, offset));
+    }
+}
+
+unsafe extern "C" fn writable_file_truncate_callback(
+    file: *mut c_void,
+    size: u64,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        report(err, file.truncate(size));
+    }
+}
+
+unsafe extern "C" fn writable_file_flush_callback(
+    file: *mut c_void,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        report(err, file.flush());
+    }
+}
+
+unsafe extern "C" fn writable_file_sync_callback(
+    file: *mut c_void,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        report(err, file.sync());
+    }
+}
+
+unsafe extern "C" fn writable_file_close_callback(
+    file: *mut c_void,
+    err: *mut ffi::rust_rocksdb_fs_error_t,
+) {
+    unsafe {
+        let file = &mut *(file as *mut Box<dyn WritableFile>);
+        report(err, file.close());
+    }
+}
+
+unsafe extern "C" fn writable_file_get_size_callback(file: *mut c_void) -> u64 {
+    unsafe {
+        let file = &*(file as *const Box<dyn WritableFile>);
+        file.size()
+    }
+}
+
+unsafe extern "C" fn writable_file_destroy_callback(file: *mut c_void) {
+    drop(unsafe { Box::from_raw(file as *mut Box<dyn WritableFile>) });
+}
+
+pub(crate) fn callbacks<F: FileSystem>() -> ffi::rust_rocksdb_filesystem_callbacks_t {
+    ffi::rust_rocksdb_filesystem_callbacks_t {
+        new_sequential_file: Some(new_sequential_file_callback::<F>),
+        new_random_access_file: Some(new_random_access_file_callback::<F>),
+        new_writable_file: Some(new_writable_file_callback::<F>),
+        file_exists: Some(file_exists_callback::<F>),
+        get_children: Some(get_children_callback::<F>),
+        delete_file: Some(delete_file_callback::<F>),
+        create_dir: Some(create_dir_callback::<F>),
+        delete_dir: Some(delete_dir_callback::<F>),
+        get_file_size: Some(get_file_size_callback::<F>),
+        get_file_modification_time: Some(get_file_modification_time_callback::<F>),
+        rename_file: Some(rename_file_callback::<F>),
+        link_file: Some(link_file_callback::<F>),
+        lock_file: Some(lock_file_callback::<F>),
+        unlock_file: Some(unlock_file_callback),
+        is_directory: Some(is_directory_callback::<F>),
+        open_dir: Some(open_dir_callback::<F>),
+        sync_dir: Some(sync_dir_callback::<F>),
+        get_absolute_path: Some(get_absolute_path_callback::<F>),
+        sequential_file_read: Some(sequential_file_read_callback),
+        sequential_file_positioned_read: Some(sequential_file_positioned_read_callback),
+        sequential_file_skip: Some(sequential_file_skip_callback),
+        sequential_file_destroy: Some(sequential_file_destroy_callback),
+        random_access_file_read: Some(random_access_file_read_callback),
+        random_access_file_destroy: Some(random_access_file_destroy_callback),
+        writable_file_append: Some(writable_file_append_callback),
+        writable_file_positioned_append: Some(writable_file_positioned_append_callback),
+        writable_file_truncate: Some(writable_file_truncate_callback),
+        writable_file_flush: Some(writable_file_flush_callback),
+        writable_file_sync: Some(writable_file_sync_callback),
+        writable_file_close: Some(writable_file_close_callback),
+        writable_file_get_size: Some(writable_file_get_size_callback),
+        writable_file_destroy: Some(writable_file_destroy_callback),
+    }
+}
@@ -84,6 +84,7 @@
 mod ffi_util;
 
 pub mod backup;
+mod change_stream;
 pub mod checkpoint;
 mod column_family;
 pub mod compaction_filter;
@@ -93,58 +94,82 @@ mod db;
 mod db_iterator;
 mod db_options;
 mod db_pinnable_slice;
+pub mod encryption;
 mod env;
+pub mod event_listener;
+pub mod fault_injection;
+pub mod file_system;
 mod iter_range;
+pub mod logger;
 pub mod merge_operator;
 pub mod perf;
 mod prop_name;
 pub mod properties;
 mod slice_transform;
 mod snapshot;
+mod sst_file_manager;
 mod sst_file_writer;
 pub mod statistics;
+pub mod table_properties;
 mod transactions;
+mod wide_columns;
 mod write_batch;
+mod write_batch_with_index;
 
 pub use crate::{
+    change_stream::{Change, ChangeEvent, ChangeOp, ChangeStream},
     column_family::{
         AsColumnFamilyRef, BoundColumnFamily, ColumnFamily, ColumnFamilyDescriptor,
         ColumnFamilyRef, ColumnFamilyTtl, DEFAULT_COLUMN_FAMILY_NAME,
     },
     compaction_filter::Decision as CompactionDecision,
     db::{
-        DBAccess, DBCommon, DBWithThreadMode, LiveFile, MultiThreaded, Range, SingleThreaded,
-        ThreadMode, DB,
+        BlobMetaData, ColumnFamilyMetaData, DBAccess, DBCommon, DBWithThreadMode, LevelMetaData,
+        LiveFile, MultiThreaded, Range, SingleThreaded, SstFileMetaData, ThreadMode, DB,
     },
     db_iterator::{
         DBIterator, DBIteratorWithThreadMode, DBRawIterator, DBRawIteratorWithThreadMode,
-        DBWALIterator, Direction, IteratorMode,
+        DBTimestampedIterator, DBTimestampedIteratorWithThreadMode, DBWALIterator, Direction,
+        IteratorMode, VersionValue,
     },
     db_options::{
         BlockBasedIndexType, BlockBasedOptions, BlockBasedTablePinningTier,
-        BottommostLevelCompaction, Cache, ChecksumType, CompactOptions, CompactionPri,
-        CuckooTableOptions, DBCompactionStyle, DBCompressionType, DBPath, DBRecoveryMode,
-        DataBlockIndexType, FifoCompactOptions, FlushOptions, IngestExternalFileOptions,
+        BottommostLevelCompaction, Cache, CacheEntryRole, CacheEntryRoleStats, ChecksumType,
+        CompactOptions, CompactionOptions, CompactionPri, CompressedSecondaryCache,
+        CompressedSecondaryCacheOptions, CuckooTableOptions, DBCompactionStyle, DBCompressionType,
+        DBPath, DBRecoveryMode, DataBlockIndexType, FifoCompactOptions, FileTemperatureAge,
+        FlushOptions, ImportColumnFamilyOptions, IngestExternalFileOptions, IoPriority,
         KeyEncodingType, LogLevel, LruCacheOptions, MemtableFactory, Options,
-        PlainTableFactoryOptions, ReadOptions, ReadTier, UniversalCompactOptions,
-        UniversalCompactionStopStyle, WaitForCompactOptions, WriteBufferManager, WriteOptions,
+        PlainTableFactoryOptions, RateLimiter, RateLimiterMode, ReadOptions, ReadTier, Temperature,
+        UniversalCompactOptions, UniversalCompactionStopStyle, WaitForCompactOptions,
+        WriteBufferManager, WriteOptions,
     },
     db_pinnable_slice::DBPinnableSlice,
     env::Env,
+    event_listener::EventListener,
+    fault_injection::FaultInjectionEnv,
     ffi_util::CStrLike,
+    file_system::FileSystem,
     iter_range::{IterateBounds, PrefixRange},
+    logger::Logger,
     merge_operator::MergeOperands,
     perf::{PerfContext, PerfMetric, PerfStatsLevel},
     slice_transform::SliceTransform,
     snapshot::{Snapshot, SnapshotWithThreadMode},
+    sst_file_manager::SstFileManager,
     sst_file_writer::SstFileWriter,
+    table_properties::TableProperties,
     transactions::{
-        OptimisticTransactionDB, OptimisticTransactionOptions, Transaction, TransactionDB,
-        TransactionDBOptions, TransactionOptions,
+        DeadlockInfo, DeadlockPath, KeyLockInfo, OptimisticTransactionDB,
+        OptimisticTransactionOptions, Transaction, TransactionDB, TransactionDBOptions,
+        TransactionOptions, TransactionWaitHandle, TxnDBWritePolicy, WaitingTxns,
     },
+    wide_columns::{WideColumns, DEFAULT_WIDE_COLUMN_NAME},
     write_batch::{
-        WriteBatch, WriteBatchIterator, WriteBatchIteratorCf, WriteBatchWithTransaction,
+        WriteBatch, WriteBatchIterator, WriteBatchIteratorCf, WriteBatchLike, WriteBatchOp,
+        WriteBatchWithTransaction,
     },
+    write_batch_with_index::WriteBatchWithIndex,
 };
 
 use librocksdb_sys as ffi;
@@ -173,6 +198,31 @@ pub enum ErrorKind {
     Unknown,
 }
 
+/// RocksDB error subcode, refining the [`ErrorKind`] of some errors.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorSubKind {
+    /// No subcode, or one this version of the crate does not know about.
+    None,
+    MutexTimeout,
+    LockTimeout,
+    LockLimit,
+    /// The device is full.
+    NoSpace,
+    Deadlock,
+    StaleFile,
+    MemoryLimit,
+    /// The space allowed by the [`SstFileManager`] was reached.
+    SpaceLimit,
+    PathNotFound,
+    MergeOperandsInsufficientCapacity,
+    ManualCompactionPaused,
+    TxnNotPrepared,
+    IOFenced,
+    MergeOperatorFailed,
+    MergeOperandThresholdExceeded,
+}
+
 /// A simple wrapper round a string, used for errors reported from
 /// ffi calls.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -210,6 +260,47 @@ impl Error {
             _ => ErrorKind::Unknown,
         }
     }
+
+    /// Parse corresponding [`ErrorSubKind`] from error message, which RocksDB
+    /// writes right after the one of the [`ErrorKind`].
+    pub fn sub_kind(&self) -> ErrorSubKind {
+        const SUB_KINDS: [(&str, ErrorSubKind); 15] = [
+            ("Timeout Acquiring Mutex", ErrorSubKind::MutexTimeout),
+            ("Timeout waiting to lock key", ErrorSubKind::LockTimeout),
+            (
+                "Failed to acquire lock due to max_num_locks limit",
+                ErrorSubKind::LockLimit,
+            ),
+            ("No space left on device", ErrorSubKind::NoSpace),
+            ("Deadlock", ErrorSubKind::Deadlock),
+            ("Stale file handle", ErrorSubKind::StaleFile),
+            ("Memory limit reached", ErrorSubKind::MemoryLimit),
+            ("Space limit reached", ErrorSubKind::SpaceLimit),
+            ("No such file or directory", ErrorSubKind::PathNotFound),
+            (
+                "Insufficient capacity for merge operands",
+                ErrorSubKind::MergeOperandsInsufficientCapacity,
+            ),
+            (
+                "Manual compaction paused",
+                ErrorSubKind::ManualCompactionPaused,
+            ),
+            ("Txn not prepared", ErrorSubKind::TxnNotPrepared),
+            ("IO fenced off", ErrorSubKind::IOFenced),
+            ("Merge operator failed", ErrorSubKind::MergeOperatorFailed),
+            (
+                "Number of operands merged exceeded threshold",
+                ErrorSubKind::MergeOperandThresholdExceeded,
+            ),
+        ];
+        let Some((_, rest)) = self.message.split_once(": ") else {
+            return ErrorSubKind::None;
+        };
+        SUB_KINDS
+            .iter()
+            .find(|(message, _)| rest.starts_with(message))
+            .map_or(ErrorSubKind::None, |&(_, sub_kind)| sub_kind)
+    }
 }
 
 impl AsRef<str> for Error {
@@ -245,11 +336,13 @@ mod test {
 
     use super::{
         column_family::UnboundColumnFamily,
+        db::DBWithThreadModeInner,
         db_options::{CacheWrapper, WriteBufferManagerWrapper},
         env::{Env, EnvWrapper},
-        BlockBasedOptions, BoundColumnFamily, Cache, ColumnFamily, ColumnFamilyDescriptor,
-        DBIterator, DBRawIterator, IngestExternalFileOptions, Options, PlainTableFactoryOptions,
-        ReadOptions, Snapshot, SstFileWriter, WriteBatch, WriteBufferManager, WriteOptions, DB,
+        BlockBasedOptions, BoundColumnFamily, Cache, ChangeStream, ColumnFamily,
+        ColumnFamilyDescriptor, DBIterator, DBRawIterator, DBWALIterator,
+        IngestExternalFileOptions, MultiThreaded, Options, PlainTableFactoryOptions, ReadOptions,
+        SingleThreaded, Snapshot, SstFileWriter, WriteBatch, WriteBufferManager, WriteOptions, DB,
     };
 
     #[test]
@@ -289,6 +382,9 @@ mod test {
         is_send::<TransactionOptions>();
         is_send::<WriteBufferManager>();
         is_send::<WriteBufferManagerWrapper>();
+        is_send::<DBWALIterator>();
+        is_send::<ChangeStream<'_, SingleThreaded, DBWithThreadModeInner>>();
+        is_send::<ChangeStream<'_, MultiThreaded, DBWithThreadModeInner>>();
     }
 
     #[test]
@@ -0,0 +1,93 @@
+//! Routing of the RocksDB info log into Rust.
+//!
+//! By default RocksDB writes its log to a `LOG` file in the database (or
+//! [`Options::set_db_log_dir`](crate::Options::set_db_log_dir)) directory.
+//! [`Options::set_info_logger`](crate::Options::set_info_logger) replaces
+//! that file with a [`Logger`] implementation. With the `log` or `tracing`
+//! crate features enabled, [`LogLogger`] and [`TracingLogger`] forward the
+//! lines to the respective crate under the `rocksdb` target.
+
+use crate::LogLevel;
+use libc::{c_char, c_uint, c_void, size_t};
+use std::slice;
+
+/// Receives the lines of the RocksDB info log.
+///
+/// Called from any thread that logs, including RocksDB's background threads,
+/// so implementations must be thread-safe and should not block.
+pub trait Logger: Send + Sync + 'static {
+    /// Handles a single formatted log line, without trailing newline.
+    fn log(&self, level: LogLevel, message: &str);
+}
+
+impl<F> Logger for F
+where
+    F: Fn(LogLevel, &str) + Send + Sync + 'static,
+{
+    fn log(&self, level: LogLevel, message: &str) {
+        self(level, message);
+    }
+}
+
+/// Forwards the RocksDB info log to the [`log`](https://docs.rs/log) crate.
+///
+/// `Fatal` lines are logged as errors and `Header` lines as info.
+#[cfg(feature = "log")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogLogger;
+
+#[cfg(feature = "log")]
+impl Logger for LogLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        let level = match level {
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Info | LogLevel::Header => log::Level::Info,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Error | LogLevel::Fatal => log::Level::Error,
+        };
+        log::log!(target: "rocksdb", level, "{message}");
+    }
+}
+
+/// Forwards the RocksDB info log to the [`tracing`](https://docs.rs/tracing)
+/// crate as events.
+///
+/// `Fatal` lines are emitted as errors and `Header` lines as info.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TracingLogger;
+
+#[cfg(feature = "tracing")]
+impl Logger for TracingLogger {
+    fn log(&self, level: LogLevel, message: &str) {
+        match level {
+            LogLevel::Debug => tracing::debug!(target: "rocksdb", "{message}"),
+            LogLevel::Info | LogLevel::Header => tracing::info!(target: "rocksdb", "{message}"),
+            LogLevel::Warn => tracing::warn!(target: "rocksdb", "{message}"),
+            LogLevel::Error | LogLevel::Fatal => tracing::error!(target: "rocksdb", "{message}"),
+        }
+    }
+}
+
+fn level_from_raw(level: c_uint) -> LogLevel {
+    match level {
+        0 => LogLevel::Debug,
+        1 => LogLevel::Info,
+        2 => LogLevel::Warn,
+        3 => LogLevel::Error,
+        4 => LogLevel::Fatal,
+        _ => LogLevel::Header,
+    }
+}
+
+pub(crate) unsafe extern "C" fn log_callback<L: Logger>(
+    raw_cb: *mut c_void,
+    level: c_uint,
+    msg: *mut c_char,
+    len: size_t,
+) {
+    let cb = unsafe { &*(raw_cb as *const L) };
+    let msg = unsafe { slice::from_raw_parts(msg as *const u8, len) };
+    let msg = String::from_utf8_lossy(msg);
+    cb.log(level_from_raw(level), msg.trim_end_matches('\n'));
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use libc::{c_char, c_void, size_t};
+
+use crate::{ffi, ffi_util::lossy_string, Env, Error};
+
+pub(crate) struct SstFileManagerWrapper {
+    pub(crate) inner: NonNull<ffi::rust_rocksdb_sst_file_manager_t>,
+    // the manager only keeps a raw pointer to the env
+    _env: Env,
+}
+
+impl Drop for SstFileManagerWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_destroy(self.inner.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for SstFileManagerWrapper {}
+unsafe impl Sync for SstFileManagerWrapper {}
+
+/// Tracks the SST files of one or more databases, set with
+/// [`Options::set_sst_file_manager`](crate::Options::set_sst_file_manager).
+///
+/// It can cap the space used by the SST files: once the limit is reached,
+/// flushes and compactions stop and writes fail with an error of sub kind
+/// [`ErrorSubKind::SpaceLimit`](crate::ErrorSubKind::SpaceLimit), until files
+/// are deleted or the limit is raised. It can also rate limit the deletion of
+/// obsolete files, which are then renamed to `*.trash` files and deleted in
+/// the background, to avoid the I/O spikes of deleting many files at once.
+#[derive(Clone)]
+pub struct SstFileManager(pub(crate) Arc<SstFileManagerWrapper>);
+
+impl SstFileManager {
+    /// Creates a manager for the files of `env`, without space limit nor
+    /// deletion rate limit.
+    pub fn new(env: &Env) -> Result<Self, Error> {
+        let inner = unsafe { ffi_try!(ffi::rust_rocksdb_sst_file_manager_create(env.0.inner)) };
+        let inner = NonNull::new(inner)
+            .ok_or_else(|| Error::new("Could not create SST file manager".to_owned()))?;
+        Ok(Self(Arc::new(SstFileManagerWrapper {
+            inner,
+            _env: env.clone(),
+        })))
+    }
+
+    /// Sets the maximum space the SST files may use, in bytes. 0 means
+    /// unlimited.
+    ///
+    /// Default: 0
+    pub fn set_max_allowed_space_usage(&self, max_allowed_space: u64) {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_set_max_allowed_space_usage(
+                self.0.inner.as_ptr(),
+                max_allowed_space,
+            );
+        }
+    }
+
+    /// Sets the space, in bytes, to leave free on top of the estimated
+    /// output of a compaction before starting it, so that other writes
+    /// still fit while it runs. Compactions that would exceed the limit are
+    /// not started.
+    ///
+    /// Default: 0
+    pub fn set_compaction_buffer_size(&self, compaction_buffer_size: u64) {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_set_compaction_buffer_size(
+                self.0.inner.as_ptr(),
+                compaction_buffer_size,
+            );
+        }
+    }
+
+    /// Returns whether the total size of the SST files reached the maximum
+    /// allowed space.
+    pub fn is_max_allowed_space_reached(&self) -> bool {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_is_max_allowed_space_reached(self.0.inner.as_ptr())
+                != 0
+        }
+    }
+
+    /// Returns whether the total size of the SST files, plus the space
+    /// reserved for the output of running compactions, reached the maximum
+    /// allowed space.
+    pub fn is_max_allowed_space_reached_including_compactions(&self) -> bool {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_is_max_space_reached_with_compactions(
+                self.0.inner.as_ptr(),
+            ) != 0
+        }
+    }
+
+    /// Returns the total size of the tracked files, in bytes.
+    pub fn get_total_size(&self) -> u64 {
+        unsafe { ffi::rust_rocksdb_sst_file_manager_get_total_size(self.0.inner.as_ptr()) }
+    }
+
+    /// Returns the tracked files with their sizes, in bytes.
+    pub fn get_tracked_files(&self) -> HashMap<String, u64> {
+        let mut files = HashMap::new();
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_get_tracked_files(
+                self.0.inner.as_ptr(),
+                (&mut files as *mut HashMap<String, u64>).cast::<c_void>(),
+                Some(tracked_file_callback),
+            );
+        }
+        files
+    }
+
+    /// Returns the rate at which obsolete files are deleted, in bytes per
+    /// second. 0 means that files are deleted immediately.
+    pub fn get_delete_rate_bytes_per_second(&self) -> i64 {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_get_delete_rate_bytes_per_second(
+                self.0.inner.as_ptr(),
+            )
+        }
+    }
+
+    /// Sets the rate at which obsolete files are deleted, in bytes per
+    /// second. 0 disables the rate limiting.
+    ///
+    /// Default: 0
+    pub fn set_delete_rate_bytes_per_second(&self, delete_rate: i64) {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_set_delete_rate_bytes_per_second(
+                self.0.inner.as_ptr(),
+                delete_rate,
+            );
+        }
+    }
+
+    /// Returns the ratio of trash to live data above which files are
+    /// deleted immediately, regardless of the delete rate.
+    pub fn get_max_trash_db_ratio(&self) -> f64 {
+        unsafe { ffi::rust_rocksdb_sst_file_manager_get_max_trash_db_ratio(self.0.inner.as_ptr()) }
+    }
+
+    /// Sets the ratio of trash to live data above which files are deleted
+    /// immediately, regardless of the delete rate.
+    ///
+    /// Default: 0.25
+    pub fn set_max_trash_db_ratio(&self, ratio: f64) {
+        unsafe {
+            ffi::rust_rocksdb_sst_file_manager_set_max_trash_db_ratio(self.0.inner.as_ptr(), ratio);
+        }
+    }
+
+    /// Returns the total size of the files waiting to be deleted, in bytes.
+    pub fn get_total_trash_size(&self) -> u64 {
+        unsafe { ffi::rust_rocksdb_sst_file_manager_get_total_trash_size(self.0.inner.as_ptr()) }
+    }
+}
+
+unsafe extern "C" fn tracked_file_callback(
+    state: *mut c_void,
+    path: *const c_char,
+    path_len: size_t,
+    size: u64,
+) {
+    unsafe {
+        let files = &mut *(state as *mut HashMap<String, u64>);
+        files.insert(lossy_string(path, path_len), size);
+    }
+}
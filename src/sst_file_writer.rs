@@ -13,7 +13,7 @@
 // limitations under the License.
 //`
 
-use crate::{ffi, ffi_util::to_cpath, Error, Options};
+use crate::{ffi, ffi_util::to_cpath, Error, Options, WideColumns};
 
 use libc::{self, c_char, size_t};
 use std::{ffi::CString, marker::PhantomData, path::Path};
@@ -121,6 +121,30 @@ impl<'a> SstFileWriter<'a> {
         }
     }
 
+    /// Adds a wide-column entity with the given key to currently opened file
+    /// REQUIRES: key is after any previously added key according to comparator.
+    pub fn put_entity<K: AsRef<[u8]>>(
+        &mut self,
+        key: K,
+        columns: &WideColumns,
+    ) -> Result<(), Error> {
+        let key = key.as_ref();
+        let raw = columns.as_raw_parts();
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_sstfilewriter_put_entity(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                raw.len(),
+                raw.names.as_ptr(),
+                raw.names_sizes.as_ptr(),
+                raw.values.as_ptr(),
+                raw.values_sizes.as_ptr(),
+            ));
+            Ok(())
+        }
+    }
+
     /// Adds a Put key with value to currently opened file
     /// REQUIRES: key is after any previously added key according to comparator.
     pub fn put_with_ts<K, V, S>(&mut self, key: K, ts: S, value: V) -> Result<(), Error>
@@ -0,0 +1,320 @@
+//! Properties of SST files, and custom properties collected while the files
+//! are written.
+//!
+//! Implement [`TablePropertiesCollectorFactory`] and register it with
+//! [`Options::add_table_properties_collector_factory`][add_factory] to store
+//! custom properties in every SST file, then read them back with
+//! [`DB::get_properties_of_all_tables`][all_tables].
+//!
+//! [add_factory]: crate::Options::add_table_properties_collector_factory
+//! [all_tables]: crate::DBCommon::get_properties_of_all_tables
+
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CStr;
+
+use libc::{c_char, c_int, c_uchar, c_void, size_t};
+
+use crate::{ffi, ffi_util::lossy_string};
+
+/// Custom properties of an SST file, by name.
+pub type UserCollectedProperties = BTreeMap<String, Vec<u8>>;
+
+/// The type of an entry added to an SST file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EntryType {
+    Put,
+    Delete,
+    SingleDelete,
+    Merge,
+    RangeDeletion,
+    BlobIndex,
+    DeleteWithTimestamp,
+    WideColumnEntity,
+    TimedPut,
+    Other,
+}
+
+impl EntryType {
+    fn from_raw(raw: c_int) -> Self {
+        match raw {
+            0 => Self::Put,
+            1 => Self::Delete,
+            2 => Self::SingleDelete,
+            3 => Self::Merge,
+            4 => Self::RangeDeletion,
+            5 => Self::BlobIndex,
+            6 => Self::DeleteWithTimestamp,
+            7 => Self::WideColumnEntity,
+            8 => Self::TimedPut,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Collects custom properties while one SST file is written.
+///
+/// A new collector is created by the factory for every file, and is called
+/// from the thread writing it.
+pub trait TablePropertiesCollector: Send + 'static {
+    /// Called for every entry added to the file, in order.
+    fn add_user_key(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        entry_type: EntryType,
+        seq: u64,
+        file_size: u64,
+    );
+
+    /// Called once all entries were added. Returns the properties to store
+    /// in the file.
+    fn finish(&mut self) -> UserCollectedProperties;
+
+    /// Returns whether the file should be compacted, checked after `finish`.
+    fn need_compact(&self) -> bool {
+        false
+    }
+}
+
+/// Context in which a [`TablePropertiesCollector`] is created.
+#[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
+pub struct TablePropertiesCollectorContext {
+    /// Id of the column family the file belongs to
+    pub column_family_id: u32,
+    /// Level of the file, or -1 when unknown
+    pub level_at_creation: i32,
+}
+
+/// Creates a [`TablePropertiesCollector`] for every SST file written.
+///
+/// The factory is shared by all the background threads writing files.
+pub trait TablePropertiesCollectorFactory: Send + Sync + 'static {
+    type Collector: TablePropertiesCollector;
+
+    /// Returns a collector for a new file.
+    fn create(&self, context: TablePropertiesCollectorContext) -> Self::Collector;
+
+    /// Returns a name that identifies this factory and its collectors.
+    fn name(&self) -> &CStr;
+}
+
+/// Properties of an SST file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TableProperties {
+    /// Total size of the data blocks
+    pub data_size: u64,
+    /// Size of the index block
+    pub index_size: u64,
+    /// Size of the filter block
+    pub filter_size: u64,
+    /// Total size of the keys
+    pub raw_key_size: u64,
+    /// Total size of the values
+    pub raw_value_size: u64,
+    /// Number of data blocks
+    pub num_data_blocks: u64,
+    /// Number of entries
+    pub num_entries: u64,
+    /// Number of deletions
+    pub num_deletions: u64,
+    /// Number of merge operands
+    pub num_merge_operands: u64,
+    /// Number of range deletions
+    pub num_range_deletions: u64,
+    /// Format version of the table
+    pub format_version: u64,
+    /// Time the oldest data in the file was written, in seconds since the
+    /// epoch, or 0 when unknown
+    pub creation_time: u64,
+    /// Time of the oldest key in the file, in seconds since the epoch, or 0
+    /// when unknown
+    pub oldest_key_time: u64,
+    /// Time the file was created, in seconds since the epoch, or 0 when
+    /// unknown
+    pub file_creation_time: u64,
+    /// Id of the column family the file belongs to
+    pub column_family_id: u64,
+    /// Name of the column family the file belongs to
+    pub column_family_name: String,
+    /// Name of the comparator used to order the keys
+    pub comparator_name: String,
+    /// Name of the merge operator, or "nullptr" when none is set
+    pub merge_operator_name: String,
+    /// Name of the filter policy, empty when none is set
+    pub filter_policy_name: String,
+    /// Name of the compression algorithm
+    pub compression_name: String,
+    /// Properties stored by collectors, including RocksDB's own
+    pub user_collected_properties: UserCollectedProperties,
+}
+
+impl TableProperties {
+    unsafe fn from_c(props: &ffi::rust_rocksdb_table_properties_t) -> Self {
+        unsafe {
+            let user_collected_properties = (0..props.num_user_collected_properties)
+                .map(|i| {
+                    let name = lossy_string(
+                        *props.user_collected_names.add(i),
+                        *props.user_collected_names_lens.add(i),
+                    );
+                    let value = slice_from_raw(
+                        *props.user_collected_values.add(i),
+                        *props.user_collected_values_lens.add(i),
+                    );
+                    (name, value.to_vec())
+                })
+                .collect();
+            Self {
+                data_size: props.data_size,
+                index_size: props.index_size,
+                filter_size: props.filter_size,
+                raw_key_size: props.raw_key_size,
+                raw_value_size: props.raw_value_size,
+                num_data_blocks: props.num_data_blocks,
+                num_entries: props.num_entries,
+                num_deletions: props.num_deletions,
+                num_merge_operands: props.num_merge_operands,
+                num_range_deletions: props.num_range_deletions,
+                format_version: props.format_version,
+                creation_time: props.creation_time,
+                oldest_key_time: props.oldest_key_time,
+                file_creation_time: props.file_creation_time,
+                column_family_id: props.column_family_id,
+                column_family_name: lossy_string(
+                    props.column_family_name,
+                    props.column_family_name_len,
+                ),
+                comparator_name: lossy_string(props.comparator_name, props.comparator_name_len),
+                merge_operator_name: lossy_string(
+                    props.merge_operator_name,
+                    props.merge_operator_name_len,
+                ),
+                filter_policy_name: lossy_string(
+                    props.filter_policy_name,
+                    props.filter_policy_name_len,
+                ),
+                compression_name: lossy_string(props.compression_name, props.compression_name_len),
+                user_collected_properties,
+            }
+        }
+    }
+}
+
+unsafe fn slice_from_raw<'a>(data: *const c_char, len: size_t) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(data as *const u8, len) }
+    }
+}
+
+pub(crate) unsafe extern "C" fn table_properties_callback(
+    state: *mut c_void,
+    file: *const c_char,
+    file_len: size_t,
+    props: *const ffi::rust_rocksdb_table_properties_t,
+) {
+    unsafe {
+        let tables = &mut *(state as *mut HashMap<String, TableProperties>);
+        tables.insert(
+            lossy_string(file, file_len),
+            TableProperties::from_c(&*props),
+        );
+    }
+}
+
+pub(crate) unsafe extern "C" fn destructor_callback<F: TablePropertiesCollectorFactory>(
+    raw_self: *mut c_void,
+) {
+    drop(unsafe { Box::from_raw(raw_self as *mut F) });
+}
+
+unsafe extern "C" fn name_callback<F: TablePropertiesCollectorFactory>(
+    raw_self: *mut c_void,
+) -> *const c_char {
+    let self_ = unsafe { &*(raw_self as *const F) };
+    self_.name().as_ptr()
+}
+
+unsafe extern "C" fn create_collector_callback<F: TablePropertiesCollectorFactory>(
+    raw_self: *mut c_void,
+    column_family_id: u32,
+    level_at_creation: c_int,
+) -> *mut c_void {
+    let self_ = unsafe { &*(raw_self as *const F) };
+    let collector = self_.create(TablePropertiesCollectorContext {
+        column_family_id,
+        level_at_creation,
+    });
+    Box::into_raw(Box::new(collector)).cast::<c_void>()
+}
+
+unsafe extern "C" fn add_user_key_callback<C: TablePropertiesCollector>(
+    raw_collector: *mut c_void,
+    key: *const c_char,
+    key_len: size_t,
+    value: *const c_char,
+    value_len: size_t,
+    entry_type: c_int,
+    seq: u64,
+    file_size: u64,
+) {
+    unsafe {
+        let collector = &mut *(raw_collector as *mut C);
+        collector.add_user_key(
+            slice_from_raw(key, key_len),
+            slice_from_raw(value, value_len),
+            EntryType::from_raw(entry_type),
+            seq,
+            file_size,
+        );
+    }
+}
+
+unsafe extern "C" fn finish_callback<C: TablePropertiesCollector>(
+    raw_collector: *mut c_void,
+    props: *mut c_void,
+    add: Option<unsafe extern "C" fn(*mut c_void, *const c_char, size_t, *const c_char, size_t)>,
+) {
+    unsafe {
+        let collector = &mut *(raw_collector as *mut C);
+        let add = add.expect("missing property callback");
+        for (name, value) in collector.finish() {
+            add(
+                props,
+                name.as_ptr() as *const c_char,
+                name.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+        }
+    }
+}
+
+unsafe extern "C" fn need_compact_callback<C: TablePropertiesCollector>(
+    raw_collector: *mut c_void,
+) -> c_uchar {
+    let collector = unsafe { &*(raw_collector as *const C) };
+    c_uchar::from(collector.need_compact())
+}
+
+unsafe extern "C" fn collector_destroy_callback<C: TablePropertiesCollector>(
+    raw_collector: *mut c_void,
+) {
+    drop(unsafe { Box::from_raw(raw_collector as *mut C) });
+}
+
+pub(crate) fn callbacks<F: TablePropertiesCollectorFactory>(
+) -> ffi::rust_rocksdb_table_properties_collector_callbacks_t {
+    ffi::rust_rocksdb_table_properties_collector_callbacks_t {
+        name: Some(name_callback::<F>),
+        create_collector: Some(create_collector_callback::<F>),
+        collector_add_user_key: Some(add_user_key_callback::<F::Collector>),
+        collector_finish: Some(finish_callback::<F::Collector>),
+        collector_need_compact: Some(need_compact_callback::<F::Collector>),
+        collector_destroy: Some(collector_destroy_callback::<F::Collector>),
+    }
+}
@@ -0,0 +1,142 @@
+use std::slice;
+
+use libc::{c_char, c_uchar, c_void, size_t};
+
+use crate::{ffi, ffi_util::raw_data};
+
+/// A key locked by transactions of a [`TransactionDB`](crate::TransactionDB),
+/// see [`TransactionDB::lock_status`](crate::TransactionDB::lock_status).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyLockInfo {
+    /// Id of the column family of the key
+    pub column_family_id: u32,
+    pub key: Vec<u8>,
+    /// Ids of the transactions holding the lock, several ones for a shared
+    /// lock
+    pub txn_ids: Vec<u64>,
+    /// Whether the lock is exclusive, taken by a write or an exclusive
+    /// `get_for_update`
+    pub exclusive: bool,
+}
+
+/// The lock a transaction waits on, see
+/// [`TransactionWaitHandle::waiting_txns`](crate::TransactionWaitHandle::waiting_txns).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitingTxns {
+    /// Id of the column family of the key
+    pub column_family_id: u32,
+    pub key: Vec<u8>,
+    /// Ids of the transactions holding the lock
+    pub txn_ids: Vec<u64>,
+}
+
+/// A transaction of a deadlock cycle, waiting for a lock held by the next
+/// one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadlockInfo {
+    pub txn_id: u64,
+    /// Id of the column family of the key waited on
+    pub column_family_id: u32,
+    /// Whether the lock waited on is exclusive
+    pub exclusive: bool,
+    pub waiting_key: Vec<u8>,
+}
+
+/// A deadlock detected by a [`TransactionDB`](crate::TransactionDB), see
+/// [`TransactionDB::deadlock_info`](crate::TransactionDB::deadlock_info).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadlockPath {
+    /// The transactions of the cycle, each waiting for a lock held by the
+    /// next one: it starts with the transaction holding the lock requested
+    /// by the one that detected the deadlock, which comes last
+    pub path: Vec<DeadlockInfo>,
+    /// Whether the detection gave up at the depth set with
+    /// [`TransactionOptions::set_deadlock_detect_depth`], in which case
+    /// `path` is incomplete
+    ///
+    /// [`TransactionOptions::set_deadlock_detect_depth`]: crate::TransactionOptions::set_deadlock_detect_depth
+    pub limit_exceeded: bool,
+    /// Time the deadlock was detected, in seconds since the epoch
+    pub deadlock_time: i64,
+}
+
+unsafe fn ids_from_raw(txn_ids: *const u64, num_txn_ids: size_t) -> Vec<u64> {
+    if num_txn_ids == 0 {
+        Vec::new()
+    } else {
+        unsafe { slice::from_raw_parts(txn_ids, num_txn_ids).to_vec() }
+    }
+}
+
+pub(crate) unsafe extern "C" fn lock_callback(
+    state: *mut c_void,
+    column_family_id: u32,
+    key: *const c_char,
+    key_len: size_t,
+    txn_ids: *const u64,
+    num_txn_ids: size_t,
+    exclusive: c_uchar,
+) {
+    unsafe {
+        let locks = &mut *(state as *mut Vec<KeyLockInfo>);
+        locks.push(KeyLockInfo {
+            column_family_id,
+            key: raw_data(key, key_len).unwrap_or_default(),
+            txn_ids: ids_from_raw(txn_ids, num_txn_ids),
+            exclusive: exclusive != 0,
+        });
+    }
+}
+
+pub(crate) unsafe extern "C" fn waiting_callback(
+    state: *mut c_void,
+    column_family_id: u32,
+    key: *const c_char,
+    key_len: size_t,
+    txn_ids: *const u64,
+    num_txn_ids: size_t,
+) {
+    if num_txn_ids == 0 {
+        // not waiting
+        return;
+    }
+    unsafe {
+        let waiting = &mut *(state as *mut Option<WaitingTxns>);
+        *waiting = Some(WaitingTxns {
+            column_family_id,
+            key: raw_data(key, key_len).unwrap_or_default(),
+            txn_ids: ids_from_raw(txn_ids, num_txn_ids),
+        });
+    }
+}
+
+pub(crate) unsafe extern "C" fn deadlock_path_callback(
+    state: *mut c_void,
+    infos: *const ffi::rust_rocksdb_deadlock_info_t,
+    num_infos: size_t,
+    limit_exceeded: c_uchar,
+    deadlock_time: i64,
+) {
+    unsafe {
+        let paths = &mut *(state as *mut Vec<DeadlockPath>);
+        let infos = if num_infos == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(infos, num_infos)
+        };
+        paths.push(DeadlockPath {
+            path: infos
+                .iter()
+                .map(|info| DeadlockInfo {
+                    txn_id: info.txn_id,
+                    column_family_id: info.column_family_id,
+                    exclusive: info.exclusive != 0,
+                    waiting_key: raw_data(info.waiting_key, info.waiting_key_len)
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            limit_exceeded: limit_exceeded != 0,
+            deadlock_time,
+        });
+    }
+}
@@ -13,12 +13,16 @@
 // limitations under the License.
 //
 
+mod lock_info;
 mod optimistic_transaction_db;
 mod options;
 mod transaction;
 mod transaction_db;
 
+pub use lock_info::{DeadlockInfo, DeadlockPath, KeyLockInfo, WaitingTxns};
 pub use optimistic_transaction_db::OptimisticTransactionDB;
-pub use options::{OptimisticTransactionOptions, TransactionDBOptions, TransactionOptions};
-pub use transaction::Transaction;
+pub use options::{
+    OptimisticTransactionOptions, TransactionDBOptions, TransactionOptions, TxnDBWritePolicy,
+};
+pub use transaction::{Transaction, TransactionWaitHandle};
 pub use transaction_db::TransactionDB;
@@ -21,7 +21,7 @@ use crate::column_family::ColumnFamilyTtl;
 use crate::{
     db::{DBCommon, DBInner},
     ffi,
-    ffi_util::to_cpath,
+    ffi_util::{check_linked_version, to_cpath},
     write_batch::WriteBatchWithTransaction,
     AsColumnFamilyRef, ColumnFamilyDescriptor, Error, OptimisticTransactionOptions, Options,
     ThreadMode, Transaction, WriteOptions, DEFAULT_COLUMN_FAMILY_NAME,
@@ -142,6 +142,7 @@ impl<T: ThreadMode> OptimisticTransactionDB<T> {
             .collect();
 
         let cpath = to_cpath(&path)?;
+        check_linked_version()?;
 
         if let Err(e) = fs::create_dir_all(&path) {
             return Err(Error::new(format!(
@@ -14,6 +14,7 @@
 //
 
 use crate::ffi;
+use libc::c_int;
 
 pub struct TransactionOptions {
     pub(crate) inner: *mut ffi::rocksdb_transaction_options_t,
@@ -126,6 +127,21 @@ impl TransactionOptions {
             ffi::rocksdb_transaction_options_set_max_write_batch_size(self.inner, size);
         }
     }
+
+    /// Specifies the size in bytes above which the writes buffered by a
+    /// transaction are written to the database before commit, with the
+    /// [`TxnDBWritePolicy::WriteUnprepared`] policy. 0 means no limit, and a
+    /// negative value uses
+    /// [`TransactionDBOptions::set_default_write_batch_flush_threshold`].
+    ///
+    /// Default: -1.
+    pub fn set_write_batch_flush_threshold(&mut self, threshold: i64) {
+        unsafe {
+            ffi::rust_rocksdb_transaction_options_set_write_batch_flush_threshold(
+                self.inner, threshold,
+            );
+        }
+    }
 }
 
 impl Drop for TransactionOptions {
@@ -136,6 +152,28 @@ impl Drop for TransactionOptions {
     }
 }
 
+/// When the writes of the transactions of a
+/// [`TransactionDB`](crate::TransactionDB) are written to the database,
+/// see [`TransactionDBOptions::set_write_policy`].
+///
+/// Whatever the policy, the writes of a transaction are only visible to
+/// other transactions and readers once it commits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TxnDBWritePolicy {
+    /// Writes are buffered in memory until commit, then written at once.
+    WriteCommitted = 0,
+    /// Writes are buffered in memory until prepare, then written, shortening
+    /// the commit of two-phase transactions.
+    WritePrepared = 1,
+    /// Writes are buffered in memory until they exceed the threshold set
+    /// with [`TransactionOptions::set_write_batch_flush_threshold`] or
+    /// [`TransactionDBOptions::set_default_write_batch_flush_threshold`],
+    /// then written as the transaction goes, so that large transactions do
+    /// not buffer everything in memory. Without a threshold, this behaves
+    /// like [`WritePrepared`](Self::WritePrepared).
+    WriteUnprepared = 2,
+}
+
 pub struct TransactionDBOptions {
     pub(crate) inner: *mut ffi::rocksdb_transactiondb_options_t,
 }
@@ -214,6 +252,35 @@ impl TransactionDBOptions {
         }
     }
 
+    /// Sets when the writes of transactions are written to the database.
+    ///
+    /// The policy is persisted in the write-ahead log: a database must be
+    /// reopened with the policy it was written with, unless all its
+    /// transactions were committed or rolled back and the log is empty.
+    ///
+    /// Default: [`TxnDBWritePolicy::WriteCommitted`]
+    pub fn set_write_policy(&mut self, policy: TxnDBWritePolicy) {
+        unsafe {
+            ffi::rust_rocksdb_transactiondb_options_set_write_policy(self.inner, policy as c_int);
+        }
+    }
+
+    /// Specifies the size in bytes above which the writes buffered by a
+    /// transaction are written to the database before commit, with the
+    /// [`TxnDBWritePolicy::WriteUnprepared`] policy, unless the transaction
+    /// sets its own with
+    /// [`TransactionOptions::set_write_batch_flush_threshold`]. 0 means no
+    /// limit.
+    ///
+    /// Default: 0.
+    pub fn set_default_write_batch_flush_threshold(&mut self, threshold: i64) {
+        unsafe {
+            ffi::rust_rocksdb_transactiondb_options_set_default_write_batch_flush_threshold(
+                self.inner, threshold,
+            );
+        }
+    }
+
     /// Specifies lock table stripes count.
     ///
     /// Increasing this value will increase the concurrency by dividing the lock
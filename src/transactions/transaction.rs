@@ -13,12 +13,15 @@
 // limitations under the License.
 //
 
-use std::{marker::PhantomData, ptr};
+use std::{marker::PhantomData, ptr, time::Duration};
 
 use crate::{
     db::{convert_values, DBAccess},
-    ffi, AsColumnFamilyRef, DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode,
-    Direction, Error, IteratorMode, ReadOptions, SnapshotWithThreadMode, WriteBatchWithTransaction,
+    ffi,
+    transactions::lock_info::waiting_callback,
+    AsColumnFamilyRef, DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode,
+    Direction, Error, IteratorMode, ReadOptions, SnapshotWithThreadMode, WaitingTxns,
+    WriteBatchWithTransaction,
 };
 use libc::{c_char, c_void, size_t};
 
@@ -172,6 +175,28 @@ impl<DB> Transaction<'_, DB> {
         }
     }
 
+    /// Returns the id of the transaction, unique within its database, as
+    /// reported by [`TransactionDB::lock_status`].
+    ///
+    /// [`TransactionDB::lock_status`]: crate::TransactionDB::lock_status
+    pub fn id(&self) -> u64 {
+        unsafe { ffi::rust_rocksdb_transaction_get_id(self.inner) }
+    }
+
+    /// Returns a handle telling which lock this transaction waits on, from
+    /// other threads than the one blocked in the wait.
+    pub fn wait_handle(&self) -> TransactionWaitHandle<'_> {
+        TransactionWaitHandle {
+            inner: self.inner,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the time elapsed since the transaction began.
+    pub fn elapsed_time(&self) -> Duration {
+        Duration::from_millis(unsafe { ffi::rust_rocksdb_transaction_get_elapsed_time(self.inner) })
+    }
+
     pub fn prepare(&self) -> Result<(), Error> {
         unsafe {
             ffi_try!(ffi::rocksdb_transaction_prepare(self.inner));
@@ -179,6 +204,46 @@ impl<DB> Transaction<'_, DB> {
         Ok(())
     }
 
+    /// Sets the timestamp the keys written by this transaction get when it
+    /// commits, required to commit writes to column families with
+    /// user-defined timestamps. Transaction timestamps are 64-bit integers,
+    /// stored in keys as by `u64::to_le_bytes`, which is also the form the
+    /// `ts` of [`put_cf_with_ts`](Self::put_cf_with_ts) and
+    /// [`ReadOptions::set_timestamp`] take.
+    ///
+    /// Only supported by a [`TransactionDB`](crate::TransactionDB) using the
+    /// [`TxnDBWritePolicy::WriteCommitted`](crate::TxnDBWritePolicy::WriteCommitted)
+    /// policy.
+    pub fn set_commit_timestamp(&self, ts: u64) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_transaction_set_commit_timestamp(
+                self.inner, ts
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the commit timestamp, if set.
+    pub fn get_commit_timestamp(&self) -> Option<u64> {
+        let ts = unsafe { ffi::rust_rocksdb_transaction_get_commit_timestamp(self.inner) };
+        (ts != u64::MAX).then_some(ts)
+    }
+
+    /// Sets the timestamp used to validate the keys read with
+    /// [`get_for_update`](Self::get_for_update) and written by this
+    /// transaction: they conflict if they were written with a newer
+    /// timestamp. The reads themselves use the timestamp set with
+    /// [`ReadOptions::set_timestamp`], which must be equal to this one when
+    /// both are set.
+    pub fn set_read_timestamp_for_validation(&self, ts: u64) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(
+                ffi::rust_rocksdb_transaction_set_read_timestamp_for_validation(self.inner, ts)
+            );
+        }
+        Ok(())
+    }
+
     /// Returns snapshot associated with transaction if snapshot was enabled in [`TransactionOptions`].
     /// Otherwise, returns a snapshot with `nullptr` inside which doesn't affect read operations.
     ///
@@ -343,6 +408,9 @@ impl<DB> Transaction<'_, DB> {
     /// Currently, this function will return an error of the [`MergeInProgress`] kind
     /// if the most recent write to the queried key in this batch is a Merge.
     ///
+    /// In a column family with user-defined timestamps, the timestamp to read
+    /// at must be set with [`ReadOptions::set_timestamp`].
+    ///
     /// [`MergeInProgress`]: crate::ErrorKind::MergeInProgress
     pub fn get_cf_opt<K: AsRef<[u8]>>(
         &self,
@@ -438,6 +506,11 @@ impl<DB> Transaction<'_, DB> {
     /// can cause [`commit`] to fail. Otherwise, it could return any error that could
     /// be returned by `[DB::get]`.
     ///
+    /// In a column family with user-defined timestamps, the key is read at
+    /// the timestamp set with [`set_read_timestamp_for_validation`], which
+    /// must be set. A timestamp set with [`ReadOptions::set_timestamp`] must
+    /// be equal to it.
+    ///
     /// [`Busy`]: crate::ErrorKind::Busy
     /// [`TimedOut`]: crate::ErrorKind::TimedOut
     /// [`TryAgain`]: crate::ErrorKind::TryAgain
@@ -446,6 +519,7 @@ impl<DB> Transaction<'_, DB> {
     /// [`OptimisticTransactionDB`]: crate::OptimisticTransactionDB
     /// [`commit`]: Self::commit
     /// [`DB::get`]: crate::DB::get
+    /// [`set_read_timestamp_for_validation`]: Self::set_read_timestamp_for_validation
     pub fn get_for_update_cf_opt<K: AsRef<[u8]>>(
         &self,
         cf: &impl AsColumnFamilyRef,
@@ -645,6 +719,70 @@ impl<DB> Transaction<'_, DB> {
         }
     }
 
+    /// Put the key value in the default column family with a user-defined
+    /// timestamp, and do conflict checking on the key.
+    ///
+    /// See [`put_cf_with_ts`] for details.
+    ///
+    /// [`put_cf_with_ts`]: Self::put_cf_with_ts
+    pub fn put_with_ts<K, V, S>(&self, key: K, ts: S, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        self.put_cf_with_ts_impl(ptr::null_mut(), key.as_ref(), ts.as_ref(), value.as_ref())
+    }
+
+    /// Put the key value in the given column family with a user-defined
+    /// timestamp, and do conflict checking on the key.
+    ///
+    /// `ts` is a transaction timestamp encoded as by `u64::to_le_bytes`.
+    /// All the keys of a transaction are written with its commit timestamp,
+    /// which this sets to `ts` once the key is put: an error of kind
+    /// [`InvalidArgument`] is returned if it was already set to another
+    /// timestamp or if `ts` is not 8 bytes long. Other errors are the same
+    /// as for [`put_cf`], and leave the commit timestamp unchanged.
+    ///
+    /// [`InvalidArgument`]: crate::ErrorKind::InvalidArgument
+    /// [`put_cf`]: Self::put_cf
+    pub fn put_cf_with_ts<K, V, S>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        ts: S,
+        value: V,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        self.put_cf_with_ts_impl(cf.inner(), key.as_ref(), ts.as_ref(), value.as_ref())
+    }
+
+    fn put_cf_with_ts_impl(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        ts: &[u8],
+        value: &[u8],
+    ) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_transaction_put_cf_with_ts(
+                self.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                ts.as_ptr() as *const c_char,
+                ts.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            ));
+        }
+        Ok(())
+    }
+
     /// Merge value with existing value of key, and also do conflict checking on the key.
     ///
     /// See [`merge_cf`] for details.
@@ -749,6 +887,43 @@ impl<DB> Transaction<'_, DB> {
         Ok(())
     }
 
+    /// Delete the key value, assuming it was written exactly once, and do
+    /// conflict checking. See
+    /// [`DBCommon::single_delete_opt`](crate::DBCommon::single_delete_opt).
+    ///
+    /// Returns the same errors as [`delete`](Self::delete).
+    pub fn single_delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
+        self.single_delete_raw(ptr::null_mut(), key.as_ref())
+    }
+
+    /// Delete the key value in the given column family, assuming it was
+    /// written exactly once, and do conflict checking.
+    ///
+    /// Returns the same errors as [`delete_cf`](Self::delete_cf).
+    pub fn single_delete_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), Error> {
+        self.single_delete_raw(cf.inner(), key.as_ref())
+    }
+
+    fn single_delete_raw(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+    ) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_transaction_singledelete_cf(
+                self.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t
+            ));
+        }
+        Ok(())
+    }
+
     pub fn iterator<'a: 'b, 'b>(
         &'a self,
         mode: IteratorMode,
@@ -892,6 +1067,38 @@ impl<DB> Transaction<'_, DB> {
     }
 }
 
+/// Tells which lock a [`Transaction`] waits on, see
+/// [`Transaction::wait_handle`].
+///
+/// A transaction can't be shared between threads, and the thread owning it is
+/// blocked while it waits for a lock, but its handle can be sent to another
+/// thread.
+#[derive(Clone, Copy)]
+pub struct TransactionWaitHandle<'txn> {
+    inner: *mut ffi::rocksdb_transaction_t,
+    _marker: PhantomData<&'txn ()>,
+}
+
+// Reading the lock waited on is synchronized by RocksDB.
+unsafe impl Send for TransactionWaitHandle<'_> {}
+unsafe impl Sync for TransactionWaitHandle<'_> {}
+
+impl TransactionWaitHandle<'_> {
+    /// Returns the lock the transaction waits on and the ids of the
+    /// transactions holding it, or `None` when it is not waiting.
+    pub fn waiting_txns(&self) -> Option<WaitingTxns> {
+        let mut waiting: Option<WaitingTxns> = None;
+        unsafe {
+            ffi::rust_rocksdb_transaction_get_waiting_txns(
+                self.inner,
+                (&mut waiting as *mut Option<WaitingTxns>).cast::<c_void>(),
+                Some(waiting_callback),
+            );
+        }
+        waiting
+    }
+}
+
 impl<DB> Drop for Transaction<'_, DB> {
     fn drop(&mut self) {
         unsafe {
@@ -32,12 +32,13 @@ use crate::{
     db::{convert_values, DBAccess},
     db_options::OptionsMustOutliveDB,
     ffi,
-    ffi_util::to_cpath,
+    ffi_util::{check_linked_version, raw_data, to_cpath},
+    transactions::lock_info::{deadlock_path_callback, lock_callback},
     AsColumnFamilyRef, BoundColumnFamily, ColumnFamily, ColumnFamilyDescriptor,
-    DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode, Direction, Error,
-    IteratorMode, MultiThreaded, Options, ReadOptions, SingleThreaded, SnapshotWithThreadMode,
-    ThreadMode, Transaction, TransactionDBOptions, TransactionOptions, WriteBatchWithTransaction,
-    WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
+    DBIteratorWithThreadMode, DBPinnableSlice, DBRawIteratorWithThreadMode, DeadlockPath,
+    Direction, Error, IteratorMode, KeyLockInfo, MultiThreaded, Options, ReadOptions,
+    SingleThreaded, SnapshotWithThreadMode, ThreadMode, Transaction, TransactionDBOptions,
+    TransactionOptions, WriteBatchWithTransaction, WriteOptions, DB, DEFAULT_COLUMN_FAMILY_NAME,
 };
 use ffi::rocksdb_transaction_t;
 use libc::{c_char, c_int, c_void, size_t};
@@ -239,6 +240,7 @@ impl<T: ThreadMode> TransactionDB<T> {
             .collect();
 
         let cpath = to_cpath(&path)?;
+        check_linked_version()?;
 
         if let Err(e) = fs::create_dir_all(&path) {
             return Err(Error::new(format!(
@@ -437,6 +439,45 @@ impl<T: ThreadMode> TransactionDB<T> {
             .collect()
     }
 
+    /// Returns the keys currently locked by transactions, with the
+    /// transactions holding them.
+    pub fn lock_status(&self) -> Vec<KeyLockInfo> {
+        let mut locks = Vec::new();
+        unsafe {
+            ffi::rust_rocksdb_transactiondb_get_lock_status(
+                self.inner,
+                (&mut locks as *mut Vec<KeyLockInfo>).cast::<c_void>(),
+                Some(lock_callback),
+            );
+        }
+        locks
+    }
+
+    /// Returns the most recent deadlocks detected, newest first. Deadlocks
+    /// are only detected by transactions with
+    /// [`TransactionOptions::set_deadlock_detect`] enabled.
+    pub fn deadlock_info(&self) -> Vec<DeadlockPath> {
+        let mut paths = Vec::new();
+        unsafe {
+            ffi::rust_rocksdb_transactiondb_get_deadlock_info(
+                self.inner,
+                (&mut paths as *mut Vec<DeadlockPath>).cast::<c_void>(),
+                Some(deadlock_path_callback),
+            );
+        }
+        paths
+    }
+
+    /// Sets the number of recent deadlocks kept for
+    /// [`deadlock_info`](Self::deadlock_info), discarding the older ones.
+    ///
+    /// Default: 5
+    pub fn set_deadlock_info_buffer_size(&self, size: u32) {
+        unsafe {
+            ffi::rust_rocksdb_transactiondb_set_deadlock_info_buffer_size(self.inner, size);
+        }
+    }
+
     /// Returns the bytes associated with a key value.
     pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, Error> {
         self.get_pinned(key).map(|x| x.map(|v| v.as_ref().to_vec()))
@@ -473,6 +514,64 @@ impl<T: ThreadMode> TransactionDB<T> {
             .map(|x| x.map(|v| v.as_ref().to_vec()))
     }
 
+    /// Returns the value associated with a key in the default column family,
+    /// with user-defined timestamps, and the timestamp of the version read.
+    ///
+    /// The timestamp to read at must be set with
+    /// [`ReadOptions::set_timestamp`].
+    pub fn get_with_ts<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        self.get_cf_with_ts_impl(ptr::null_mut(), key.as_ref(), readopts)
+    }
+
+    /// Returns the value associated with a key in a column family with
+    /// user-defined timestamps, and the timestamp of the version read.
+    ///
+    /// The timestamp to read at must be set with
+    /// [`ReadOptions::set_timestamp`].
+    pub fn get_cf_with_ts<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        self.get_cf_with_ts_impl(cf.inner(), key.as_ref(), readopts)
+    }
+
+    fn get_cf_with_ts_impl(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        readopts: &ReadOptions,
+    ) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        let mut val_len: size_t = 0;
+        let mut ts: *mut c_char = ptr::null_mut();
+        let mut ts_len: size_t = 0;
+        unsafe {
+            let val = ffi_try!(ffi::rust_rocksdb_transactiondb_get_cf_with_ts(
+                self.inner,
+                readopts.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                &mut val_len,
+                &mut ts,
+                &mut ts_len,
+            ));
+            if val.is_null() {
+                return Ok(None);
+            }
+            let value = raw_data(val, val_len).unwrap_or_default();
+            ffi::rocksdb_free(val as *mut c_void);
+            let timestamp = raw_data(ts, ts_len).unwrap_or_default();
+            ffi::rocksdb_free(ts as *mut c_void);
+            Ok(Some((value, timestamp)))
+        }
+    }
+
     pub fn get_pinned<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<DBPinnableSlice<'_>>, Error> {
         self.get_pinned_opt(key, &ReadOptions::default())
     }
@@ -702,6 +801,75 @@ impl<T: ThreadMode> TransactionDB<T> {
         Ok(())
     }
 
+    /// Puts the key value in the default column family with user-defined
+    /// timestamps, writing it with timestamp `ts` in a transaction of its own.
+    ///
+    /// See [`Transaction::put_cf_with_ts`].
+    pub fn put_with_ts<K, V, S>(&self, key: K, ts: S, value: V) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        self.put_with_ts_opt(key, ts, value, &WriteOptions::default())
+    }
+
+    /// Puts the key value in a column family with user-defined timestamps,
+    /// writing it with timestamp `ts` in a transaction of its own.
+    ///
+    /// See [`Transaction::put_cf_with_ts`].
+    pub fn put_cf_with_ts<K, V, S>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        ts: S,
+        value: V,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        self.put_cf_with_ts_opt(cf, key, ts, value, &WriteOptions::default())
+    }
+
+    /// Like [`put_with_ts`](Self::put_with_ts), with write options.
+    pub fn put_with_ts_opt<K, V, S>(
+        &self,
+        key: K,
+        ts: S,
+        value: V,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        let txn = self.transaction_opt(writeopts, &TransactionOptions::default());
+        txn.put_with_ts(key, ts, value)?;
+        txn.commit()
+    }
+
+    /// Like [`put_cf_with_ts`](Self::put_cf_with_ts), with write options.
+    pub fn put_cf_with_ts_opt<K, V, S>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        ts: S,
+        value: V,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error>
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+        S: AsRef<[u8]>,
+    {
+        let txn = self.transaction_opt(writeopts, &TransactionOptions::default());
+        txn.put_cf_with_ts(cf, key, ts, value)?;
+        txn.commit()
+    }
+
     pub fn write(&self, batch: WriteBatchWithTransaction<true>) -> Result<(), Error> {
         self.write_opt(batch, &WriteOptions::default())
     }
@@ -832,6 +1000,69 @@ impl<T: ThreadMode> TransactionDB<T> {
         Ok(())
     }
 
+    /// Remove the database entry for "key", assuming it was written exactly
+    /// once. See [`single_delete_cf_opt`](Self::single_delete_cf_opt).
+    pub fn single_delete<K: AsRef<[u8]>>(&self, key: K) -> Result<(), Error> {
+        self.single_delete_opt(key, &WriteOptions::default())
+    }
+
+    /// Remove the database entry for "key" from the given column family,
+    /// assuming it was written exactly once. See
+    /// [`single_delete_cf_opt`](Self::single_delete_cf_opt).
+    pub fn single_delete_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+    ) -> Result<(), Error> {
+        self.single_delete_cf_opt(cf, key, &WriteOptions::default())
+    }
+
+    /// Remove the database entry for "key" with WriteOptions, assuming it
+    /// was written exactly once. See
+    /// [`single_delete_cf_opt`](Self::single_delete_cf_opt).
+    pub fn single_delete_opt<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        self.single_delete_raw(ptr::null_mut(), key.as_ref(), writeopts)
+    }
+
+    /// Remove the database entry for "key" from the given column family with
+    /// WriteOptions, assuming it was written exactly once.
+    ///
+    /// The key must have been put at most once since it was last deleted,
+    /// and never merged into: unlike [`delete_cf_opt`](Self::delete_cf_opt),
+    /// the tombstone is dropped as soon as it meets a value during
+    /// compaction, so the result is undefined if the key was overwritten or
+    /// mixed with [`merge`](Self::merge).
+    pub fn single_delete_cf_opt<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        self.single_delete_raw(cf.inner(), key.as_ref(), writeopts)
+    }
+
+    fn single_delete_raw(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_transactiondb_singledelete_cf(
+                self.inner,
+                writeopts.inner,
+                cf,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            ));
+        }
+        Ok(())
+    }
+
     pub fn iterator<'a: 'b, 'b>(
         &'a self,
         mode: IteratorMode,
@@ -0,0 +1,185 @@
+use libc::{c_char, size_t};
+use std::slice;
+
+/// The name of the anonymous default column of a wide-column entity.
+///
+/// Plain key-values written with `put` are read back as an entity with a
+/// single column of this name, and the value of this column (if any) is
+/// returned when an entity is read with `get`.
+pub const DEFAULT_WIDE_COLUMN_NAME: &[u8] = b"";
+
+/// An owned set of named columns belonging to a wide-column entity.
+///
+/// Columns are kept in insertion order when writing; RocksDB sorts them by
+/// name when the entity is stored, so entities read back from the database
+/// are always ordered by column name.
+///
+/// ```
+/// use rocksdb::{DB, Options, WideColumns};
+///
+/// let tempdir = tempfile::Builder::new()
+///     .prefix("_path_for_rocksdb_storage_wide_columns")
+///     .tempdir()
+///     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_wide_columns");
+/// let path = tempdir.path();
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     let columns: WideColumns = [("name", "alice"), ("role", "admin")].into_iter().collect();
+///     db.put_entity(b"user1", &columns).unwrap();
+///
+///     let read = db.get_entity(b"user1").unwrap().unwrap();
+///     assert_eq!(read.get(b"role"), Some(&b"admin"[..]));
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WideColumns {
+    columns: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl WideColumns {
+    /// Creates an entity without any column.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a column to the entity.
+    pub fn push<N: AsRef<[u8]>, V: AsRef<[u8]>>(&mut self, name: N, value: V) {
+        self.columns
+            .push((name.as_ref().to_vec(), value.as_ref().to_vec()));
+    }
+
+    /// Returns the value of the first column called `name`, if any.
+    pub fn get<N: AsRef<[u8]>>(&self, name: N) -> Option<&[u8]> {
+        let name = name.as_ref();
+        self.columns
+            .iter()
+            .find(|(n, _)| n.as_slice() == name)
+            .map(|(_, v)| v.as_slice())
+    }
+
+    /// Returns the value of the default (anonymous) column, if any.
+    pub fn default_value(&self) -> Option<&[u8]> {
+        self.get(DEFAULT_WIDE_COLUMN_NAME)
+    }
+
+    /// Iterates over `(name, value)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.columns
+            .iter()
+            .map(|(n, v)| (n.as_slice(), v.as_slice()))
+    }
+
+    /// Returns the number of columns, including the default one.
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Returns `true` if the entity has no column.
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// Builds a `WideColumns` by copying `count` columns out of a RocksDB
+    /// owned column set through the given accessors.
+    ///
+    /// # Safety
+    /// The accessors must return valid pointers for every index below `count`.
+    pub(crate) unsafe fn from_raw(
+        count: usize,
+        name: impl Fn(usize, &mut size_t) -> *const c_char,
+        value: impl Fn(usize, &mut size_t) -> *const c_char,
+    ) -> Self {
+        let columns = (0..count)
+            .map(|i| {
+                let mut name_len: size_t = 0;
+                let name_ptr = name(i, &mut name_len);
+                let mut value_len: size_t = 0;
+                let value_ptr = value(i, &mut value_len);
+                (
+                    raw_to_vec(name_ptr, name_len),
+                    raw_to_vec(value_ptr, value_len),
+                )
+            })
+            .collect();
+        Self { columns }
+    }
+
+    /// Returns the column names and values as parallel pointer and length
+    /// arrays, the layout expected by the C API. The arrays borrow from `self`.
+    pub(crate) fn as_raw_parts(&self) -> RawWideColumns {
+        let mut raw = RawWideColumns {
+            names: Vec::with_capacity(self.len()),
+            names_sizes: Vec::with_capacity(self.len()),
+            values: Vec::with_capacity(self.len()),
+            values_sizes: Vec::with_capacity(self.len()),
+        };
+        for (name, value) in &self.columns {
+            raw.names.push(name.as_ptr() as *const c_char);
+            raw.names_sizes.push(name.len() as size_t);
+            raw.values.push(value.as_ptr() as *const c_char);
+            raw.values_sizes.push(value.len() as size_t);
+        }
+        raw
+    }
+}
+
+unsafe fn raw_to_vec(ptr: *const c_char, len: size_t) -> Vec<u8> {
+    if len == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(ptr as *const u8, len).to_vec()
+    }
+}
+
+pub(crate) struct RawWideColumns {
+    pub names: Vec<*const c_char>,
+    pub names_sizes: Vec<size_t>,
+    pub values: Vec<*const c_char>,
+    pub values_sizes: Vec<size_t>,
+}
+
+impl RawWideColumns {
+    pub fn len(&self) -> size_t {
+        self.names.len() as size_t
+    }
+}
+
+impl<N: AsRef<[u8]>, V: AsRef<[u8]>> FromIterator<(N, V)> for WideColumns {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        let mut columns = Self::new();
+        for (name, value) in iter {
+            columns.push(name, value);
+        }
+        columns
+    }
+}
+
+impl IntoIterator for WideColumns {
+    type Item = (Vec<u8>, Vec<u8>);
+    type IntoIter = std::vec::IntoIter<(Vec<u8>, Vec<u8>)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.columns.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WideColumns;
+
+    #[test]
+    fn wide_columns_lookup() {
+        let mut columns: WideColumns = [("a", "1"), ("b", "2")].into_iter().collect();
+        columns.push("", "default");
+        assert_eq!(columns.len(), 3);
+        assert_eq!(columns.get("b"), Some(&b"2"[..]));
+        assert_eq!(columns.get("c"), None);
+        assert_eq!(columns.default_value(), Some(&b"default"[..]));
+
+        let raw = columns.as_raw_parts();
+        assert_eq!(raw.len(), 3);
+        assert_eq!(raw.names_sizes, vec![1, 1, 0]);
+        assert_eq!(raw.values_sizes, vec![1, 1, 7]);
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{ffi, AsColumnFamilyRef};
+use crate::{ffi, ffi_util::raw_data, AsColumnFamilyRef, Error, WideColumns, WriteOptions};
 use libc::{c_char, c_void, size_t};
 use std::slice;
 
@@ -53,6 +53,32 @@ pub struct WriteBatchWithTransaction<const TRANSACTION: bool> {
     pub(crate) inner: *mut ffi::rocksdb_writebatch_t,
 }
 
+/// A batch of write operations that [`DBCommon::write`] applies atomically:
+/// a [`WriteBatch`] or a [`WriteBatchWithIndex`].
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+///
+/// [`DBCommon::write`]: crate::DBCommon::write
+/// [`WriteBatchWithIndex`]: crate::WriteBatchWithIndex
+pub trait WriteBatchLike: sealed::WriteBatchLike {}
+
+pub(crate) mod sealed {
+    use crate::{ffi, Error, WriteOptions};
+
+    pub trait WriteBatchLike {
+        /// Writes the batch to `db`.
+        ///
+        /// # Safety
+        ///
+        /// `db` must point to an open database.
+        unsafe fn write_to_db(
+            &self,
+            db: *mut ffi::rocksdb_t,
+            writeopts: &WriteOptions,
+        ) -> Result<(), Error>;
+    }
+}
+
 /// Receives the puts and deletes of a write batch.
 ///
 /// The application must provide an implementation of this trait when
@@ -62,6 +88,12 @@ pub trait WriteBatchIterator {
     fn put(&mut self, key: &[u8], value: &[u8]);
     /// Called with a key that was `delete`d from the batch.
     fn delete(&mut self, key: &[u8]);
+    /// Called with a key that was `single_delete`d from the batch.
+    ///
+    /// Defaults to [`delete`](Self::delete).
+    fn single_delete(&mut self, key: &[u8]) {
+        self.delete(key);
+    }
 }
 
 /// Receives the puts, deletes, and merges of a write batch with column family
@@ -84,6 +116,106 @@ pub trait WriteBatchIteratorCf {
     /// Merge operations combine the provided value with the existing value at
     /// the key using a database-defined merge operator.
     fn merge_cf(&mut self, cf_id: u32, key: &[u8], value: &[u8]);
+    /// Called with a column family ID and key that were `single_delete`d from
+    /// the specific column family of the batch.
+    ///
+    /// Defaults to [`delete_cf`](Self::delete_cf).
+    fn single_delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+        self.delete_cf(cf_id, key);
+    }
+}
+
+/// An operation of a write batch, see [`WriteBatchWithTransaction::ops`].
+///
+/// `cf_id` is the id of the column family the operation applies to, 0 for the
+/// default column family. In column families with user-defined timestamps, the
+/// keys end with the timestamp of the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteBatchOp {
+    Put {
+        cf_id: u32,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Merge {
+        cf_id: u32,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        cf_id: u32,
+        key: Vec<u8>,
+    },
+    SingleDelete {
+        cf_id: u32,
+        key: Vec<u8>,
+    },
+    /// Deletion of the keys in `[begin_key, end_key)`
+    DeleteRange {
+        cf_id: u32,
+        begin_key: Vec<u8>,
+        end_key: Vec<u8>,
+    },
+    /// A wide-column entity, with its columns sorted by name
+    PutEntity {
+        cf_id: u32,
+        key: Vec<u8>,
+        columns: WideColumns,
+    },
+    /// A blob added with [`put_log_data`](WriteBatchWithTransaction::put_log_data),
+    /// only kept in the WAL
+    LogData(Vec<u8>),
+}
+
+unsafe extern "C" fn writebatch_op_callback(
+    state: *mut c_void,
+    op: *const ffi::rust_rocksdb_writebatch_op_t,
+) {
+    unsafe {
+        let result = &mut *(state as *mut Result<Vec<WriteBatchOp>, Error>);
+        let Ok(ops) = result else {
+            // an earlier operation could not be decoded
+            return;
+        };
+        let op = &*op;
+        let cf_id = op.column_family_id;
+        let key = raw_data(op.key, op.key_len).unwrap_or_default();
+        let value = raw_data(op.value, op.value_len).unwrap_or_default();
+        let op = match op.type_ {
+            0 => WriteBatchOp::Put { cf_id, key, value },
+            1 => WriteBatchOp::Merge { cf_id, key, value },
+            2 => WriteBatchOp::Delete { cf_id, key },
+            3 => WriteBatchOp::SingleDelete { cf_id, key },
+            4 => WriteBatchOp::DeleteRange {
+                cf_id,
+                begin_key: key,
+                end_key: value,
+            },
+            5 => WriteBatchOp::PutEntity {
+                cf_id,
+                key,
+                columns: WideColumns::from_raw(
+                    op.num_columns,
+                    |i, len| {
+                        *len = *op.column_names_lens.add(i);
+                        *op.column_names.add(i)
+                    },
+                    |i, len| {
+                        *len = *op.column_values_lens.add(i);
+                        *op.column_values.add(i)
+                    },
+                ),
+            },
+            6 => WriteBatchOp::LogData(value),
+            op_type => {
+                *result = Err(Error::new(format!(
+                    "Corruption: unexpected operation type {op_type} in write batch"
+                )));
+                return;
+            }
+        };
+        ops.push(op);
+    }
 }
 
 unsafe extern "C" fn writebatch_put_callback<T: WriteBatchIterator>(
@@ -113,6 +245,18 @@ unsafe extern "C" fn writebatch_delete_callback<T: WriteBatchIterator>(
     }
 }
 
+unsafe extern "C" fn writebatch_single_delete_callback<T: WriteBatchIterator>(
+    state: *mut c_void,
+    k: *const c_char,
+    klen: usize,
+) {
+    unsafe {
+        let callbacks = &mut *(state as *mut T);
+        let key = slice::from_raw_parts(k as *const u8, klen);
+        callbacks.single_delete(key);
+    }
+}
+
 unsafe extern "C" fn writebatch_put_cf_callback<T: WriteBatchIteratorCf>(
     state: *mut c_void,
     cfid: u32,
@@ -158,6 +302,19 @@ unsafe extern "C" fn writebatch_merge_cf_callback<T: WriteBatchIteratorCf>(
     }
 }
 
+unsafe extern "C" fn writebatch_single_delete_cf_callback<T: WriteBatchIteratorCf>(
+    state: *mut c_void,
+    cfid: u32,
+    k: *const c_char,
+    klen: usize,
+) {
+    unsafe {
+        let callbacks = &mut *(state as *mut T);
+        let key = slice::from_raw_parts(k as *const u8, klen);
+        callbacks.single_delete_cf(cfid, key);
+    }
+}
+
 impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
     /// Create a new `WriteBatch` without allocating memory.
     pub fn new() -> Self {
@@ -216,25 +373,26 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
     }
 
     /// Iterate the put and delete operations within this write batch. Note that
-    /// this does _not_ return an `Iterator` but instead will invoke the `put()`
-    /// and `delete()` member functions of the provided `WriteBatchIterator`
-    /// trait implementation.
+    /// this does _not_ return an `Iterator` but instead will invoke the `put()`,
+    /// `delete()` and `single_delete()` member functions of the provided
+    /// `WriteBatchIterator` trait implementation.
     pub fn iterate<T: WriteBatchIterator>(&self, callbacks: &mut T) {
         let state = std::ptr::from_mut::<T>(callbacks) as *mut c_void;
         unsafe {
-            ffi::rocksdb_writebatch_iterate(
+            ffi::rust_rocksdb_writebatch_iterate(
                 self.inner,
                 state,
                 Some(writebatch_put_callback::<T>),
                 Some(writebatch_delete_callback::<T>),
+                Some(writebatch_single_delete_callback::<T>),
             );
         }
     }
 
     /// Iterate the put, delete, and merge operations within this write batch with column family
     /// information. Note that this does _not_ return an `Iterator` but instead will invoke the
-    /// `put_cf()`, `delete_cf()`, and `merge_cf()` member functions of the provided
-    /// `WriteBatchIteratorCf` trait implementation.
+    /// `put_cf()`, `delete_cf()`, `merge_cf()` and `single_delete_cf()` member functions of the
+    /// provided `WriteBatchIteratorCf` trait implementation.
     ///
     /// # Notes
     /// - For operations on the default column family ("default"), the `cf_id` parameter passed to
@@ -242,16 +400,39 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
     pub fn iterate_cf<T: WriteBatchIteratorCf>(&self, callbacks: &mut T) {
         let state = std::ptr::from_mut::<T>(callbacks) as *mut c_void;
         unsafe {
-            ffi::rocksdb_writebatch_iterate_cf(
+            ffi::rust_rocksdb_writebatch_iterate_cf(
                 self.inner,
                 state,
                 Some(writebatch_put_cf_callback::<T>),
                 Some(writebatch_delete_cf_callback::<T>),
                 Some(writebatch_merge_cf_callback::<T>),
+                Some(writebatch_single_delete_cf_callback::<T>),
             );
         }
     }
 
+    /// Returns the operations of this write batch, in order.
+    ///
+    /// Unlike [`iterate`](Self::iterate) and [`iterate_cf`](Self::iterate_cf),
+    /// every kind of operation is reported, which makes it suitable to decode
+    /// the batches of [`DBWALIterator`](crate::DBWALIterator) or
+    /// [`from_data`](Self::from_data). Timed puts are reported as puts, and the
+    /// markers of two-phase commit transactions are skipped.
+    ///
+    /// Fails if the batch is malformed, or holds a kind of operation this
+    /// crate does not know.
+    pub fn ops(&self) -> Result<impl Iterator<Item = WriteBatchOp>, Error> {
+        let mut ops: Result<Vec<WriteBatchOp>, Error> = Ok(Vec::new());
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_writebatch_iterate_ops(
+                self.inner,
+                (&mut ops as *mut Result<Vec<WriteBatchOp>, Error>).cast::<c_void>(),
+                Some(writebatch_op_callback),
+            ));
+        }
+        Ok(ops?.into_iter())
+    }
+
     /// Insert a value into the database under the given key.
     pub fn put<K, V>(&mut self, key: K, value: V)
     where
@@ -318,6 +499,35 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
         }
     }
 
+    /// Insert a wide-column entity into the specific column family of the
+    /// database under the given key.
+    ///
+    /// Fails if the columns cannot be encoded, e.g. when a column name is
+    /// repeated.
+    pub fn put_entity_cf<K: AsRef<[u8]>>(
+        &mut self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        columns: &WideColumns,
+    ) -> Result<(), Error> {
+        let key = key.as_ref();
+        let raw = columns.as_raw_parts();
+        unsafe {
+            ffi_try!(ffi::rust_rocksdb_writebatch_put_entity_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                raw.len(),
+                raw.names.as_ptr(),
+                raw.names_sizes.as_ptr(),
+                raw.values.as_ptr(),
+                raw.values_sizes.as_ptr(),
+            ));
+        }
+        Ok(())
+    }
+
     pub fn merge<K, V>(&mut self, key: K, value: V)
     where
         K: AsRef<[u8]>,
@@ -385,6 +595,35 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
         }
     }
 
+    /// Removes the database entry for key, assuming it was written exactly once.
+    /// See [`DBCommon::single_delete_opt`](crate::DBCommon::single_delete_opt).
+    pub fn single_delete<K: AsRef<[u8]>>(&mut self, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry in the specific column family for key,
+    /// assuming it was written exactly once.
+    pub fn single_delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_singledelete_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
     /// Removes the database entry in the specific column family with timestamp for key.
     /// Does nothing if the key was not found.
     pub fn delete_cf_with_ts<K: AsRef<[u8]>, S: AsRef<[u8]>>(
@@ -435,6 +674,38 @@ impl<const TRANSACTION: bool> WriteBatchWithTransaction<TRANSACTION> {
             ffi::rocksdb_writebatch_clear(self.inner);
         }
     }
+
+    /// Records the current state of the batch, to go back to with
+    /// [`rollback_to_save_point`](Self::rollback_to_save_point). Save points
+    /// can be nested.
+    pub fn set_save_point(&mut self) {
+        unsafe {
+            ffi::rocksdb_writebatch_set_save_point(self.inner);
+        }
+    }
+
+    /// Removes the updates added since the most recent save point, and the
+    /// save point itself.
+    ///
+    /// Returns an error of kind [`NotFound`](crate::ErrorKind::NotFound) if
+    /// no save point is set.
+    pub fn rollback_to_save_point(&mut self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_writebatch_rollback_to_save_point(self.inner));
+        }
+        Ok(())
+    }
+
+    /// Removes the most recent save point, keeping the updates added since.
+    ///
+    /// Returns an error of kind [`NotFound`](crate::ErrorKind::NotFound) if
+    /// no save point is set.
+    pub fn pop_save_point(&mut self) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_writebatch_pop_save_point(self.inner));
+        }
+        Ok(())
+    }
 }
 
 impl WriteBatchWithTransaction<false> {
@@ -478,6 +749,21 @@ impl WriteBatchWithTransaction<false> {
     }
 }
 
+impl WriteBatchLike for WriteBatch {}
+
+impl sealed::WriteBatchLike for WriteBatch {
+    unsafe fn write_to_db(
+        &self,
+        db: *mut ffi::rocksdb_t,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_write(db, writeopts.inner, self.inner));
+        }
+        Ok(())
+    }
+}
+
 impl<const TRANSACTION: bool> Default for WriteBatchWithTransaction<TRANSACTION> {
     fn default() -> Self {
         Self::new()
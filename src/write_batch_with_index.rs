@@ -0,0 +1,430 @@
+use std::ptr;
+
+use libc::{c_char, c_uchar, c_void, size_t};
+
+use crate::{
+    db::{DBAccess, DBInner},
+    ffi,
+    ffi_util::raw_data,
+    write_batch::sealed,
+    AsColumnFamilyRef, DBCommon, DBRawIteratorWithThreadMode, Error, Options, ReadOptions,
+    ThreadMode, WriteBatchLike, WriteOptions,
+};
+
+/// A batch of write operations, indexed by key so that the batch can be read
+/// before it is written, alone or on top of the database.
+///
+/// Like a [`WriteBatch`](crate::WriteBatch), the batch is written atomically
+/// with [`DBCommon::write`].
+///
+/// ```
+/// use rocksdb::{DB, Options, ReadOptions, WriteBatchWithIndex};
+///
+/// let tempdir = tempfile::Builder::new()
+///     .prefix("_path_for_rocksdb_storage_wbwi")
+///     .tempdir()
+///     .expect("Failed to create temporary path for the _path_for_rocksdb_storage_wbwi");
+/// let path = tempdir.path();
+/// {
+///     let db = DB::open_default(path).unwrap();
+///     db.put(b"k1", b"v1").unwrap();
+///
+///     let mut batch = WriteBatchWithIndex::new(0, true);
+///     batch.put(b"k2", b"v2");
+///     batch.delete(b"k1");
+///     assert!(batch.get_from_batch(b"k2", &Options::default()).unwrap().is_some());
+///     assert!(batch
+///         .get_from_batch_and_db(&db, b"k1", &ReadOptions::default())
+///         .unwrap()
+///         .is_none());
+///
+///     db.write(batch).unwrap();
+/// }
+/// let _ = DB::destroy(&Options::default(), path);
+/// ```
+pub struct WriteBatchWithIndex {
+    pub(crate) inner: *mut ffi::rocksdb_writebatch_wi_t,
+}
+
+impl WriteBatchWithIndex {
+    /// Creates a batch, allocating `reserved_bytes` up front.
+    ///
+    /// With `overwrite_key`, the index only keeps the latest update of every
+    /// key, which the reads and iterators then return. Otherwise the
+    /// iterators return every update, newest first.
+    pub fn new(reserved_bytes: usize, overwrite_key: bool) -> Self {
+        Self {
+            inner: unsafe {
+                ffi::rocksdb_writebatch_wi_create(reserved_bytes, c_uchar::from(overwrite_key))
+            },
+        }
+    }
+
+    /// Returns the number of updates in the batch.
+    pub fn len(&self) -> usize {
+        unsafe { ffi::rocksdb_writebatch_wi_count(self.inner) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return a reference to a byte array which represents a serialized version of the batch,
+    /// as by [`WriteBatch::data`](crate::WriteBatch::data).
+    pub fn data(&self) -> &[u8] {
+        unsafe {
+            let mut batch_size: size_t = 0;
+            let batch_data = ffi::rocksdb_writebatch_wi_data(self.inner, &mut batch_size);
+            std::slice::from_raw_parts(batch_data as _, batch_size)
+        }
+    }
+
+    /// Return the serialized size of the batch, in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        self.data().len()
+    }
+
+    /// Insert a value into the database under the given key.
+    pub fn put<K, V>(&mut self, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+        }
+    }
+
+    /// Insert a value into the specific column family of the database under the given key.
+    pub fn put_cf<K, V>(&mut self, cf: &impl AsColumnFamilyRef, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+        }
+    }
+
+    pub fn merge<K, V>(&mut self, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_merge(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+        }
+    }
+
+    pub fn merge_cf<K, V>(&mut self, cf: &impl AsColumnFamilyRef, key: K, value: V)
+    where
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.as_ref();
+        let value = value.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_merge_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+                value.as_ptr() as *const c_char,
+                value.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry for key. Does nothing if the key was not found.
+    pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry in the specific column family for key.
+    /// Does nothing if the key was not found.
+    pub fn delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_delete_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry for key, see
+    /// [`WriteBatch::single_delete`](crate::WriteBatch::single_delete).
+    pub fn single_delete<K: AsRef<[u8]>>(&mut self, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_singledelete(
+                self.inner,
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Removes the database entry in the specific column family for key, see
+    /// [`WriteBatch::single_delete`](crate::WriteBatch::single_delete).
+    pub fn single_delete_cf<K: AsRef<[u8]>>(&mut self, cf: &impl AsColumnFamilyRef, key: K) {
+        let key = key.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_singledelete_cf(
+                self.inner,
+                cf.inner(),
+                key.as_ptr() as *const c_char,
+                key.len() as size_t,
+            );
+        }
+    }
+
+    /// Appends a blob to the write-ahead log, see
+    /// [`WriteBatch::put_log_data`](crate::WriteBatch::put_log_data).
+    pub fn put_log_data<V: AsRef<[u8]>>(&mut self, log_data: V) {
+        let log_data = log_data.as_ref();
+
+        unsafe {
+            ffi::rocksdb_writebatch_wi_put_log_data(
+                self.inner,
+                log_data.as_ptr() as *const c_char,
+                log_data.len() as size_t,
+            );
+        }
+    }
+
+    /// Clear all updates buffered in this batch.
+    pub fn clear(&mut self) {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_clear(self.inner);
+        }
+    }
+
+    /// Returns the value of the key in the default column family resulting
+    /// from the updates in the batch alone.
+    ///
+    /// `options` are those of the database, used for merges. A merge on top
+    /// of a value the batch doesn't hold fails with an error of kind
+    /// [`MergeInProgress`](crate::ErrorKind::MergeInProgress).
+    pub fn get_from_batch<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        options: &Options,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.get_from_batch_impl(ptr::null_mut(), key.as_ref(), options)
+    }
+
+    /// Returns the value of the key in a column family resulting from the
+    /// updates in the batch alone. See [`get_from_batch`](Self::get_from_batch).
+    pub fn get_from_batch_cf<K: AsRef<[u8]>>(
+        &self,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        options: &Options,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.get_from_batch_impl(cf.inner(), key.as_ref(), options)
+    }
+
+    fn get_from_batch_impl(
+        &self,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        options: &Options,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let mut val_len: size_t = 0;
+        unsafe {
+            let val = if cf.is_null() {
+                ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch(
+                    self.inner,
+                    options.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    &mut val_len,
+                ))
+            } else {
+                ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_cf(
+                    self.inner,
+                    options.inner,
+                    cf,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    &mut val_len,
+                ))
+            };
+            Ok(take_value(val, val_len))
+        }
+    }
+
+    /// Returns the value of the key in the default column family resulting
+    /// from applying the updates in the batch to the database, as read with
+    /// `readopts`.
+    pub fn get_from_batch_and_db<T: ThreadMode, I: DBInner, K: AsRef<[u8]>>(
+        &self,
+        db: &DBCommon<T, I>,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.get_from_batch_and_db_impl(db.inner.inner(), ptr::null_mut(), key.as_ref(), readopts)
+    }
+
+    /// Returns the value of the key in a column family resulting from
+    /// applying the updates in the batch to the database. See
+    /// [`get_from_batch_and_db`](Self::get_from_batch_and_db).
+    pub fn get_from_batch_and_db_cf<T: ThreadMode, I: DBInner, K: AsRef<[u8]>>(
+        &self,
+        db: &DBCommon<T, I>,
+        cf: &impl AsColumnFamilyRef,
+        key: K,
+        readopts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.get_from_batch_and_db_impl(db.inner.inner(), cf.inner(), key.as_ref(), readopts)
+    }
+
+    fn get_from_batch_and_db_impl(
+        &self,
+        db: *mut ffi::rocksdb_t,
+        cf: *mut ffi::rocksdb_column_family_handle_t,
+        key: &[u8],
+        readopts: &ReadOptions,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        let mut val_len: size_t = 0;
+        unsafe {
+            let val = if cf.is_null() {
+                ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_and_db(
+                    self.inner,
+                    db,
+                    readopts.inner,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    &mut val_len,
+                ))
+            } else {
+                ffi_try!(ffi::rocksdb_writebatch_wi_get_from_batch_and_db_cf(
+                    self.inner,
+                    db,
+                    readopts.inner,
+                    cf,
+                    key.as_ptr() as *const c_char,
+                    key.len() as size_t,
+                    &mut val_len,
+                ))
+            };
+            Ok(take_value(val, val_len))
+        }
+    }
+
+    /// Returns an iterator over the default column family of the database,
+    /// as seen by `base`, with the updates in the batch applied on top.
+    ///
+    /// The iterator borrows the batch, which cannot be updated while it is
+    /// alive.
+    pub fn iterator_with_base<'a: 'b, 'b, D: DBAccess>(
+        &'b self,
+        base: DBRawIteratorWithThreadMode<'a, D>,
+    ) -> DBRawIteratorWithThreadMode<'b, D> {
+        base.map_inner(|base| unsafe {
+            ffi::rocksdb_writebatch_wi_create_iterator_with_base(self.inner, base)
+        })
+    }
+
+    /// Returns an iterator over a column family of the database, as seen by
+    /// `base`, with the updates in the batch applied on top. `base` must
+    /// iterate over the same column family. See
+    /// [`iterator_with_base`](Self::iterator_with_base).
+    pub fn iterator_with_base_cf<'a: 'b, 'b, D: DBAccess>(
+        &'b self,
+        base: DBRawIteratorWithThreadMode<'a, D>,
+        cf: &impl AsColumnFamilyRef,
+    ) -> DBRawIteratorWithThreadMode<'b, D> {
+        base.map_inner(|base| unsafe {
+            ffi::rocksdb_writebatch_wi_create_iterator_with_base_cf(self.inner, base, cf.inner())
+        })
+    }
+}
+
+/// Copies the value returned by the C API and frees it.
+unsafe fn take_value(val: *mut c_char, val_len: size_t) -> Option<Vec<u8>> {
+    unsafe {
+        let value = raw_data(val, val_len);
+        ffi::rocksdb_free(val as *mut c_void);
+        value
+    }
+}
+
+impl WriteBatchLike for WriteBatchWithIndex {}
+
+impl sealed::WriteBatchLike for WriteBatchWithIndex {
+    unsafe fn write_to_db(
+        &self,
+        db: *mut ffi::rocksdb_t,
+        writeopts: &WriteOptions,
+    ) -> Result<(), Error> {
+        unsafe {
+            ffi_try!(ffi::rocksdb_write_writebatch_wi(
+                db,
+                writeopts.inner,
+                self.inner
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for WriteBatchWithIndex {
+    /// Creates a batch keeping only the latest update of every key.
+    fn default() -> Self {
+        Self::new(0, true)
+    }
+}
+
+impl Drop for WriteBatchWithIndex {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::rocksdb_writebatch_wi_destroy(self.inner);
+        }
+    }
+}
+
+unsafe impl Send for WriteBatchWithIndex {}
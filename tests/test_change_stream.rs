@@ -0,0 +1,223 @@
+mod util;
+
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    Change, ChangeEvent, ChangeOp, DBWithThreadMode, MultiThreaded, Options, WideColumns,
+    WriteBatch, WriteOptions, DB,
+};
+use util::DBPath;
+
+fn change(sequence_number: u64, column_family: (u32, &str), op: ChangeOp) -> ChangeEvent {
+    ChangeEvent::Change(Change {
+        sequence_number,
+        column_family_id: column_family.0,
+        column_family_name: Some(column_family.1.to_owned()),
+        op,
+    })
+}
+
+fn put(key: &[u8], value: &[u8]) -> ChangeOp {
+    ChangeOp::Put {
+        key: key.to_vec(),
+        value: value.to_vec(),
+    }
+}
+
+#[test]
+fn change_stream_follows_writes() {
+    let path = DBPath::new("_rust_rocksdb_change_stream_follows_writes");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    let mut stream = db.change_stream(0);
+    assert_eq!(stream.poll().unwrap(), None);
+
+    let mut batch = WriteBatch::default();
+    batch.put(b"k1", b"v1");
+    batch.put_log_data(b"ignored");
+    batch.delete_range_cf(&cf1, b"a", b"z");
+    db.write(batch).unwrap();
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(1, (0, "default"), put(b"k1", b"v1")))
+    );
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(
+            2,
+            (1, "cf1"),
+            ChangeOp::DeleteRange {
+                begin_key: b"a".to_vec(),
+                end_key: b"z".to_vec(),
+            }
+        ))
+    );
+    assert_eq!(stream.poll().unwrap(), None);
+    assert_eq!(stream.last_sequence_number(), 2);
+
+    let columns: WideColumns = [("a", "1")].into_iter().collect();
+    db.put_entity_cf(&cf1, b"k2", &columns).unwrap();
+    db.delete(b"k1").unwrap();
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(
+            3,
+            (1, "cf1"),
+            ChangeOp::PutEntity {
+                key: b"k2".to_vec(),
+                columns,
+            }
+        ))
+    );
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(
+            4,
+            (0, "default"),
+            ChangeOp::Delete {
+                key: b"k1".to_vec()
+            }
+        ))
+    );
+    assert_eq!(stream.poll().unwrap(), None);
+    assert_eq!(stream.last_sequence_number(), 4);
+}
+
+#[test]
+fn change_stream_resumes_in_batch() {
+    let path = DBPath::new("_rust_rocksdb_change_stream_resumes_in_batch");
+    let db = DB::open_default(&path).unwrap();
+
+    let mut batch = WriteBatch::default();
+    batch.put(b"k1", b"v1");
+    batch.put(b"k2", b"v2");
+    db.write(batch).unwrap();
+
+    let mut stream = db.change_stream(0);
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(1, (0, "default"), put(b"k1", b"v1")))
+    );
+    let last = stream.last_sequence_number();
+    assert_eq!(last, 1);
+
+    let mut stream = db.change_stream(last);
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(2, (0, "default"), put(b"k2", b"v2")))
+    );
+    assert_eq!(stream.poll().unwrap(), None);
+
+    let mut stream = db.change_stream(stream.last_sequence_number());
+    assert_eq!(stream.poll().unwrap(), None);
+}
+
+#[test]
+fn change_stream_reports_gaps() {
+    let path = DBPath::new("_rust_rocksdb_change_stream_reports_gaps");
+    let db = DB::open_default(&path).unwrap();
+
+    let mut no_wal = WriteOptions::default();
+    no_wal.disable_wal(true);
+    db.put_opt(b"k1", b"v1", &no_wal).unwrap();
+    db.put(b"k2", b"v2").unwrap();
+
+    let mut stream = db.change_stream(0);
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(ChangeEvent::Gap { start: 1, end: 2 })
+    );
+    assert_eq!(stream.last_sequence_number(), 1);
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(2, (0, "default"), put(b"k2", b"v2")))
+    );
+    assert_eq!(stream.poll().unwrap(), None);
+}
+
+#[test]
+fn change_stream_reports_purged_wal() {
+    let path = DBPath::new("_rust_rocksdb_change_stream_reports_purged_wal");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    // delete the WAL files as soon as their writes are flushed
+    opts.set_wal_ttl_seconds(0);
+    opts.set_wal_size_limit_mb(0);
+    let db = DB::open(&opts, &path).unwrap();
+
+    db.put(b"k1", b"v1").unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.put(b"k3", b"v3").unwrap();
+    db.flush().unwrap();
+    db.flush_wal(true).unwrap();
+    // the WAL files are purged in the background once the flush completes
+    let wal_files = || {
+        fs::read_dir(&path)
+            .unwrap()
+            .filter(|entry| {
+                let name = entry.as_ref().unwrap().file_name();
+                name.to_string_lossy().ends_with(".log")
+            })
+            .count()
+    };
+    for _ in 0..100 {
+        if wal_files() == 1 {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(wal_files(), 1);
+
+    // a follower that only consumed the first write resumes
+    let mut stream = db.change_stream(1);
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(ChangeEvent::Gap { start: 2, end: 4 })
+    );
+    assert_eq!(stream.last_sequence_number(), 3);
+    assert_eq!(stream.poll().unwrap(), None);
+
+    db.put(b"k4", b"v4").unwrap();
+    assert_eq!(
+        stream.poll().unwrap(),
+        Some(change(4, (0, "default"), put(b"k4", b"v4")))
+    );
+    assert_eq!(stream.poll().unwrap(), None);
+}
+
+#[test]
+fn change_stream_waits_for_writes() {
+    let path = DBPath::new("_rust_rocksdb_change_stream_waits_for_writes");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let db = DBWithThreadMode::<MultiThreaded>::open(&opts, &path).unwrap();
+
+    let mut stream = db.change_stream(0);
+    stream.set_poll_interval(Duration::from_millis(10));
+    assert_eq!(
+        stream.poll_timeout(Duration::from_millis(50)).unwrap(),
+        None
+    );
+
+    thread::scope(|s| {
+        s.spawn(|| {
+            thread::sleep(Duration::from_millis(50));
+            // column families created after the stream are named too
+            db.create_cf("cf1", &Options::default()).unwrap();
+            db.put_cf(&db.cf_handle("cf1").unwrap(), b"k1", b"v1")
+                .unwrap();
+        });
+        assert_eq!(
+            stream.next().unwrap().unwrap(),
+            change(1, (1, "cf1"), put(b"k1", b"v1"))
+        );
+    });
+}
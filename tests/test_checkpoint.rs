@@ -16,7 +16,7 @@ mod util;
 
 use pretty_assertions::assert_eq;
 
-use rocksdb::{checkpoint::Checkpoint, Options, DB};
+use rocksdb::{checkpoint::Checkpoint, ImportColumnFamilyOptions, Options, DB};
 use util::DBPath;
 
 #[test]
@@ -100,6 +100,78 @@ pub fn test_multi_checkpoints() {
     assert_eq!(cp.get(b"k6").unwrap().unwrap(), b"v6");
 }
 
+#[test]
+pub fn test_export_import_column_family() {
+    const PATH_PREFIX: &str = "_rust_rocksdb_cp_export_import_";
+
+    let db_path = DBPath::new(&format!("{PATH_PREFIX}db1"));
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    #[cfg(feature = "multi-threaded-cf")]
+    let db = DB::open_cf(&opts, &db_path, ["cf1"]).unwrap();
+    #[cfg(not(feature = "multi-threaded-cf"))]
+    let mut db = DB::open_cf(&opts, &db_path, ["cf1"]).unwrap();
+
+    // Export the column family
+    let export_path = DBPath::new(&format!("{PATH_PREFIX}export"));
+    let metadata = {
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put_cf(&cf1, b"k1", b"v1").unwrap();
+        db.put_cf(&cf1, b"k2", b"v2").unwrap();
+        db.flush_cf(&cf1).unwrap();
+
+        let cp = Checkpoint::new(&db).unwrap();
+        let metadata = cp.export_column_family(&cf1, &export_path).unwrap();
+
+        // Exporting into an existing directory fails
+        assert!(cp.export_column_family(&cf1, &export_path).is_err());
+        metadata
+    };
+    assert_eq!(metadata.db_comparator_name(), "leveldb.BytewiseComparator");
+    let files = metadata.files();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].start_key.as_deref(), Some(&b"k1"[..]));
+    assert_eq!(files[0].end_key.as_deref(), Some(&b"k2"[..]));
+
+    // Import a copy into another database
+    let db2_path = DBPath::new(&format!("{PATH_PREFIX}db2"));
+    #[cfg(feature = "multi-threaded-cf")]
+    let db2 = DB::open(&opts, &db2_path).unwrap();
+    #[cfg(not(feature = "multi-threaded-cf"))]
+    let mut db2 = DB::open(&opts, &db2_path).unwrap();
+    db2.create_column_family_with_import(
+        "imported",
+        &Options::default(),
+        &ImportColumnFamilyOptions::default(),
+        &metadata,
+    )
+    .unwrap();
+    {
+        let imported = db2.cf_handle("imported").unwrap();
+        assert_eq!(db2.get_cf(&imported, b"k1").unwrap().unwrap(), b"v1");
+        assert_eq!(db2.get_cf(&imported, b"k2").unwrap().unwrap(), b"v2");
+    }
+
+    // Importing under an existing name fails
+    assert!(db2
+        .create_column_family_with_import(
+            "imported",
+            &Options::default(),
+            &ImportColumnFamilyOptions::default(),
+            &metadata,
+        )
+        .is_err());
+
+    // Move the files into the source database under a new name
+    let mut import_opts = ImportColumnFamilyOptions::default();
+    import_opts.set_move_files(true);
+    db.create_column_family_with_import("cf2", &Options::default(), &import_opts, &metadata)
+        .unwrap();
+    let cf2 = db.cf_handle("cf2").unwrap();
+    assert_eq!(db.get_cf(&cf2, b"k2").unwrap().unwrap(), b"v2");
+}
+
 #[test]
 fn test_checkpoint_outlive_db() {
     let t = trybuild::TestCases::new();
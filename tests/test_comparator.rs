@@ -1,6 +1,6 @@
 mod util;
 
-use rocksdb::{CompactOptions, Options, ReadOptions, DB};
+use rocksdb::{CompactOptions, Direction, IteratorMode, Options, ReadOptions, VersionValue, DB};
 use std::cmp::Ordering;
 use std::iter::FromIterator;
 use util::{U64Comparator, U64Timestamp};
@@ -267,3 +267,109 @@ fn test_comparator_with_column_family_with_ts() {
 
     let _ = DB::destroy(&Options::default(), path);
 }
+
+#[test]
+fn test_read_timestamps() {
+    let tempdir = tempfile::Builder::new()
+        .prefix("_path_for_rocksdb_storage_read_timestamps")
+        .tempdir()
+        .expect(
+            "Failed to create temporary path for the _path_for_rocksdb_storage_read_timestamps.",
+        );
+    let path = tempdir.path();
+
+    let mut db_opts = Options::default();
+    db_opts.create_if_missing(true);
+    db_opts.set_comparator_with_ts(
+        U64Comparator::NAME,
+        U64Timestamp::SIZE,
+        Box::new(U64Comparator::compare),
+        Box::new(U64Comparator::compare_ts),
+        Box::new(U64Comparator::compare_without_ts),
+    );
+    let db = DB::open(&db_opts, path).unwrap();
+
+    db.put_with_ts(b"k1", U64Timestamp::new(1), b"v1").unwrap();
+    db.put_with_ts(b"k1", U64Timestamp::new(3), b"v2").unwrap();
+    db.put_with_ts(b"k2", U64Timestamp::new(2), b"v3").unwrap();
+    db.delete_with_ts(b"k2", U64Timestamp::new(4)).unwrap();
+
+    let mut opts = ReadOptions::default();
+    opts.set_timestamp(U64Timestamp::new(2));
+    let (value, ts) = db.get_with_ts(b"k1", &opts).unwrap().unwrap();
+    assert_eq!(value, b"v1");
+    assert_eq!(U64Timestamp::from(ts.as_slice()), U64Timestamp::new(1));
+    opts.set_timestamp(U64Timestamp::new(4));
+    let (value, ts) = db.get_with_ts(b"k1", &opts).unwrap().unwrap();
+    assert_eq!(value, b"v2");
+    assert_eq!(U64Timestamp::from(ts.as_slice()), U64Timestamp::new(3));
+    assert!(db.get_with_ts(b"k2", &opts).unwrap().is_none());
+
+    // latest versions at a timestamp
+    let mut opts = ReadOptions::default();
+    opts.set_timestamp(U64Timestamp::new(3));
+    let mut iter = db.raw_iterator_opt(opts);
+    iter.seek_to_first();
+    assert_eq!(iter.key(), Some(&b"k1"[..]));
+    assert_eq!(
+        iter.timestamp().map(U64Timestamp::from),
+        Some(U64Timestamp::new(3))
+    );
+
+    let mut opts = ReadOptions::default();
+    opts.set_timestamp(U64Timestamp::new(3));
+    let versions: Vec<_> = db
+        .timestamped_iterator_opt(IteratorMode::Start, opts)
+        .map(Result::unwrap)
+        .map(|(key, value, ts)| (key.to_vec(), value, U64Timestamp::from(&*ts)))
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            (b"k1".to_vec(), put(b"v2"), U64Timestamp::new(3)),
+            (b"k2".to_vec(), put(b"v3"), U64Timestamp::new(2)),
+        ]
+    );
+
+    // every version in a timestamp range
+    let mut opts = ReadOptions::default();
+    opts.set_timestamp(U64Timestamp::new(4));
+    opts.set_iter_start_ts(U64Timestamp::new(1));
+    let versions: Vec<_> = db
+        .timestamped_iterator_opt(IteratorMode::Start, opts)
+        .map(Result::unwrap)
+        .map(|(key, value, ts)| (key.to_vec(), value, U64Timestamp::from(&*ts)))
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            (b"k1".to_vec(), put(b"v2"), U64Timestamp::new(3)),
+            (b"k1".to_vec(), put(b"v1"), U64Timestamp::new(1)),
+            (b"k2".to_vec(), VersionValue::Delete, U64Timestamp::new(4)),
+            (b"k2".to_vec(), put(b"v3"), U64Timestamp::new(2)),
+        ]
+    );
+
+    // an empty value is not a deletion
+    db.put_with_ts(b"k3", U64Timestamp::new(5), b"").unwrap();
+    db.delete_with_ts(b"k3", U64Timestamp::new(6)).unwrap();
+    let mut opts = ReadOptions::default();
+    opts.set_timestamp(U64Timestamp::new(6));
+    opts.set_iter_start_ts(U64Timestamp::new(5));
+    let versions: Vec<_> = db
+        .timestamped_iterator_opt(IteratorMode::From(b"k3", Direction::Forward), opts)
+        .map(Result::unwrap)
+        .map(|(key, value, ts)| (key.to_vec(), value, U64Timestamp::from(&*ts)))
+        .collect();
+    assert_eq!(
+        versions,
+        [
+            (b"k3".to_vec(), VersionValue::Delete, U64Timestamp::new(6)),
+            (b"k3".to_vec(), put(b""), U64Timestamp::new(5)),
+        ]
+    );
+}
+
+fn put(value: &[u8]) -> VersionValue {
+    VersionValue::Put(Box::from(value))
+}
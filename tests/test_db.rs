@@ -23,11 +23,12 @@ use pretty_assertions::assert_eq;
 use rocksdb::statistics::{Histogram, StatsLevel, Ticker};
 use rocksdb::{
     perf::get_memory_usage_stats, BlockBasedOptions, BottommostLevelCompaction, Cache,
-    ColumnFamilyDescriptor, ColumnFamilyTtl, CompactOptions, CuckooTableOptions, DBAccess,
-    DBCompactionStyle, DBWithThreadMode, Env, Error, ErrorKind, FifoCompactOptions, IteratorMode,
-    MultiThreaded, Options, PerfContext, PerfMetric, ReadOptions, SingleThreaded, SliceTransform,
-    Snapshot, UniversalCompactOptions, UniversalCompactionStopStyle, WaitForCompactOptions,
-    WriteBatch, DB, DEFAULT_COLUMN_FAMILY_NAME,
+    ColumnFamilyDescriptor, ColumnFamilyTtl, CompactOptions, CompactionOptions, CuckooTableOptions,
+    DBAccess, DBCompactionStyle, DBCompressionType, DBWithThreadMode, Env, Error, ErrorKind,
+    FifoCompactOptions, FileTemperatureAge, IteratorMode, MultiThreaded, Options, PerfContext,
+    PerfMetric, ReadOptions, SingleThreaded, SliceTransform, Snapshot, Temperature,
+    UniversalCompactOptions, UniversalCompactionStopStyle, WaitForCompactOptions, WriteBatch, DB,
+    DEFAULT_COLUMN_FAMILY_NAME,
 };
 use util::{assert_iter, pair, DBPath, U64Comparator, U64Timestamp};
 
@@ -437,6 +438,80 @@ fn set_column_family_metadata_test() {
     }
 }
 
+#[test]
+fn column_family_metadata_levels_test() {
+    let path = DBPath::new("_rust_rocksdb_column_family_metadata_levels_test");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_disable_auto_compactions(true);
+        let mut blob_opts = Options::default();
+        blob_opts.set_enable_blob_files(true);
+        blob_opts.set_min_blob_size(16);
+        let cfs = vec![
+            ColumnFamilyDescriptor::new("cf1", Options::default()),
+            ColumnFamilyDescriptor::new("blobs", blob_opts),
+        ];
+        let db = DB::open_cf_descriptors(&opts, &path, cfs).unwrap();
+
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put_cf(&cf1, b"a", b"1").unwrap();
+        db.put_cf(&cf1, b"c", b"2").unwrap();
+        db.delete_cf(&cf1, b"b").unwrap();
+        db.flush_cf(&cf1).unwrap();
+
+        let metadata = db.get_column_family_metadata_cf(&cf1);
+        assert_eq!(metadata.name, "cf1");
+        assert_eq!(metadata.levels.len(), 7);
+        assert_eq!(metadata.blob_file_count, 0);
+        assert!(metadata.blob_files.is_empty());
+
+        let level0 = &metadata.levels[0];
+        assert_eq!(level0.level, 0);
+        assert_eq!(level0.size, metadata.size);
+        assert_eq!(level0.files.len(), 1);
+        let file = &level0.files[0];
+        assert!(file.relative_filename.ends_with(".sst"));
+        assert!(!file.directory.is_empty());
+        assert_eq!(file.size, level0.size);
+        assert_eq!(file.smallest_key, b"a");
+        assert_eq!(file.largest_key, b"c");
+        assert_eq!(file.smallest_seqno, 1);
+        assert_eq!(file.largest_seqno, 3);
+        assert_eq!(file.num_entries, 3);
+        assert_eq!(file.num_deletions, 1);
+        assert!(!file.being_compacted);
+        assert!(metadata.levels[1..].iter().all(|l| l.files.is_empty()));
+
+        // move everything to the bottommost level
+        db.compact_range_cf(&cf1, None::<&[u8]>, None::<&[u8]>);
+        let metadata = db.get_column_family_metadata_cf(&cf1);
+        assert!(metadata.levels[0].files.is_empty());
+        let bottommost = metadata.levels.last().unwrap();
+        assert_eq!(bottommost.files.len(), 1);
+        assert_eq!(bottommost.files[0].num_deletions, 0);
+
+        // blob files are reported separately
+        let blobs = db.cf_handle("blobs").unwrap();
+        db.put_cf(&blobs, b"big", [b'x'; 128]).unwrap();
+        db.flush_cf(&blobs).unwrap();
+
+        let metadata = db.get_column_family_metadata_cf(&blobs);
+        assert_eq!(metadata.blob_file_count, 1);
+        assert_eq!(metadata.blob_files.len(), 1);
+        let blob = &metadata.blob_files[0];
+        assert!(blob.file_name.ends_with(".blob"));
+        assert_eq!(blob.size, metadata.blob_file_size);
+        assert_eq!(blob.total_blob_count, 1);
+        assert_eq!(blob.garbage_blob_count, 0);
+        assert_eq!(
+            metadata.levels[0].files[0].oldest_blob_file_number,
+            blob.file_number
+        );
+    }
+}
+
 #[test]
 fn test_sequence_number() {
     let path = DBPath::new("_rust_rocksdb_test_sequence_number");
@@ -900,6 +975,143 @@ fn fifo_compaction_test() {
     }
 }
 
+#[test]
+fn compact_files_test() {
+    let path = DBPath::new("_rust_rocksdb_compact_files_test");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        opts.set_disable_auto_compactions(true);
+
+        let db = DB::open_cf(&opts, &path, ["cf1"]).unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+        for i in 0..3 {
+            for j in 0..100 {
+                db.put_cf(&cf1, format!("k{i}{j:03}"), [b'v'; 1024])
+                    .unwrap();
+            }
+            db.flush_cf(&cf1).unwrap();
+        }
+
+        let inputs: Vec<String> = db
+            .live_files()
+            .unwrap()
+            .into_iter()
+            .filter(|f| f.column_family_name == "cf1")
+            .map(|f| {
+                assert_eq!(f.level, 0);
+                f.name
+            })
+            .collect();
+        assert_eq!(inputs.len(), 3);
+
+        let mut compaction_opts = CompactionOptions::default();
+        compaction_opts.set_output_file_size_limit(64 << 10);
+        compaction_opts.set_compression(DBCompressionType::None);
+        let outputs = db
+            .compact_files_cf(&cf1, &inputs, 2, &compaction_opts)
+            .unwrap();
+        assert!(outputs.len() > 1);
+        assert!(outputs.iter().all(|name| name.ends_with(".sst")));
+
+        let livefiles: Vec<_> = db
+            .live_files()
+            .unwrap()
+            .into_iter()
+            .filter(|f| f.column_family_name == "cf1")
+            .collect();
+        assert_eq!(livefiles.len(), outputs.len());
+        assert!(livefiles.iter().all(|f| f.level == 2));
+        assert_eq!(
+            db.get_cf(&cf1, b"k1050").unwrap().unwrap(),
+            vec![b'v'; 1024]
+        );
+
+        // the inputs are gone now
+        assert!(db
+            .compact_files_cf(&cf1, &inputs, 3, &compaction_opts)
+            .is_err());
+    }
+}
+
+#[test]
+fn last_level_temperature_test() {
+    let path = DBPath::new("_rust_rocksdb_last_level_temperature_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_last_level_temperature(Temperature::Cold);
+    let db = DB::open(&opts, &path).unwrap();
+
+    // overlapping files, so that the compaction rewrites them
+    db.put(b"k1", b"v1").unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.flush().unwrap();
+    db.put(b"k1", b"v3").unwrap();
+    db.flush().unwrap();
+
+    let livefiles = db.live_files().unwrap();
+    assert_eq!(livefiles.len(), 2);
+    assert!(livefiles
+        .iter()
+        .all(|f| f.level == 0 && f.temperature == Temperature::Unknown));
+
+    db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    let livefiles = db.live_files().unwrap();
+    assert_eq!(livefiles.len(), 1);
+    assert_eq!(livefiles[0].level, 6);
+    assert_eq!(livefiles[0].temperature, Temperature::Cold);
+
+    let metadata = db.get_column_family_metadata();
+    let last_level = metadata.levels.last().unwrap();
+    assert_eq!(last_level.files[0].temperature, Temperature::Cold);
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v3");
+}
+
+#[test]
+fn fifo_temperature_age_test() {
+    let path = DBPath::new("_rust_rocksdb_fifo_temperature_age_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_compaction_style(DBCompactionStyle::Fifo);
+    let mut fifo_opts = FifoCompactOptions::default();
+    fifo_opts.set_file_temperature_age_thresholds(&[FileTemperatureAge {
+        temperature: Temperature::Cold,
+        age: 1,
+    }]);
+    opts.set_fifo_compaction_options(&fifo_opts);
+    let db = DB::open(&opts, &path).unwrap();
+
+    // The age of a file is estimated from the creation time of the next one,
+    // so the first file is old once the second is and a third one triggers
+    // the compaction.
+    db.put(b"k1", b"v1").unwrap();
+    db.flush().unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.flush().unwrap();
+    thread::sleep(Duration::from_secs(2));
+    db.put(b"k3", b"v3").unwrap();
+    db.flush().unwrap();
+    db.wait_for_compact(&WaitForCompactOptions::default())
+        .unwrap();
+
+    let livefiles = db.live_files().unwrap();
+    let temperature = |key: &[u8]| {
+        livefiles
+            .iter()
+            .find(|f| f.start_key.as_deref() == Some(key))
+            .unwrap()
+            .temperature
+    };
+    assert_eq!(temperature(b"k1"), Temperature::Cold);
+    assert_eq!(temperature(b"k3"), Temperature::Unknown);
+
+    let metadata = db.get_column_family_metadata();
+    let files = &metadata.levels[0].files;
+    assert!(files.iter().any(|f| f.temperature == Temperature::Cold));
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+}
+
 #[test]
 fn wait_for_compact_test() {
     let path = DBPath::new("_rust_rocksdb_wait_for_compact_test");
@@ -1361,6 +1573,34 @@ fn delete_range_test() {
     }
 }
 
+#[test]
+fn single_delete_test() {
+    let path = DBPath::new("_rust_rocksdb_single_delete_test");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let cfs = vec!["cf1"];
+        let db = DB::open_cf(&opts, &path, cfs).unwrap();
+
+        db.put(b"k1", b"v1").unwrap();
+        db.single_delete(b"k1").unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+
+        let cf1 = db.cf_handle("cf1").unwrap();
+        db.put_cf(&cf1, b"k2", b"v2").unwrap();
+        db.flush_cf(&cf1).unwrap();
+        db.single_delete_cf(&cf1, b"k2").unwrap();
+        assert!(db.get_cf(&cf1, b"k2").unwrap().is_none());
+
+        // once compacted, neither the value nor the tombstone is left behind
+        db.compact_range_cf(&cf1, None::<&[u8]>, None::<&[u8]>);
+        assert!(db.get_cf(&cf1, b"k2").unwrap().is_none());
+        assert_eq!(db.iterator_cf(&cf1, IteratorMode::Start).count(), 0);
+    }
+}
+
 #[test]
 fn multi_get() {
     let path = DBPath::new("_rust_rocksdb_multi_get");
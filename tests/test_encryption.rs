@@ -0,0 +1,99 @@
+mod util;
+
+use std::fs;
+use std::path::Path;
+
+use rocksdb::{
+    encryption::{BlockCipher, CtrEncryptionProvider},
+    Env, Options, DB,
+};
+use util::DBPath;
+
+/// A toy cipher mixing the block with a key, enough to hide the plaintext.
+struct TestCipher([u8; 16]);
+
+impl BlockCipher for TestCipher {
+    fn block_size(&self) -> usize {
+        16
+    }
+
+    fn encrypt(&self, block: &mut [u8]) {
+        for _ in 0..4 {
+            for i in 0..16 {
+                block[i] = block[i].wrapping_add(self.0[i]).rotate_left(3) ^ block[(i + 1) % 16];
+            }
+        }
+    }
+}
+
+fn encrypted_env() -> Env {
+    let provider = CtrEncryptionProvider::new(TestCipher(*b"0123456789abcdef"), |prefix| {
+        getrandom::fill(prefix).map_err(Into::into)
+    });
+    Env::encrypted(&Env::new().unwrap(), provider).unwrap()
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+fn read_files_matching(dir: impl AsRef<Path>, pattern: &str) -> Vec<Vec<u8>> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with(pattern) || name.ends_with(pattern)
+        })
+        .map(|path| fs::read(path).unwrap())
+        .collect()
+}
+
+#[test]
+fn encrypted_env_hides_plaintext() {
+    let path = DBPath::new("_rust_rocksdb_encryption");
+    let env = encrypted_env();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"flushed-key", b"plaintext flushed to an sst file")
+            .unwrap();
+        db.flush().unwrap();
+        // stays in the WAL
+        db.put(b"logged-key", b"plaintext only in the wal").unwrap();
+    }
+
+    let ssts = read_files_matching(&path, ".sst");
+    let wals = read_files_matching(&path, ".log");
+    let manifests = read_files_matching(&path, "MANIFEST-");
+    assert!(!ssts.is_empty() && !wals.is_empty() && !manifests.is_empty());
+    assert!(ssts
+        .iter()
+        .all(|f| !contains(f, b"flushed-key") && !contains(f, b"plaintext")));
+    assert!(wals
+        .iter()
+        .all(|f| !contains(f, b"logged-key") && !contains(f, b"plaintext")));
+    // the MANIFEST names the comparator of every column family
+    assert!(manifests
+        .iter()
+        .all(|f| !contains(f, b"BytewiseComparator")));
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        assert_eq!(
+            db.get(b"flushed-key").unwrap().unwrap(),
+            b"plaintext flushed to an sst file"
+        );
+        assert_eq!(
+            db.get(b"logged-key").unwrap().unwrap(),
+            b"plaintext only in the wal"
+        );
+    }
+
+    // without the key the database can't be read
+    let plain_opts = Options::default();
+    assert!(DB::open(&plain_opts, &path).is_err());
+    DB::destroy(&opts, &path).unwrap();
+}
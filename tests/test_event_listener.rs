@@ -0,0 +1,109 @@
+mod util;
+
+use std::sync::{Arc, Mutex};
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    event_listener::{
+        CompactionJobInfo, CompactionReason, FlushJobInfo, FlushReason, TableFileCreationInfo,
+        TableFileCreationReason, TableFileDeletionInfo,
+    },
+    EventListener, Options, DB,
+};
+use util::DBPath;
+
+#[derive(Default)]
+struct Events {
+    flushes_begun: Vec<FlushJobInfo>,
+    flushes: Vec<FlushJobInfo>,
+    compactions_begun: Vec<CompactionJobInfo>,
+    compactions: Vec<CompactionJobInfo>,
+    files_created: Vec<TableFileCreationInfo>,
+    files_deleted: Vec<TableFileDeletionInfo>,
+}
+
+struct Recorder(Arc<Mutex<Events>>);
+
+impl EventListener for Recorder {
+    fn on_flush_begin(&self, info: &FlushJobInfo) {
+        self.0.lock().unwrap().flushes_begun.push(info.clone());
+    }
+
+    fn on_flush_completed(&self, info: &FlushJobInfo) {
+        self.0.lock().unwrap().flushes.push(info.clone());
+    }
+
+    fn on_compaction_begin(&self, info: &CompactionJobInfo) {
+        self.0.lock().unwrap().compactions_begun.push(info.clone());
+    }
+
+    fn on_compaction_completed(&self, info: &CompactionJobInfo) {
+        self.0.lock().unwrap().compactions.push(info.clone());
+    }
+
+    fn on_table_file_created(&self, info: &TableFileCreationInfo) {
+        self.0.lock().unwrap().files_created.push(info.clone());
+    }
+
+    fn on_table_file_deleted(&self, info: &TableFileDeletionInfo) {
+        self.0.lock().unwrap().files_deleted.push(info.clone());
+    }
+}
+
+#[test]
+fn event_listener_flush_and_compaction() {
+    let path = DBPath::new("_rust_rocksdb_event_listener");
+    let events = Arc::new(Mutex::new(Events::default()));
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.add_event_listener(Recorder(events.clone()));
+        let db = DB::open(&opts, &path).unwrap();
+
+        db.put(b"k1", b"a").unwrap();
+        db.put(b"k2", b"b").unwrap();
+        db.flush().unwrap();
+        db.put(b"k1", b"c").unwrap();
+        db.flush().unwrap();
+        db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    }
+    // closing the database waits for the background jobs and notifications
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.flushes_begun.len(), 2);
+    assert_eq!(events.flushes.len(), 2);
+    let flush = &events.flushes[0];
+    assert_eq!(flush.cf_name, "default");
+    assert_eq!(flush.flush_reason, FlushReason::ManualFlush);
+    assert!(flush.file_path.ends_with(".sst"));
+    assert!(flush.largest_seqno >= flush.smallest_seqno);
+
+    assert_eq!(events.compactions_begun.len(), 1);
+    assert_eq!(events.compactions.len(), 1);
+    let compaction = &events.compactions[0];
+    assert_eq!(compaction.status, Ok(()));
+    assert_eq!(
+        compaction.compaction_reason,
+        CompactionReason::ManualCompaction
+    );
+    assert_eq!(compaction.input_files.len(), 2);
+    assert_eq!(compaction.output_files.len(), 1);
+    assert_eq!(compaction.num_input_records, 3);
+    assert_eq!(compaction.num_output_records, 2);
+
+    let reasons: Vec<_> = events.files_created.iter().map(|f| f.reason).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            TableFileCreationReason::Flush,
+            TableFileCreationReason::Flush,
+            TableFileCreationReason::Compaction,
+        ]
+    );
+    assert!(events.files_created.iter().all(|f| f.status.is_ok()));
+
+    // the compaction inputs are obsolete
+    assert_eq!(events.files_deleted.len(), 2);
+    assert!(events.files_deleted.iter().all(|f| f.status.is_ok()));
+}
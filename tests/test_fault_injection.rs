@@ -0,0 +1,96 @@
+mod util;
+
+use rocksdb::{
+    fault_injection::{InjectedFault, IoOperation},
+    Env, ErrorKind, FaultInjectionEnv, Options, WriteOptions, DB,
+};
+use util::DBPath;
+
+fn fault_env() -> FaultInjectionEnv {
+    FaultInjectionEnv::new(&Env::new().unwrap()).unwrap()
+}
+
+#[test]
+fn crash_drops_unsynced_writes() {
+    let path = DBPath::new("_rust_rocksdb_fault_injection_crash");
+    let fault_env = fault_env();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(fault_env.env());
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        let mut sync = WriteOptions::default();
+        sync.set_sync(true);
+        db.put_opt(b"k1", b"v1", &sync).unwrap();
+        db.put(b"k2", b"v2").unwrap();
+
+        fault_env.set_filesystem_active(false);
+        assert!(db.put_opt(b"k3", b"v3", &sync).is_err());
+    }
+    fault_env.drop_unsynced_data().unwrap();
+    fault_env.set_filesystem_active(true);
+
+    let faults = fault_env.injected_faults();
+    assert!(faults.iter().any(|f| matches!(
+        f,
+        InjectedFault::DroppedUnsyncedData { path, bytes }
+            if path.to_string_lossy().ends_with(".log") && *bytes > 0
+    )));
+
+    let db = DB::open(&opts, &path).unwrap();
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    assert!(db.get(b"k2").unwrap().is_none());
+    assert!(db.get(b"k3").unwrap().is_none());
+}
+
+#[test]
+fn fail_writes_to_files() {
+    let path = DBPath::new("_rust_rocksdb_fault_injection_files");
+    let fault_env = fault_env();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(fault_env.env());
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+
+    fault_env.fail_files(IoOperation::Write, ".sst");
+    let err = db.flush().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::IOError);
+
+    let faults = fault_env.injected_faults();
+    assert!(!faults.is_empty());
+    assert!(faults.iter().all(|f| matches!(
+        f,
+        InjectedFault::Error { path, operation: IoOperation::Write }
+            if path.to_string_lossy().ends_with(".sst")
+    )));
+}
+
+#[test]
+fn fail_reads_with_probability() {
+    let path = DBPath::new("_rust_rocksdb_fault_injection_reads");
+    let fault_env = fault_env();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(fault_env.env());
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.flush().unwrap();
+    assert!(fault_env.injected_faults().is_empty());
+
+    fault_env.set_seed(42);
+    fault_env.set_failure_probability(IoOperation::Read, 1.0);
+    assert!(db.get(b"k1").is_err());
+    assert!(fault_env.injected_faults().iter().any(|f| matches!(
+        f,
+        InjectedFault::Error {
+            operation: IoOperation::Read,
+            ..
+        }
+    )));
+
+    fault_env.clear_failures();
+    fault_env.clear_injected_faults();
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    assert!(fault_env.injected_faults().is_empty());
+}
@@ -0,0 +1,418 @@
+mod util;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rocksdb::{
+    file_system::{
+        EnvFileSystem, FileLock, FileOptions, RandomAccessFile, SequentialFile, WritableFile,
+    },
+    Env, FileSystem, Options, Temperature, DB,
+};
+use util::DBPath;
+
+#[derive(Default)]
+struct Calls {
+    created: Vec<PathBuf>,
+    opened_for_read: Vec<PathBuf>,
+    opened_dirs: Vec<PathBuf>,
+    file_options: Vec<(PathBuf, FileOptions)>,
+    direct_io_files: usize,
+    syncs: usize,
+}
+
+struct SyncCounter {
+    inner: Box<dyn WritableFile>,
+    calls: Arc<Mutex<Calls>>,
+}
+
+impl WritableFile for SyncCounter {
+    fn append(&mut self, data: &[u8]) -> io::Result<()> {
+        self.inner.append(data)
+    }
+
+    fn positioned_append(&mut self, data: &[u8], offset: u64) -> io::Result<()> {
+        self.inner.positioned_append(data, offset)
+    }
+
+    fn truncate(&mut self, size: u64) -> io::Result<()> {
+        self.inner.truncate(size)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.calls.lock().unwrap().syncs += 1;
+        self.inner.sync()
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.inner.close()
+    }
+
+    fn size(&self) -> u64 {
+        self.inner.size()
+    }
+
+    fn use_direct_io(&self) -> bool {
+        self.inner.use_direct_io()
+    }
+
+    fn required_buffer_alignment(&self) -> usize {
+        self.inner.required_buffer_alignment()
+    }
+}
+
+struct Recording {
+    base: EnvFileSystem,
+    calls: Arc<Mutex<Calls>>,
+}
+
+impl Recording {
+    fn opened(&self, path: &Path, opts: &FileOptions, for_read: bool) {
+        let mut calls = self.calls.lock().unwrap();
+        if for_read {
+            calls.opened_for_read.push(path.to_owned());
+        } else {
+            calls.created.push(path.to_owned());
+        }
+        calls.file_options.push((path.to_owned(), *opts));
+    }
+}
+
+impl FileSystem for Recording {
+    fn new_sequential_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn SequentialFile>> {
+        self.opened(path, opts, true);
+        self.base.new_sequential_file(path, opts)
+    }
+
+    fn new_random_access_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn RandomAccessFile>> {
+        self.opened(path, opts, true);
+        self.base.new_random_access_file(path, opts)
+    }
+
+    fn new_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        self.opened(path, opts, false);
+        let inner = self.base.new_writable_file(path, opts)?;
+        if inner.use_direct_io() {
+            self.calls.lock().unwrap().direct_io_files += 1;
+        }
+        Ok(Box::new(SyncCounter {
+            inner,
+            calls: self.calls.clone(),
+        }))
+    }
+
+    fn reopen_writable_file(
+        &self,
+        path: &Path,
+        opts: &FileOptions,
+    ) -> io::Result<Box<dyn WritableFile>> {
+        self.base.reopen_writable_file(path, opts)
+    }
+
+    fn file_exists(&self, path: &Path) -> io::Result<bool> {
+        self.base.file_exists(path)
+    }
+
+    fn get_children(&self, dir: &Path) -> io::Result<Vec<String>> {
+        self.base.get_children(dir)
+    }
+
+    fn delete_file(&self, path: &Path) -> io::Result<()> {
+        self.base.delete_file(path)
+    }
+
+    fn create_dir(&self, path: &Path) -> io::Result<()> {
+        self.base.create_dir(path)
+    }
+
+    fn create_dir_if_missing(&self, path: &Path) -> io::Result<()> {
+        self.base.create_dir_if_missing(path)
+    }
+
+    fn delete_dir(&self, path: &Path) -> io::Result<()> {
+        self.base.delete_dir(path)
+    }
+
+    fn get_file_size(&self, path: &Path) -> io::Result<u64> {
+        self.base.get_file_size(path)
+    }
+
+    fn get_file_modification_time(&self, path: &Path) -> io::Result<u64> {
+        self.base.get_file_modification_time(path)
+    }
+
+    fn rename_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        self.base.rename_file(src, target)
+    }
+
+    fn link_file(&self, src: &Path, target: &Path) -> io::Result<()> {
+        self.base.link_file(src, target)
+    }
+
+    fn lock_file(&self, path: &Path) -> io::Result<Box<dyn FileLock>> {
+        self.base.lock_file(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> io::Result<bool> {
+        self.base.is_directory(path)
+    }
+
+    fn open_dir(&self, path: &Path) -> io::Result<()> {
+        self.calls.lock().unwrap().opened_dirs.push(path.to_owned());
+        self.base.open_dir(path)
+    }
+
+    fn sync_dir(&self, path: &Path) -> io::Result<()> {
+        self.base.sync_dir(path)
+    }
+
+    fn absolute_path(&self, path: &Path) -> io::Result<PathBuf> {
+        self.base.absolute_path(path)
+    }
+}
+
+fn has_file_with_suffix(paths: &[PathBuf], suffix: &str) -> bool {
+    paths.iter().any(|p| p.to_string_lossy().ends_with(suffix))
+}
+
+#[test]
+fn file_system_sees_database_io() {
+    let path = DBPath::new("_rust_rocksdb_file_system");
+    let calls = Arc::new(Mutex::new(Calls::default()));
+    let env = Env::from_file_system(Recording {
+        base: EnvFileSystem::default(),
+        calls: calls.clone(),
+    })
+    .unwrap();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+    }
+    {
+        let calls = calls.lock().unwrap();
+        assert!(has_file_with_suffix(&calls.created, ".sst"));
+        assert!(has_file_with_suffix(&calls.created, ".log"));
+        assert!(calls.created.iter().any(|p| p
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("MANIFEST-")));
+        assert!(calls.syncs > 0);
+        let db_dir: &Path = (&path).as_ref();
+        assert!(calls.opened_dirs.iter().any(|p| p == db_dir));
+    }
+
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    }
+    let calls = calls.lock().unwrap();
+    assert!(has_file_with_suffix(&calls.opened_for_read, ".sst"));
+    assert!(calls
+        .opened_for_read
+        .iter()
+        .any(|p| p.file_name().unwrap() == "CURRENT"));
+}
+
+#[test]
+fn file_system_passes_direct_io_options() {
+    // direct I/O is not available everywhere, e.g. on tmpfs
+    let probe_dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_file_system_direct_io_probe")
+        .tempdir()
+        .expect("Failed to create temporary path for the direct I/O probe.");
+    let mut direct = FileOptions::default();
+    direct.use_direct_writes = true;
+    match EnvFileSystem::default().new_writable_file(&probe_dir.path().join("probe"), &direct) {
+        Ok(file) if file.use_direct_io() => {}
+        _ => return,
+    }
+
+    let path = DBPath::new("_rust_rocksdb_file_system_direct_io");
+    let calls = Arc::new(Mutex::new(Calls::default()));
+    let env = Env::from_file_system(Recording {
+        base: EnvFileSystem::default(),
+        calls: calls.clone(),
+    })
+    .unwrap();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_use_direct_reads(true);
+    opts.set_use_direct_io_for_flush_and_compaction(true);
+    opts.set_env(&env);
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        for i in 0..100u32 {
+            db.put(i.to_be_bytes(), vec![i as u8; 1000]).unwrap();
+        }
+        db.flush().unwrap();
+    }
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        for i in 0..100u32 {
+            assert_eq!(
+                db.get(i.to_be_bytes()).unwrap().unwrap(),
+                vec![i as u8; 1000]
+            );
+        }
+    }
+
+    let calls = calls.lock().unwrap();
+    let is_sst = |p: &PathBuf| p.to_string_lossy().ends_with(".sst");
+    assert!(calls
+        .file_options
+        .iter()
+        .any(|(p, o)| is_sst(p) && o.use_direct_writes));
+    assert!(calls
+        .file_options
+        .iter()
+        .any(|(p, o)| is_sst(p) && o.use_direct_reads));
+    // the flushed table was written with direct I/O through the wrapper
+    assert!(calls.direct_io_files > 0);
+}
+
+#[test]
+fn file_system_sees_last_level_temperature() {
+    let path = DBPath::new("_rust_rocksdb_file_system_temperature");
+    let calls = Arc::new(Mutex::new(Calls::default()));
+    let env = Env::from_file_system(Recording {
+        base: EnvFileSystem::default(),
+        calls: calls.clone(),
+    })
+    .unwrap();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_last_level_temperature(Temperature::Cold);
+    opts.set_env(&env);
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.flush().unwrap();
+    db.put(b"k1", b"v2").unwrap();
+    db.flush().unwrap();
+    let flushed: Vec<String> = db
+        .live_files()
+        .unwrap()
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+
+    db.compact_range(None::<&[u8]>, None::<&[u8]>);
+    let livefiles = db.live_files().unwrap();
+    assert_eq!(livefiles.len(), 1);
+    assert_eq!(livefiles[0].temperature, Temperature::Cold);
+
+    let calls = calls.lock().unwrap();
+    let temperature_of = |name: &str| {
+        let name = name.trim_start_matches('/');
+        calls
+            .file_options
+            .iter()
+            .find(|(p, _)| p.file_name().unwrap() == name)
+            .unwrap()
+            .1
+            .temperature
+    };
+    // only the files written to the last level are cold
+    assert_eq!(temperature_of(&livefiles[0].name), Temperature::Cold);
+    for name in &flushed {
+        assert_eq!(temperature_of(name), Temperature::Unknown);
+    }
+}
+
+#[test]
+fn file_system_wraps_mem_env() {
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_file_system_mem_env")
+        .tempdir()
+        .expect("Failed to create temporary path for the mem env test.");
+    let path = dir.path().join("db");
+    let mem_env = Env::mem_env().unwrap();
+    let calls = Arc::new(Mutex::new(Calls::default()));
+    let env = Env::from_file_system(Recording {
+        base: EnvFileSystem::new(&mem_env),
+        calls: calls.clone(),
+    })
+    .unwrap();
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(&env);
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+    }
+    {
+        let db = DB::open(&opts, &path).unwrap();
+        assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    }
+    assert!(has_file_with_suffix(&calls.lock().unwrap().created, ".sst"));
+    // nothing reached the disk
+    assert!(!path.exists());
+}
+
+#[test]
+fn env_file_system_reports_missing_files() {
+    let fs = EnvFileSystem::default();
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_env_file_system")
+        .tempdir()
+        .expect("Failed to create temporary path for the env file system test.");
+    let file = dir.path().join("file");
+
+    assert!(!fs.file_exists(&file).unwrap());
+    let err = fs
+        .new_sequential_file(&file, &FileOptions::default())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    let mut writer = fs
+        .new_writable_file(&file, &FileOptions::default())
+        .unwrap();
+    writer.append(b"hello world").unwrap();
+    writer.sync().unwrap();
+    writer.close().unwrap();
+    drop(writer);
+
+    assert!(fs.file_exists(&file).unwrap());
+    assert_eq!(fs.get_file_size(&file).unwrap(), 11);
+    assert_eq!(
+        fs.get_children(dir.path()).unwrap(),
+        vec!["file".to_owned()]
+    );
+
+    let reader = fs
+        .new_random_access_file(&file, &FileOptions::default())
+        .unwrap();
+    let mut buf = [0; 5];
+    assert_eq!(reader.read_at(6, &mut buf).unwrap(), 5);
+    assert_eq!(&buf, b"world");
+
+    fs.open_dir(dir.path()).unwrap();
+    assert!(fs.open_dir(&dir.path().join("missing")).is_err());
+}
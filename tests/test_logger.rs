@@ -0,0 +1,35 @@
+mod util;
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use rocksdb::{LogLevel, Options, DB};
+use util::DBPath;
+
+#[test]
+fn info_logger_receives_log_lines() {
+    let path = DBPath::new("_rust_rocksdb_info_logger");
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        let sink = lines.clone();
+        opts.set_info_logger(LogLevel::Info, move |level: LogLevel, message: &str| {
+            sink.lock().unwrap().push((level, message.to_owned()));
+        });
+        let db = DB::open(&opts, &path).unwrap();
+        db.put(b"k1", b"v1").unwrap();
+        db.flush().unwrap();
+    }
+
+    let lines = lines.lock().unwrap();
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|(level, _)| *level != LogLevel::Debug));
+    assert!(lines
+        .iter()
+        .any(|(_, message)| message.contains("Level-0 flush table")));
+    assert!(lines.iter().all(|(_, message)| !message.ends_with('\n')));
+    // the LOG file is replaced by the logger
+    let db_dir: &Path = (&path).as_ref();
+    assert!(!db_dir.join("LOG").exists());
+}
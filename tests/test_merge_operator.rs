@@ -16,7 +16,9 @@ mod util;
 
 use bincode::{Decode, Encode};
 use pretty_assertions::assert_eq;
-use rocksdb::{merge_operator::MergeFn, DBCompactionStyle, MergeOperands, Options, DB};
+use rocksdb::{
+    merge_operator::MergeFn, DBCompactionStyle, ErrorKind, MergeOperands, Options, ReadOptions, DB,
+};
 use util::DBPath;
 
 const BINCODE_CONFIG: bincode::config::Configuration = bincode::config::standard();
@@ -41,6 +43,87 @@ fn test_provided_merge(
     Some(result)
 }
 
+#[test]
+fn get_merge_operands_test() {
+    let db_path = DBPath::new("_rust_rocksdb_get_merge_operands_test");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_merge_operator_associative("test operator", test_provided_merge);
+
+    let db = DB::open_cf(&opts, &db_path, ["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    db.put_cf(&cf1, b"k1", b"a").unwrap();
+    db.merge_cf(&cf1, b"k1", b"b").unwrap();
+    db.flush_cf(&cf1).unwrap();
+    for operand in ["c", "d", "efg"] {
+        db.merge_cf(&cf1, b"k1", operand).unwrap();
+    }
+
+    // the base value comes first, followed by the operands oldest first
+    let operands = db.get_merge_operands_cf(&cf1, b"k1").unwrap();
+    assert_eq!(
+        operands,
+        vec![
+            b"a".to_vec(),
+            b"b".to_vec(),
+            b"c".to_vec(),
+            b"d".to_vec(),
+            b"efg".to_vec()
+        ]
+    );
+    assert_eq!(db.get_cf(&cf1, b"k1").unwrap().unwrap(), b"abcdefg");
+
+    // more operands than the default buffer holds
+    for i in 0..40 {
+        db.merge(b"k2", format!("{i},")).unwrap();
+    }
+    let operands = db.get_merge_operands(b"k2").unwrap();
+    assert_eq!(operands.len(), 40);
+    assert_eq!(operands[39], b"39,");
+
+    let readopts = ReadOptions::default();
+    assert_eq!(
+        db.get_merge_operands_cf_opt(&cf1, b"k1", &readopts, 5)
+            .unwrap()
+            .len(),
+        5
+    );
+    let err = db
+        .get_merge_operands_cf_opt(&cf1, b"k1", &readopts, 2)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Incomplete);
+    let err = db
+        .get_merge_operands_cf_opt(&cf1, b"k1", &readopts, 0)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+
+    // a huge cap only allocates room for the operands found
+    assert_eq!(
+        db.get_merge_operands_cf_opt(&cf1, b"k1", &readopts, usize::MAX)
+            .unwrap()
+            .len(),
+        5
+    );
+    let default_cf = db.cf_handle("default").unwrap();
+    assert_eq!(
+        db.get_merge_operands_cf_opt(&default_cf, b"k2", &readopts, 40)
+            .unwrap()
+            .len(),
+        40
+    );
+    let err = db
+        .get_merge_operands_cf_opt(&default_cf, b"k2", &readopts, 39)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Incomplete);
+
+    assert!(db
+        .get_merge_operands_cf(&cf1, b"missing")
+        .unwrap()
+        .is_empty());
+}
+
 #[test]
 fn merge_test() {
     use crate::{Options, DB};
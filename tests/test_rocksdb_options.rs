@@ -17,9 +17,11 @@ mod util;
 use std::{fs, io::Read as _};
 
 use rocksdb::checkpoint::Checkpoint;
+use rocksdb::statistics::Ticker;
 use rocksdb::{
-    BlockBasedOptions, BlockBasedTablePinningTier, Cache, DBCompressionType, DataBlockIndexType,
-    Env, LruCacheOptions, Options, ReadOptions, DB,
+    BlockBasedOptions, BlockBasedTablePinningTier, Cache, CacheEntryRole, CompressedSecondaryCache,
+    CompressedSecondaryCacheOptions, DBCompressionType, DataBlockIndexType, Env, IoPriority,
+    LruCacheOptions, Options, RateLimiter, RateLimiterMode, ReadOptions, WriteOptions, DB,
 };
 use util::DBPath;
 
@@ -382,6 +384,43 @@ fn test_set_ratelimiter() {
     }
 }
 
+#[test]
+fn test_shared_rate_limiter() {
+    let path1 = DBPath::new("_shared_rate_limiter_1");
+    let path2 = DBPath::new("_shared_rate_limiter_2");
+    let rate_limiter =
+        RateLimiter::new_with_mode(1024 * 1024, 1000, 10, RateLimiterMode::AllIo, false);
+    assert_eq!(rate_limiter.get_bytes_per_second(), 1024 * 1024);
+    rate_limiter.set_bytes_per_second(10 * 1024 * 1024);
+    assert_eq!(rate_limiter.get_bytes_per_second(), 10 * 1024 * 1024);
+    assert_eq!(rate_limiter.get_total_requests(IoPriority::Total), 0);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_shared_rate_limiter(&rate_limiter);
+    let db1 = DB::open(&opts, &path1).unwrap();
+    let db2 = DB::open(&opts, &path2).unwrap();
+
+    let mut write_opts = WriteOptions::default();
+    write_opts.set_rate_limiter_priority(IoPriority::User);
+    db1.put_opt(b"k1", b"a", &write_opts).unwrap();
+    assert!(rate_limiter.get_total_requests(IoPriority::User) > 0);
+
+    db1.flush().unwrap();
+    let after_first_flush = rate_limiter.get_total_bytes_through(IoPriority::Total);
+    assert!(after_first_flush > 0);
+    db2.put(b"k2", b"b").unwrap();
+    db2.flush().unwrap();
+    assert!(rate_limiter.get_total_bytes_through(IoPriority::Total) > after_first_flush);
+
+    let mut read_opts = ReadOptions::default();
+    read_opts.set_rate_limiter_priority(IoPriority::User);
+    assert_eq!(&*db2.get_opt(b"k2", &read_opts).unwrap().unwrap(), b"b");
+
+    rate_limiter.request(1024, IoPriority::High);
+    assert!(rate_limiter.get_total_bytes_through(IoPriority::High) >= 1024);
+}
+
 #[test]
 fn test_set_blob_cache() {
     let path = DBPath::new("_set_blob_cache");
@@ -435,6 +474,170 @@ fn test_lru_cache_custom_opts() {
     assert_eq!(&*db.get(KEY).unwrap().unwrap(), VALUE);
 }
 
+#[test]
+fn test_cache_entry_stats() {
+    let path = DBPath::new("_rust_rocksdb_cache_entry_stats");
+    let cache = Cache::new_lru_cache(16 * 1024 * 1024);
+    assert_eq!(cache.get_capacity(), 16 * 1024 * 1024);
+    assert!(!cache.has_strict_capacity_limit());
+    assert_eq!(cache.get_occupancy_count(), 0);
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_cache(&cache);
+    block_opts.set_cache_index_and_filter_blocks(true);
+    block_opts.set_bloom_filter(10.0, false);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_block_based_table_factory(&block_opts);
+    let db = DB::open(&opts, &path).unwrap();
+
+    for i in 0..100u32 {
+        db.put(i.to_be_bytes(), b"value").unwrap();
+    }
+    db.flush().unwrap();
+    for i in 0..100u32 {
+        db.get(i.to_be_bytes()).unwrap().unwrap();
+    }
+
+    let stats = cache.entry_stats();
+    let data = stats[&CacheEntryRole::DataBlock];
+    assert!(data.count > 0 && data.charge > 0);
+    assert!(stats[&CacheEntryRole::IndexBlock].count > 0);
+    assert!(stats[&CacheEntryRole::FilterBlock].count > 0);
+    assert_eq!(stats[&CacheEntryRole::BlobValue].count, 0);
+
+    let total_count: usize = stats.values().map(|s| s.count).sum();
+    assert_eq!(total_count, cache.get_occupancy_count());
+}
+
+#[test]
+fn test_compressed_secondary_cache() {
+    let path = DBPath::new("_rust_rocksdb_compressed_secondary_cache");
+
+    let mut secondary_opts = CompressedSecondaryCacheOptions::default();
+    secondary_opts.set_capacity(8 * 1024 * 1024);
+    let secondary_cache = CompressedSecondaryCache::new(&secondary_opts);
+    assert_eq!(secondary_cache.get_capacity(), 8 * 1024 * 1024);
+    assert_eq!(secondary_cache.get_usage(), 0);
+
+    let mut lru_opts = LruCacheOptions::default();
+    lru_opts.set_capacity(64 * 1024);
+    lru_opts.set_num_shard_bits(0);
+    lru_opts.set_secondary_cache(&secondary_cache);
+    let cache = Cache::new_lru_cache_opts(&lru_opts);
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_cache(&cache);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_block_based_table_factory(&block_opts);
+    let db = DB::open(&opts, &path).unwrap();
+
+    let value = [b'v'; 1024];
+    for i in 0..2000u32 {
+        db.put(i.to_be_bytes(), value).unwrap();
+    }
+    db.flush().unwrap();
+
+    // blocks are only kept in the secondary cache once evicted twice
+    for _ in 0..3 {
+        for i in 0..2000u32 {
+            assert_eq!(&*db.get(i.to_be_bytes()).unwrap().unwrap(), value);
+        }
+    }
+    assert!(secondary_cache.get_usage() > 0);
+
+    secondary_cache.set_capacity(1024 * 1024);
+    assert_eq!(secondary_cache.get_capacity(), 1024 * 1024);
+}
+
+#[test]
+fn test_compressed_secondary_cache_promotion() {
+    let path = DBPath::new("_rust_rocksdb_compressed_secondary_cache_promotion");
+
+    let mut secondary_opts = CompressedSecondaryCacheOptions::default();
+    secondary_opts.set_capacity(1024 * 1024);
+    let secondary_cache = CompressedSecondaryCache::new(&secondary_opts);
+
+    let mut lru_opts = LruCacheOptions::default();
+    lru_opts.set_capacity(64 * 1024);
+    lru_opts.set_num_shard_bits(0);
+    lru_opts.set_secondary_cache(&secondary_cache);
+    let mut cache = Cache::new_lru_cache_opts(&lru_opts);
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_cache(&cache);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.enable_statistics();
+    opts.set_block_based_table_factory(&block_opts);
+    let db = DB::open(&opts, &path).unwrap();
+
+    let value = [b'v'; 1024];
+    for i in 0..2000u32 {
+        db.put(i.to_be_bytes(), value).unwrap();
+    }
+    db.flush().unwrap();
+    let read_all = || {
+        for i in 0..2000u32 {
+            assert_eq!(&*db.get(i.to_be_bytes()).unwrap().unwrap(), value);
+        }
+    };
+
+    for _ in 0..3 {
+        read_all();
+    }
+    let usage = secondary_cache.get_usage();
+    assert!(usage > 0);
+
+    // With room in the primary cache, the blocks found in the secondary
+    // cache are promoted and stay there decompressed, ~2MB in total, which
+    // is not memory of the secondary cache.
+    cache.set_capacity(16 * 1024 * 1024);
+    for _ in 0..3 {
+        read_all();
+    }
+    assert!(opts.get_ticker_count(Ticker::SecondaryCacheHits) > 0);
+    assert!(secondary_cache.get_usage() <= usage);
+    assert!(secondary_cache.get_usage() <= secondary_cache.get_capacity());
+}
+
+#[test]
+fn test_hyper_clock_cache_with_secondary_cache() {
+    let path = DBPath::new("_rust_rocksdb_hyper_clock_secondary_cache");
+
+    let mut secondary_opts = CompressedSecondaryCacheOptions::default();
+    secondary_opts.set_capacity(4 * 1024 * 1024);
+    secondary_opts.set_compression_type(DBCompressionType::None);
+    let secondary_cache = CompressedSecondaryCache::new(&secondary_opts);
+    let cache =
+        Cache::new_hyper_clock_cache_with_secondary_cache(64 * 1024, 4 * 1024, &secondary_cache);
+
+    let mut block_opts = BlockBasedOptions::default();
+    block_opts.set_block_cache(&cache);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.enable_statistics();
+    opts.set_block_based_table_factory(&block_opts);
+    let db = DB::open(&opts, &path).unwrap();
+
+    let value = [b'v'; 1024];
+    for i in 0..2000u32 {
+        db.put(i.to_be_bytes(), value).unwrap();
+    }
+    db.flush().unwrap();
+
+    // A block is only kept by the secondary cache on its second eviction
+    // from the primary cache, so it can be found there on the third pass.
+    for _ in 0..3 {
+        for i in 0..2000u32 {
+            assert_eq!(&*db.get(i.to_be_bytes()).unwrap().unwrap(), value);
+        }
+    }
+    assert!(opts.get_ticker_count(Ticker::SecondaryCacheHits) > 0);
+    assert!(secondary_cache.get_usage() > 0);
+}
+
 #[test]
 fn test_set_write_dbid_to_manifest() {
     let path = DBPath::new("_set_write_dbid_to_manifest");
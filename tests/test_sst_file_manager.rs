@@ -0,0 +1,68 @@
+mod util;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{Env, ErrorKind, ErrorSubKind, Options, SstFileManager, DB};
+use util::DBPath;
+
+fn sst_file_manager() -> SstFileManager {
+    SstFileManager::new(&Env::new().unwrap()).unwrap()
+}
+
+#[test]
+fn sst_file_manager_tracks_files() {
+    let path = DBPath::new("_rust_rocksdb_sst_file_manager_tracks");
+    let sfm = sst_file_manager();
+    assert_eq!(sfm.get_total_size(), 0);
+    assert!(sfm.get_tracked_files().is_empty());
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_sst_file_manager(&sfm);
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.flush().unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.flush().unwrap();
+
+    let files = sfm.get_tracked_files();
+    assert_eq!(files.len(), 2);
+    assert!(files.keys().all(|name| name.ends_with(".sst")));
+    assert_eq!(files.values().sum::<u64>(), sfm.get_total_size());
+    assert!(!sfm.is_max_allowed_space_reached());
+    assert_eq!(sfm.get_total_trash_size(), 0);
+}
+
+#[test]
+fn sst_file_manager_space_limit() {
+    let path = DBPath::new("_rust_rocksdb_sst_file_manager_space_limit");
+    let sfm = sst_file_manager();
+    sfm.set_max_allowed_space_usage(1);
+
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_sst_file_manager(&sfm);
+    let db = DB::open(&opts, &path).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    // the flushed file pushes the database over the limit
+    let err = db.flush().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::IOError);
+    assert_eq!(err.sub_kind(), ErrorSubKind::SpaceLimit);
+    assert!(sfm.is_max_allowed_space_reached());
+}
+
+#[test]
+fn sst_file_manager_settings() {
+    let sfm = sst_file_manager();
+    assert_eq!(sfm.get_delete_rate_bytes_per_second(), 0);
+    sfm.set_delete_rate_bytes_per_second(1024 * 1024);
+    assert_eq!(sfm.get_delete_rate_bytes_per_second(), 1024 * 1024);
+
+    sfm.set_max_trash_db_ratio(0.5);
+    assert_eq!(sfm.get_max_trash_db_ratio(), 0.5);
+
+    sfm.set_max_allowed_space_usage(1024);
+    sfm.set_compaction_buffer_size(512);
+    assert!(!sfm.is_max_allowed_space_reached());
+    assert!(!sfm.is_max_allowed_space_reached_including_compactions());
+}
@@ -0,0 +1,129 @@
+mod util;
+
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    table_properties::{
+        EntryType, TablePropertiesCollector, TablePropertiesCollectorContext,
+        TablePropertiesCollectorFactory, UserCollectedProperties,
+    },
+    Options, Range, DB,
+};
+use util::DBPath;
+
+/// Counts the puts and deletes of every file.
+#[derive(Default)]
+struct CountingCollector {
+    puts: u64,
+    deletes: u64,
+}
+
+impl TablePropertiesCollector for CountingCollector {
+    fn add_user_key(
+        &mut self,
+        _key: &[u8],
+        _value: &[u8],
+        entry_type: EntryType,
+        _seq: u64,
+        _file_size: u64,
+    ) {
+        match entry_type {
+            EntryType::Put => self.puts += 1,
+            EntryType::Delete => self.deletes += 1,
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self) -> UserCollectedProperties {
+        let mut props = UserCollectedProperties::new();
+        props.insert("test.puts".to_owned(), self.puts.to_le_bytes().to_vec());
+        props.insert(
+            "test.deletes".to_owned(),
+            self.deletes.to_le_bytes().to_vec(),
+        );
+        props
+    }
+}
+
+struct CountingFactory {
+    created: Arc<AtomicUsize>,
+}
+
+impl TablePropertiesCollectorFactory for CountingFactory {
+    type Collector = CountingCollector;
+
+    fn create(&self, _context: TablePropertiesCollectorContext) -> CountingCollector {
+        self.created.fetch_add(1, Ordering::SeqCst);
+        CountingCollector::default()
+    }
+
+    fn name(&self) -> &CStr {
+        c"CountingFactory"
+    }
+}
+
+fn read_count(props: &UserCollectedProperties, name: &str) -> u64 {
+    u64::from_le_bytes(props[name].as_slice().try_into().unwrap())
+}
+
+#[test]
+fn table_properties_collector() {
+    let path = DBPath::new("_rust_rocksdb_table_properties_collector");
+    let created = Arc::new(AtomicUsize::new(0));
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.add_table_properties_collector_factory(CountingFactory {
+        created: created.clone(),
+    });
+    let db = DB::open(&opts, &path).unwrap();
+
+    db.put(b"a", b"1").unwrap();
+    db.put(b"b", b"2").unwrap();
+    db.delete(b"c").unwrap();
+    db.flush().unwrap();
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+
+    let tables = db.get_properties_of_all_tables().unwrap();
+    assert_eq!(tables.len(), 1);
+    let (file, props) = tables.iter().next().unwrap();
+    assert!(file.ends_with(".sst"));
+    assert_eq!(props.num_entries, 3);
+    assert_eq!(props.num_deletions, 1);
+    assert_eq!(props.column_family_name, "default");
+    assert_eq!(read_count(&props.user_collected_properties, "test.puts"), 2);
+    assert_eq!(
+        read_count(&props.user_collected_properties, "test.deletes"),
+        1
+    );
+}
+
+#[test]
+fn table_properties_in_range() {
+    let path = DBPath::new("_rust_rocksdb_table_properties_in_range");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf"]).unwrap();
+    let cf = db.cf_handle("cf").unwrap();
+
+    db.put_cf(&cf, b"a1", b"v").unwrap();
+    db.put_cf(&cf, b"a2", b"v").unwrap();
+    db.flush_cf(&cf).unwrap();
+    db.put_cf(&cf, b"b1", b"v").unwrap();
+    db.flush_cf(&cf).unwrap();
+
+    let all = db.get_properties_of_all_tables_cf(&cf).unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.values().all(|props| props.column_family_name == "cf"));
+    assert!(db.get_properties_of_all_tables().unwrap().is_empty());
+
+    let in_range = db
+        .get_properties_of_tables_in_range_cf(&cf, &[Range::new(b"a", b"b")])
+        .unwrap();
+    assert_eq!(in_range.len(), 1);
+    assert_eq!(in_range.values().next().unwrap().num_entries, 2);
+}
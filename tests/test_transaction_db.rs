@@ -18,11 +18,12 @@ mod util;
 use pretty_assertions::assert_eq;
 
 use rocksdb::{
-    CuckooTableOptions, DBAccess, Direction, Error, ErrorKind, IteratorMode, Options, ReadOptions,
-    SliceTransform, TransactionDB, TransactionDBOptions, TransactionOptions,
+    ColumnFamilyDescriptor, CuckooTableOptions, DBAccess, Direction, Env, Error, ErrorKind,
+    FaultInjectionEnv, IteratorMode, Options, ReadOptions, SliceTransform, Transaction,
+    TransactionDB, TransactionDBOptions, TransactionOptions, TxnDBWritePolicy,
     WriteBatchWithTransaction, WriteOptions, DB,
 };
-use util::DBPath;
+use util::{DBPath, U64Comparator, U64Timestamp};
 
 #[test]
 fn open_default() {
@@ -574,6 +575,41 @@ fn transaction_cf() {
     }
 }
 
+#[test]
+fn single_delete() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_single_delete");
+    {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+        let db: TransactionDB =
+            TransactionDB::open_cf(&opts, &TransactionDBOptions::default(), &path, ["cf1"])
+                .unwrap();
+        let cf1 = db.cf_handle("cf1").unwrap();
+
+        db.put(b"k1", b"v1").unwrap();
+        db.single_delete(b"k1").unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+
+        db.put_cf(&cf1, b"k2", b"v2").unwrap();
+        db.single_delete_cf(&cf1, b"k2").unwrap();
+        assert!(db.get_cf(&cf1, b"k2").unwrap().is_none());
+
+        db.put(b"k3", b"v3").unwrap();
+        db.put_cf(&cf1, b"k4", b"v4").unwrap();
+        let txn = db.transaction();
+        txn.single_delete(b"k3").unwrap();
+        txn.single_delete_cf(&cf1, b"k4").unwrap();
+        assert!(txn.get(b"k3").unwrap().is_none());
+        assert!(txn.get_cf(&cf1, b"k4").unwrap().is_none());
+        assert_eq!(db.get(b"k3").unwrap().unwrap(), b"v3");
+        txn.commit().unwrap();
+
+        assert!(db.get(b"k3").unwrap().is_none());
+        assert!(db.get_cf(&cf1, b"k4").unwrap().is_none());
+    }
+}
+
 #[test]
 fn transaction_snapshot() {
     let path = DBPath::new("_rust_rocksdb_transaction_db_transaction_snapshot");
@@ -676,6 +712,412 @@ fn two_phase_commit() {
     }
 }
 
+#[test]
+fn user_defined_timestamps() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_user_defined_timestamps");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let mut cf_opts = Options::default();
+    cf_opts.set_comparator_with_ts(
+        U64Comparator::NAME,
+        U64Timestamp::SIZE,
+        Box::new(U64Comparator::compare),
+        Box::new(U64Comparator::compare_ts),
+        Box::new(U64Comparator::compare_without_ts),
+    );
+    let cfs = vec![ColumnFamilyDescriptor::new("cf", cf_opts)];
+    let db: TransactionDB =
+        TransactionDB::open_cf_descriptors(&opts, &TransactionDBOptions::default(), &path, cfs)
+            .unwrap();
+    let cf = db.cf_handle("cf").unwrap();
+
+    let txn = db.transaction();
+    assert_eq!(txn.get_commit_timestamp(), None);
+    // a timestamp of the wrong size is rejected before anything is written
+    let err = txn.put_cf_with_ts(cf, b"k1", [2u8], b"v1").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(txn.get_commit_timestamp(), None);
+    txn.put_cf_with_ts(cf, b"k1", U64Timestamp::new(2), b"v1")
+        .unwrap();
+    assert_eq!(txn.get_commit_timestamp(), Some(2));
+    // all the keys of a transaction get the same timestamp
+    let err = txn
+        .put_cf_with_ts(cf, b"k2", U64Timestamp::new(3), b"v2")
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    txn.put_cf_with_ts(cf, b"k2", U64Timestamp::new(2), b"v2")
+        .unwrap();
+    txn.commit().unwrap();
+
+    db.put_cf_with_ts(cf, b"k1", U64Timestamp::new(4), b"v3")
+        .unwrap();
+    let mut sync = WriteOptions::default();
+    sync.set_sync(true);
+    db.put_cf_with_ts_opt(cf, b"k3", U64Timestamp::new(4), b"v5", &sync)
+        .unwrap();
+
+    let mut readopts = ReadOptions::default();
+    readopts.set_timestamp(U64Timestamp::new(1));
+    assert!(db.get_cf_opt(cf, b"k1", &readopts).unwrap().is_none());
+    assert!(db.get_cf_with_ts(cf, b"k1", &readopts).unwrap().is_none());
+    readopts.set_timestamp(U64Timestamp::new(3));
+    assert_eq!(db.get_cf_opt(cf, b"k1", &readopts).unwrap().unwrap(), b"v1");
+    assert_eq!(db.get_cf_opt(cf, b"k2", &readopts).unwrap().unwrap(), b"v2");
+    assert_eq!(
+        db.get_cf_with_ts(cf, b"k1", &readopts).unwrap().unwrap(),
+        (b"v1".to_vec(), U64Timestamp::new(2).into())
+    );
+    assert!(db.get_cf_with_ts(cf, b"k3", &readopts).unwrap().is_none());
+    readopts.set_timestamp(U64Timestamp::new(4));
+    assert_eq!(
+        db.get_cf_with_ts(cf, b"k1", &readopts).unwrap().unwrap(),
+        (b"v3".to_vec(), U64Timestamp::new(4).into())
+    );
+    assert_eq!(
+        db.get_cf_with_ts(cf, b"k3", &readopts).unwrap().unwrap(),
+        (b"v5".to_vec(), U64Timestamp::new(4).into())
+    );
+
+    // transactions read at the timestamp of the read options
+    let txn = db.transaction();
+    readopts.set_timestamp(U64Timestamp::new(3));
+    assert_eq!(
+        txn.get_cf_opt(cf, b"k1", &readopts).unwrap().unwrap(),
+        b"v1"
+    );
+    readopts.set_timestamp(U64Timestamp::new(4));
+    assert_eq!(
+        txn.get_cf_opt(cf, b"k1", &readopts).unwrap().unwrap(),
+        b"v3"
+    );
+    txn.rollback().unwrap();
+
+    // k1 was written at 4, after the validation timestamp
+    let txn = db.transaction();
+    txn.set_read_timestamp_for_validation(3).unwrap();
+    let err = txn
+        .get_for_update_cf_opt(cf, b"k1", true, &ReadOptions::default())
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Busy);
+    // the read timestamp must be the validation one
+    let err = txn
+        .get_for_update_cf_opt(cf, b"k2", true, &readopts)
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidArgument);
+    readopts.set_timestamp(U64Timestamp::new(3));
+    assert_eq!(
+        txn.get_for_update_cf_opt(cf, b"k2", true, &readopts)
+            .unwrap()
+            .unwrap(),
+        b"v2"
+    );
+    txn.set_commit_timestamp(5).unwrap();
+    txn.put_cf(cf, b"k2", b"v4").unwrap();
+    txn.commit().unwrap();
+
+    readopts.set_timestamp(U64Timestamp::new(5));
+    assert_eq!(
+        db.get_cf_with_ts(cf, b"k2", &readopts).unwrap().unwrap(),
+        (b"v4".to_vec(), U64Timestamp::new(5).into())
+    );
+}
+
+#[test]
+fn lock_status() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_lock_status");
+    let db: TransactionDB = TransactionDB::open_default(&path).unwrap();
+    assert!(db.lock_status().is_empty());
+
+    let txn1 = db.transaction();
+    let txn2 = db.transaction();
+    let txn3 = db.transaction();
+    assert_ne!(txn1.id(), txn2.id());
+    txn1.put(b"k1", b"v1").unwrap();
+    txn2.get_for_update(b"k2", false).unwrap();
+    txn3.get_for_update(b"k2", false).unwrap();
+
+    let mut locks = db.lock_status();
+    locks.sort_by(|a, b| a.key.cmp(&b.key));
+    assert_eq!(locks.len(), 2);
+    assert_eq!(locks[0].column_family_id, 0);
+    assert_eq!(locks[0].key, b"k1");
+    assert_eq!(locks[0].txn_ids, [txn1.id()]);
+    assert!(locks[0].exclusive);
+    assert_eq!(locks[1].key, b"k2");
+    let mut ids = locks[1].txn_ids.clone();
+    ids.sort_unstable();
+    let mut want = vec![txn2.id(), txn3.id()];
+    want.sort_unstable();
+    assert_eq!(ids, want);
+    assert!(!locks[1].exclusive);
+
+    assert_eq!(txn1.wait_handle().waiting_txns(), None);
+    assert!(txn1.elapsed_time() < std::time::Duration::from_secs(60));
+
+    txn1.commit().unwrap();
+    txn2.rollback().unwrap();
+    txn3.rollback().unwrap();
+    assert!(db.lock_status().is_empty());
+}
+
+#[test]
+fn waiting_txns() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_waiting_txns");
+    let db: TransactionDB = TransactionDB::open_default(&path).unwrap();
+
+    let mut txn_opts = TransactionOptions::default();
+    txn_opts.set_lock_timeout(10_000);
+    let txn1 = db.transaction();
+    let txn2 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+    let txn1_id = txn1.id();
+    txn1.put(b"k1", b"v1").unwrap();
+    let handle = txn2.wait_handle();
+    assert_eq!(handle.waiting_txns(), None);
+
+    let waiting = std::thread::scope(|s| {
+        // lets txn2 through once it was seen waiting on txn1
+        let waiter = s.spawn(move || {
+            let mut waiting = None;
+            for _ in 0..1000 {
+                waiting = handle.waiting_txns();
+                if waiting.is_some() {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+            txn1.commit().unwrap();
+            waiting
+        });
+        txn2.put(b"k1", b"v2").unwrap();
+        waiter.join().unwrap()
+    });
+    let waiting = waiting.unwrap();
+    assert_eq!(waiting.column_family_id, 0);
+    assert_eq!(waiting.key, b"k1");
+    assert_eq!(waiting.txn_ids, [txn1_id]);
+
+    assert_eq!(handle.waiting_txns(), None);
+    txn2.commit().unwrap();
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v2");
+}
+
+#[test]
+fn deadlock_info() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_deadlock_info");
+    let db: TransactionDB = TransactionDB::open_default(&path).unwrap();
+    assert!(db.deadlock_info().is_empty());
+
+    let mut txn_opts = TransactionOptions::default();
+    txn_opts.set_deadlock_detect(true);
+    let txn1 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+    let txn2 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+    let ids = [txn1.id(), txn2.id()];
+    txn1.put(b"k1", b"v1").unwrap();
+    txn2.put(b"k2", b"v2").unwrap();
+
+    // whichever of the two transactions requests its second lock last
+    // detects the deadlock and gives up, letting the other one through
+    fn put_and_finish(txn: Transaction<'_, TransactionDB>, key: &[u8]) -> Result<(), Error> {
+        let result = txn.put(key, b"v");
+        match result {
+            Ok(()) => txn.commit().unwrap(),
+            Err(_) => txn.rollback().unwrap(),
+        }
+        result
+    }
+    let (result1, result2) = std::thread::scope(|s| {
+        let handle = s.spawn(|| put_and_finish(txn1, b"k2"));
+        let result2 = put_and_finish(txn2, b"k1");
+        (handle.join().unwrap(), result2)
+    });
+    let errors: Vec<_> = [result1, result2]
+        .into_iter()
+        .filter_map(Result::err)
+        .collect();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind(), ErrorKind::Busy);
+
+    let deadlocks = db.deadlock_info();
+    assert_eq!(deadlocks.len(), 1);
+    assert!(!deadlocks[0].limit_exceeded);
+    assert!(deadlocks[0].deadlock_time > 0);
+    let mut path_ids: Vec<_> = deadlocks[0].path.iter().map(|info| info.txn_id).collect();
+    path_ids.sort_unstable();
+    let mut want = ids.to_vec();
+    want.sort_unstable();
+    assert_eq!(path_ids, want);
+    assert!(deadlocks[0].path.iter().all(|info| info.exclusive));
+}
+
+#[test]
+fn deadlock_path_order() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_deadlock_path_order");
+    let db: TransactionDB = TransactionDB::open_default(&path).unwrap();
+
+    let mut txn_opts = TransactionOptions::default();
+    txn_opts.set_deadlock_detect(true);
+    let txn1 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+    let txn2 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+    let txn3 = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+    let ids = [txn1.id(), txn2.id(), txn3.id()];
+    txn1.put(b"k1", b"v1").unwrap();
+    txn2.put(b"k2", b"v2").unwrap();
+    txn3.put(b"k3", b"v3").unwrap();
+
+    // txn1 waits on txn2, then txn2 on txn3, and txn3 closes the cycle
+    std::thread::scope(|s| {
+        let handle1 = s.spawn(move || {
+            txn1.put(b"k2", b"v").unwrap();
+            txn1.commit().unwrap();
+        });
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let handle2 = s.spawn(move || {
+            txn2.put(b"k3", b"v").unwrap();
+            txn2.commit().unwrap();
+        });
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let err = txn3.put(b"k1", b"v").unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Busy);
+        txn3.rollback().unwrap();
+        handle2.join().unwrap();
+        handle1.join().unwrap();
+    });
+
+    let deadlocks = db.deadlock_info();
+    assert_eq!(deadlocks.len(), 1);
+    let path = &deadlocks[0].path;
+    let path_ids: Vec<_> = path.iter().map(|info| info.txn_id).collect();
+    assert_eq!(path_ids, ids);
+    let keys: Vec<_> = path
+        .iter()
+        .map(|info| info.waiting_key.as_slice())
+        .collect();
+    assert_eq!(keys, [b"k2", b"k3", b"k1"]);
+}
+
+fn write_policy_recovery(name: &str, policy: TxnDBWritePolicy) {
+    let path = DBPath::new(name);
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    let mut txn_db_opts = TransactionDBOptions::default();
+    txn_db_opts.set_write_policy(policy);
+    {
+        let db: TransactionDB = TransactionDB::open(&opts, &txn_db_opts, &path).unwrap();
+        db.put(b"k0", b"v0").unwrap();
+
+        let txn = db.transaction();
+        txn.set_name(b"t1").unwrap();
+        txn.put(b"k1", b"v1").unwrap();
+        txn.put(b"k2", b"v2").unwrap();
+        assert_eq!(txn.get(b"k1").unwrap().unwrap(), b"v1");
+        assert!(db.get(b"k1").unwrap().is_none());
+        txn.prepare().unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+        assert!(db.transaction().get(b"k2").unwrap().is_none());
+
+        let txn = db.transaction();
+        txn.put(b"k3", b"v3").unwrap();
+        txn.commit().unwrap();
+        assert_eq!(db.get(b"k3").unwrap().unwrap(), b"v3");
+    }
+
+    {
+        let db: TransactionDB = TransactionDB::open(&opts, &txn_db_opts, &path).unwrap();
+        assert!(db.get(b"k1").unwrap().is_none());
+        let txns = db.prepared_transactions();
+        assert_eq!(txns.len(), 1);
+        for txn in txns {
+            assert_eq!(txn.get_name().unwrap(), b"t1");
+            txn.commit().unwrap();
+        }
+        assert_eq!(db.get(b"k0").unwrap().unwrap(), b"v0");
+        assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+        assert_eq!(db.get(b"k2").unwrap().unwrap(), b"v2");
+        assert_eq!(db.get(b"k3").unwrap().unwrap(), b"v3");
+    }
+}
+
+#[test]
+fn write_prepared_policy() {
+    write_policy_recovery(
+        "_rust_rocksdb_transaction_db_write_prepared",
+        TxnDBWritePolicy::WritePrepared,
+    );
+}
+
+#[test]
+fn write_unprepared_policy() {
+    write_policy_recovery(
+        "_rust_rocksdb_transaction_db_write_unprepared",
+        TxnDBWritePolicy::WriteUnprepared,
+    );
+}
+
+#[test]
+fn write_unprepared_flush_threshold() {
+    let path = DBPath::new("_rust_rocksdb_transaction_db_write_unprepared_flush");
+    let fault_env = FaultInjectionEnv::new(&Env::new().unwrap()).unwrap();
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.set_env(fault_env.env());
+    let mut txn_db_opts = TransactionDBOptions::default();
+    txn_db_opts.set_write_policy(TxnDBWritePolicy::WriteUnprepared);
+    txn_db_opts.set_default_write_batch_flush_threshold(1024);
+
+    fn put_past_threshold(txn: &Transaction<'_, TransactionDB>, prefix: &str) {
+        for i in 0..8 {
+            txn.put(format!("{prefix}{i}"), [b'v'; 512]).unwrap();
+        }
+    }
+    fn memtable_entries(db: &TransactionDB) -> u64 {
+        db.property_int_value("rocksdb.num-entries-active-mem-table")
+            .unwrap()
+            .unwrap()
+    }
+
+    {
+        let db: TransactionDB = TransactionDB::open(&opts, &txn_db_opts, &path).unwrap();
+
+        let txn = db.transaction();
+        put_past_threshold(&txn, "a");
+        // the writes reached the memtable but stay invisible to others
+        assert!(memtable_entries(&db) > 0);
+        assert_eq!(txn.get(b"a0").unwrap().unwrap(), [b'v'; 512]);
+        assert!(db.get(b"a0").unwrap().is_none());
+        assert!(db.transaction().get(b"a7").unwrap().is_none());
+        txn.rollback().unwrap();
+        assert!(db.get(b"a0").unwrap().is_none());
+        assert!(db.get(b"a7").unwrap().is_none());
+
+        // a transaction can lift the threshold of the database
+        let entries = memtable_entries(&db);
+        let mut txn_opts = TransactionOptions::default();
+        txn_opts.set_write_batch_flush_threshold(0);
+        let txn = db.transaction_opt(&WriteOptions::default(), &txn_opts);
+        put_past_threshold(&txn, "b");
+        assert_eq!(memtable_entries(&db), entries);
+        txn.commit().unwrap();
+        assert_eq!(db.get(b"b7").unwrap().unwrap(), [b'v'; 512]);
+
+        // crash in the middle of a transaction, before it is rolled back
+        let mut sync = WriteOptions::default();
+        sync.set_sync(true);
+        let txn = db.transaction_opt(&sync, &TransactionOptions::default());
+        put_past_threshold(&txn, "c");
+        fault_env.set_filesystem_active(false);
+        drop(txn);
+    }
+    fault_env.set_filesystem_active(true);
+
+    let db: TransactionDB = TransactionDB::open(&opts, &txn_db_opts, &path).unwrap();
+    assert!(db.prepared_transactions().is_empty());
+    assert!(db.get(b"a0").unwrap().is_none());
+    assert_eq!(db.get(b"b0").unwrap().unwrap(), [b'v'; 512]);
+    assert!(db.get(b"c0").unwrap().is_none());
+    assert!(db.get(b"c7").unwrap().is_none());
+}
+
 #[test]
 fn test_snapshot_outlive_transaction_db() {
     let t = trybuild::TestCases::new();
@@ -0,0 +1,128 @@
+mod util;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    IteratorMode, Options, SstFileWriter, WideColumns, WriteBatch, DB, DEFAULT_WIDE_COLUMN_NAME,
+};
+use util::DBPath;
+
+fn entity(columns: &[(&str, &str)]) -> WideColumns {
+    columns.iter().copied().collect()
+}
+
+#[test]
+fn put_and_get_entity() {
+    let path = DBPath::new("_rust_rocksdb_wide_columns_put_get");
+    let db = DB::open_default(&path).unwrap();
+
+    // RocksDB keeps the columns sorted by name
+    db.put_entity(b"k1", &entity(&[("b", "2"), ("a", "1")]))
+        .unwrap();
+    assert_eq!(
+        db.get_entity(b"k1").unwrap(),
+        Some(entity(&[("a", "1"), ("b", "2")]))
+    );
+    assert_eq!(db.get_entity(b"missing").unwrap(), None);
+
+    // plain values are exposed through the default column and vice versa
+    db.put(b"k2", b"plain").unwrap();
+    assert_eq!(
+        db.get_entity(b"k2").unwrap().unwrap().default_value(),
+        Some(&b"plain"[..])
+    );
+    db.put_entity(b"k3", &entity(&[("", "anonymous"), ("x", "y")]))
+        .unwrap();
+    assert_eq!(db.get(b"k3").unwrap().unwrap(), b"anonymous");
+}
+
+#[test]
+fn put_and_get_entity_cf() {
+    let path = DBPath::new("_rust_rocksdb_wide_columns_cf");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf1"]).unwrap();
+    let cf = db.cf_handle("cf1").unwrap();
+
+    db.put_entity_cf(&cf, b"k", &entity(&[("c", "3")])).unwrap();
+    assert_eq!(
+        db.get_entity_cf(&cf, b"k").unwrap(),
+        Some(entity(&[("c", "3")]))
+    );
+    assert_eq!(db.get_entity(b"k").unwrap(), None);
+}
+
+#[test]
+fn write_batch_put_entity() {
+    let path = DBPath::new("_rust_rocksdb_wide_columns_write_batch");
+    let db = DB::open_default(&path).unwrap();
+    let cf = db.cf_handle("default").unwrap();
+
+    let mut batch = WriteBatch::default();
+    batch
+        .put_entity_cf(&cf, b"k1", &entity(&[("a", "1")]))
+        .unwrap();
+    batch.put(b"k2", b"v2");
+    assert_eq!(batch.len(), 2);
+    db.write(batch).unwrap();
+
+    assert_eq!(db.get_entity(b"k1").unwrap(), Some(entity(&[("a", "1")])));
+
+    let mut batch = WriteBatch::default();
+    assert!(batch
+        .put_entity_cf(&cf, b"k3", &entity(&[("a", "1"), ("a", "2")]))
+        .is_err());
+}
+
+#[test]
+fn iterator_columns() {
+    let path = DBPath::new("_rust_rocksdb_wide_columns_iterator");
+    let db = DB::open_default(&path).unwrap();
+    db.put_entity(b"k1", &entity(&[("a", "1"), ("b", "2")]))
+        .unwrap();
+    db.put(b"k2", b"v2").unwrap();
+
+    let mut iter = db.raw_iterator();
+    iter.seek_to_first();
+    assert_eq!(iter.key(), Some(&b"k1"[..]));
+    assert_eq!(iter.columns(), Some(entity(&[("a", "1"), ("b", "2")])));
+
+    iter.next();
+    let columns = iter.columns().unwrap();
+    assert_eq!(columns.len(), 1);
+    assert_eq!(columns.get(DEFAULT_WIDE_COLUMN_NAME), Some(&b"v2"[..]));
+
+    iter.next();
+    assert_eq!(iter.columns(), None);
+
+    assert_eq!(db.iterator(IteratorMode::Start).count(), 2);
+}
+
+#[test]
+fn sst_file_writer_put_entity() {
+    let path = DBPath::new("_rust_rocksdb_wide_columns_sst_file_writer");
+    let dir = tempfile::Builder::new()
+        .prefix("_rust_rocksdb_wide_columns_sst_file_writer")
+        .tempdir()
+        .expect("Failed to create temporary path for file writer.");
+    let writer_path = dir.path().join("filewriter");
+    {
+        let opts = Options::default();
+        let mut writer = SstFileWriter::create(&opts);
+        writer.open(&writer_path).unwrap();
+        writer
+            .put_entity(b"k1", &entity(&[("a", "1"), ("b", "2")]))
+            .unwrap();
+        writer.put(b"k2", b"v2").unwrap();
+        writer.finish().unwrap();
+    }
+
+    let db = DB::open_default(&path).unwrap();
+    db.ingest_external_file(vec![&writer_path]).unwrap();
+    assert_eq!(
+        db.get_entity(b"k1").unwrap(),
+        Some(entity(&[("a", "1"), ("b", "2")]))
+    );
+    assert_eq!(db.get(b"k2").unwrap().unwrap(), b"v2");
+}
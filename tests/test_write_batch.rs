@@ -17,7 +17,10 @@ use std::collections::HashMap;
 
 use pretty_assertions::assert_eq;
 
-use rocksdb::{Error, WriteBatch, WriteBatchIterator, WriteBatchIteratorCf, DB};
+use rocksdb::{
+    Error, ErrorKind, Options, WideColumns, WriteBatch, WriteBatchIterator, WriteBatchIteratorCf,
+    WriteBatchOp, DB,
+};
 use util::DBPath;
 
 #[test]
@@ -30,6 +33,45 @@ fn test_write_batch_clear() {
     assert!(batch.is_empty());
 }
 
+#[test]
+fn test_write_batch_save_points() {
+    let mut batch = WriteBatch::default();
+    let err = batch.rollback_to_save_point().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    let err = batch.pop_save_point().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    batch.put(b"k1", b"v1");
+    let len = batch.len();
+    let data = batch.data().to_vec();
+    batch.set_save_point();
+    batch.put(b"k2", b"v2");
+    batch.set_save_point();
+    batch.delete(b"k1");
+    let nested_len = batch.len();
+    batch.set_save_point();
+    batch.put(b"k3", b"v3");
+
+    // the innermost save point is dropped, keeping its updates
+    batch.pop_save_point().unwrap();
+    assert_eq!(batch.len(), nested_len + 1);
+
+    batch.rollback_to_save_point().unwrap();
+    assert_eq!(batch.len(), 2);
+    batch.rollback_to_save_point().unwrap();
+    assert_eq!(batch.len(), len);
+    assert_eq!(batch.size_in_bytes(), data.len());
+    assert_eq!(batch.data(), data.as_slice());
+    let err = batch.rollback_to_save_point().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    let path = DBPath::new("_rust_rocksdb_write_batch_save_points");
+    let db = DB::open_default(&path).unwrap();
+    db.write(batch).unwrap();
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    assert!(db.get(b"k2").unwrap().is_none());
+}
+
 #[test]
 fn test_write_batch_with_serialized_data() {
     struct Iterator {
@@ -148,3 +190,149 @@ fn test_write_batch_put_log_data() {
 
     assert!(called);
 }
+
+#[test]
+fn test_write_batch_single_delete() {
+    #[derive(Default)]
+    struct Recorder {
+        deleted: Vec<Vec<u8>>,
+        single_deleted: Vec<Vec<u8>>,
+    }
+
+    impl WriteBatchIterator for Recorder {
+        fn put(&mut self, _: &[u8], _: &[u8]) {}
+
+        fn delete(&mut self, key: &[u8]) {
+            self.deleted.push(key.to_vec());
+        }
+
+        fn single_delete(&mut self, key: &[u8]) {
+            self.single_deleted.push(key.to_vec());
+        }
+    }
+
+    #[derive(Default)]
+    struct RecorderCf {
+        deleted: Vec<(u32, Vec<u8>)>,
+        single_deleted: Vec<(u32, Vec<u8>)>,
+    }
+
+    impl WriteBatchIteratorCf for RecorderCf {
+        fn put_cf(&mut self, _: u32, _: &[u8], _: &[u8]) {}
+
+        fn delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+            self.deleted.push((cf_id, key.to_vec()));
+        }
+
+        fn merge_cf(&mut self, _: u32, _: &[u8], _: &[u8]) {}
+
+        fn single_delete_cf(&mut self, cf_id: u32, key: &[u8]) {
+            self.single_deleted.push((cf_id, key.to_vec()));
+        }
+    }
+
+    let path = DBPath::new("writebatch_single_delete");
+    let db = DB::open_default(&path).unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.put(b"k2", b"v2").unwrap();
+
+    let mut batch = WriteBatch::default();
+    batch.single_delete(b"k1");
+    batch.delete(b"k2");
+    assert_eq!(batch.len(), 2);
+
+    let mut recorder = Recorder::default();
+    batch.iterate(&mut recorder);
+    assert_eq!(recorder.deleted, vec![b"k2".to_vec()]);
+    assert_eq!(recorder.single_deleted, vec![b"k1".to_vec()]);
+
+    let mut recorder = RecorderCf::default();
+    batch.iterate_cf(&mut recorder);
+    assert_eq!(recorder.deleted, vec![(0, b"k2".to_vec())]);
+    assert_eq!(recorder.single_deleted, vec![(0, b"k1".to_vec())]);
+
+    db.write(batch).unwrap();
+    assert!(db.get(b"k1").unwrap().is_none());
+    assert!(db.get(b"k2").unwrap().is_none());
+}
+
+#[test]
+fn test_write_batch_ops() {
+    let path = DBPath::new("writebatch_ops");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf1"]).unwrap();
+    let cf1 = db.cf_handle("cf1").unwrap();
+
+    let columns: WideColumns = [("b", "2"), ("a", "1")].into_iter().collect();
+    let mut batch = WriteBatch::default();
+    batch.put(b"k1", b"v1");
+    batch.merge_cf(&cf1, b"k2", b"v2");
+    batch.delete(b"k3");
+    batch.single_delete_cf(&cf1, b"k4");
+    batch.delete_range_cf(&cf1, b"k5", b"k6");
+    batch.put_entity_cf(&cf1, b"k7", &columns).unwrap();
+    batch.put_log_data(b"blob");
+
+    let expected = vec![
+        WriteBatchOp::Put {
+            cf_id: 0,
+            key: b"k1".to_vec(),
+            value: b"v1".to_vec(),
+        },
+        WriteBatchOp::Merge {
+            cf_id: 1,
+            key: b"k2".to_vec(),
+            value: b"v2".to_vec(),
+        },
+        WriteBatchOp::Delete {
+            cf_id: 0,
+            key: b"k3".to_vec(),
+        },
+        WriteBatchOp::SingleDelete {
+            cf_id: 1,
+            key: b"k4".to_vec(),
+        },
+        WriteBatchOp::DeleteRange {
+            cf_id: 1,
+            begin_key: b"k5".to_vec(),
+            end_key: b"k6".to_vec(),
+        },
+        WriteBatchOp::PutEntity {
+            cf_id: 1,
+            key: b"k7".to_vec(),
+            columns: [("a", "1"), ("b", "2")].into_iter().collect(),
+        },
+        WriteBatchOp::LogData(b"blob".to_vec()),
+    ];
+    assert_eq!(batch.ops().unwrap().collect::<Vec<_>>(), expected);
+
+    let decoded = WriteBatch::from_data(batch.data());
+    assert_eq!(decoded.ops().unwrap().collect::<Vec<_>>(), expected);
+
+    let mut batch = WriteBatch::default();
+    batch.put(b"k1", b"v1");
+    batch.put_log_data(b"blob");
+    batch.delete_range(b"k2", b"k3");
+    db.write(batch).unwrap();
+    let (_, logged) = db.get_updates_since(0).unwrap().next().unwrap().unwrap();
+    assert_eq!(
+        logged.ops().unwrap().collect::<Vec<_>>(),
+        vec![
+            WriteBatchOp::Put {
+                cf_id: 0,
+                key: b"k1".to_vec(),
+                value: b"v1".to_vec(),
+            },
+            WriteBatchOp::LogData(b"blob".to_vec()),
+            WriteBatchOp::DeleteRange {
+                cf_id: 0,
+                begin_key: b"k2".to_vec(),
+                end_key: b"k3".to_vec(),
+            },
+        ]
+    );
+
+    assert!(WriteBatch::from_data(b"malformed").ops().is_err());
+}
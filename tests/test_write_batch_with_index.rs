@@ -0,0 +1,158 @@
+mod util;
+
+use pretty_assertions::assert_eq;
+
+use rocksdb::{
+    merge_operator::MergeOperands, ErrorKind, Options, ReadOptions, WriteBatchWithIndex, DB,
+};
+use util::DBPath;
+
+fn concat_merge(
+    _key: &[u8],
+    existing_val: Option<&[u8]>,
+    operands: &MergeOperands,
+) -> Option<Vec<u8>> {
+    let mut result = existing_val.map(<[u8]>::to_vec).unwrap_or_default();
+    for op in operands {
+        result.extend_from_slice(op);
+    }
+    Some(result)
+}
+
+#[test]
+fn get_from_batch() {
+    let opts = Options::default();
+    let mut batch = WriteBatchWithIndex::default();
+    assert!(batch.is_empty());
+    batch.put(b"k1", b"v1");
+    batch.put(b"k2", b"v2");
+    batch.put(b"k1", b"v3");
+    batch.delete(b"k2");
+    assert_eq!(batch.len(), 4);
+    assert_eq!(batch.size_in_bytes(), batch.data().len());
+
+    assert_eq!(batch.get_from_batch(b"k1", &opts).unwrap().unwrap(), b"v3");
+    assert!(batch.get_from_batch(b"k2", &opts).unwrap().is_none());
+    assert!(batch.get_from_batch(b"k3", &opts).unwrap().is_none());
+
+    // the batch alone cannot resolve a merge on top of the database
+    batch.merge(b"k4", b"a");
+    let err = batch.get_from_batch(b"k4", &opts).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MergeInProgress);
+
+    batch.clear();
+    assert!(batch.is_empty());
+    assert!(batch.get_from_batch(b"k1", &opts).unwrap().is_none());
+}
+
+#[test]
+fn get_from_batch_and_db() {
+    let path = DBPath::new("_rust_rocksdb_wbwi_get_from_batch_and_db");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    opts.set_merge_operator_associative("concat", concat_merge);
+    let db = DB::open_cf(&opts, &path, ["cf"]).unwrap();
+    let cf = db.cf_handle("cf").unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.put_cf(&cf, b"k1", b"cf1").unwrap();
+
+    let mut batch = WriteBatchWithIndex::default();
+    batch.delete(b"k1");
+    batch.merge(b"k2", b"+");
+    batch.put(b"k3", b"v3");
+    batch.put_cf(&cf, b"k2", b"cf2");
+
+    let readopts = ReadOptions::default();
+    assert!(batch
+        .get_from_batch_and_db(&db, b"k1", &readopts)
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        batch
+            .get_from_batch_and_db(&db, b"k2", &readopts)
+            .unwrap()
+            .unwrap(),
+        b"v2+"
+    );
+    assert_eq!(
+        batch
+            .get_from_batch_and_db(&db, b"k3", &readopts)
+            .unwrap()
+            .unwrap(),
+        b"v3"
+    );
+    assert_eq!(
+        batch
+            .get_from_batch_and_db_cf(&db, &cf, b"k1", &readopts)
+            .unwrap()
+            .unwrap(),
+        b"cf1"
+    );
+    assert_eq!(
+        batch.get_from_batch_cf(&cf, b"k2", &opts).unwrap().unwrap(),
+        b"cf2"
+    );
+
+    // nothing is visible before the batch is written
+    assert_eq!(db.get(b"k1").unwrap().unwrap(), b"v1");
+    assert!(db.get(b"k3").unwrap().is_none());
+
+    db.write(batch).unwrap();
+    assert!(db.get(b"k1").unwrap().is_none());
+    assert_eq!(db.get(b"k2").unwrap().unwrap(), b"v2+");
+    assert_eq!(db.get(b"k3").unwrap().unwrap(), b"v3");
+    assert_eq!(db.get_cf(&cf, b"k2").unwrap().unwrap(), b"cf2");
+}
+
+#[test]
+fn iterator_with_base() {
+    let path = DBPath::new("_rust_rocksdb_wbwi_iterator_with_base");
+    let mut opts = Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = DB::open_cf(&opts, &path, ["cf"]).unwrap();
+    let cf = db.cf_handle("cf").unwrap();
+    db.put(b"k1", b"v1").unwrap();
+    db.put(b"k2", b"v2").unwrap();
+    db.put(b"k4", b"v4").unwrap();
+    db.put_cf(&cf, b"k1", b"cf1").unwrap();
+
+    let mut batch = WriteBatchWithIndex::default();
+    batch.delete(b"k2");
+    batch.put(b"k3", b"v3");
+    batch.put(b"k4", b"new");
+    batch.put_cf(&cf, b"k0", b"cf0");
+
+    let mut iter = batch.iterator_with_base(db.raw_iterator());
+    let mut items = Vec::new();
+    iter.seek_to_first();
+    while let Some((key, value)) = iter.item() {
+        items.push((key.to_vec(), value.to_vec()));
+        iter.next();
+    }
+    iter.status().unwrap();
+    assert_eq!(
+        items,
+        [
+            (b"k1".to_vec(), b"v1".to_vec()),
+            (b"k3".to_vec(), b"v3".to_vec()),
+            (b"k4".to_vec(), b"new".to_vec()),
+        ]
+    );
+
+    iter.seek_to_last();
+    assert_eq!(iter.key(), Some(&b"k4"[..]));
+    iter.prev();
+    assert_eq!(iter.key(), Some(&b"k3"[..]));
+    drop(iter);
+
+    let mut iter = batch.iterator_with_base_cf(db.raw_iterator_cf(&cf), &cf);
+    iter.seek_to_first();
+    assert_eq!(iter.item(), Some((&b"k0"[..], &b"cf0"[..])));
+    iter.next();
+    assert_eq!(iter.item(), Some((&b"k1"[..], &b"cf1"[..])));
+    iter.next();
+    assert!(!iter.valid());
+}